@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+
+DROP TABLE webhook_deliveries;
+DROP TYPE WEBHOOK_DELIVERY_STATUS;
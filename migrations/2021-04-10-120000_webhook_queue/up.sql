@@ -0,0 +1,24 @@
+-- Your SQL goes here
+
+CREATE TYPE WEBHOOK_DELIVERY_STATUS AS ENUM ('HELD', 'PENDING', 'DELIVERED', 'FAILED');
+
+-- Outbox of webhook messages. Rows are inserted in the same transaction as the change they notify about, and are picked
+-- up by a background task that takes care of the actual delivery.
+--
+-- 'HELD' messages are not yet eligible for delivery (for instance because the video of a new submission still has to be
+-- verified), 'PENDING' messages will be delivered once 'next_attempt' has passed.
+CREATE TABLE webhook_deliveries (
+    id SERIAL PRIMARY KEY,
+    record INTEGER NULL, -- no foreign key, the delivery log should survive the deletion of the record
+    payload TEXT NOT NULL,
+    status_ WEBHOOK_DELIVERY_STATUS NOT NULL DEFAULT 'PENDING',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT (NOW() AT TIME ZONE 'utc'),
+    next_attempt TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT (NOW() AT TIME ZONE 'utc'),
+    last_attempt TIMESTAMP WITHOUT TIME ZONE NULL,
+    last_response SMALLINT NULL,
+    last_error TEXT NULL
+);
+
+CREATE INDEX webhook_deliveries_due ON webhook_deliveries(next_attempt) WHERE status_ = 'PENDING';
+CREATE INDEX webhook_deliveries_record ON webhook_deliveries(record);
@@ -0,0 +1,9 @@
+-- This file should undo anything in `up.sql`
+
+ALTER TABLE webhook_deliveries
+    DROP COLUMN webhook,
+    DROP COLUMN event;
+
+DROP TABLE webhook_subscriptions;
+DROP TABLE webhooks;
+DROP TYPE WEBHOOK_EVENT;
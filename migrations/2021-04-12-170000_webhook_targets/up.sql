@@ -0,0 +1,29 @@
+-- Your SQL goes here
+
+CREATE TYPE WEBHOOK_EVENT AS ENUM (
+    'RECORD_SUBMITTED', 'RECORD_APPROVED', 'RECORD_REJECTED', 'RECORD_MODIFIED', 'RECORD_DELETED',
+    'DEMON_ADDED', 'DEMON_MOVED', 'DEMON_MODIFIED',
+    'PLAYER_BANNED', 'PLAYER_UNBANNED', 'PLAYER_MODIFIED',
+    'SUBMITTER_BANNED', 'SUBMITTER_UNBANNED'
+);
+
+CREATE TABLE webhooks (
+    id SERIAL PRIMARY KEY,
+    name TEXT NOT NULL,
+    url TEXT NOT NULL
+);
+
+CREATE TABLE webhook_subscriptions (
+    webhook INTEGER NOT NULL REFERENCES webhooks(id) ON DELETE CASCADE,
+    event WEBHOOK_EVENT NOT NULL,
+    PRIMARY KEY (webhook, event)
+);
+
+-- Messages queued before this migration were addressed to the webhook configured via the DISCORD_WEBHOOK environment
+-- variable. They keep a NULL webhook (and are not delivered) until that webhook is recreated on startup, at which point
+-- they are reattached to it. All of them were about records, but which event exactly isn't known anymore.
+ALTER TABLE webhook_deliveries
+    ADD COLUMN webhook INTEGER NULL REFERENCES webhooks(id) ON DELETE CASCADE,
+    ADD COLUMN event WEBHOOK_EVENT NOT NULL DEFAULT 'RECORD_MODIFIED';
+
+ALTER TABLE webhook_deliveries ALTER COLUMN event DROP DEFAULT;
@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+
+ALTER TABLE webhooks DROP COLUMN format;
+
+DROP TYPE WEBHOOK_FORMAT;
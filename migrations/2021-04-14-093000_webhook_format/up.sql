@@ -0,0 +1,5 @@
+-- Your SQL goes here
+
+CREATE TYPE WEBHOOK_FORMAT AS ENUM ('DISCORD', 'JSON');
+
+ALTER TABLE webhooks ADD COLUMN format WEBHOOK_FORMAT NOT NULL DEFAULT 'DISCORD';
@@ -0,0 +1,16 @@
+-- Your SQL goes here
+
+CREATE TYPE VIDEO_STATUS AS ENUM ('ALIVE', 'DEAD', 'UNREACHABLE');
+
+-- Result of the last liveness check of each video used by an approved record or as a demon's verification video. Keyed
+-- by the video URL, so that records and demons sharing a video share its check result
+CREATE TABLE video_checks (
+    video VARCHAR(200) PRIMARY KEY,
+    status_ VIDEO_STATUS NOT NULL,
+    last_response SMALLINT NULL, -- the HTTP status code the video host responded with
+    last_error TEXT NULL,
+    checked_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT (NOW() AT TIME ZONE 'utc'),
+    dead_since TIMESTAMP WITHOUT TIME ZONE NULL
+);
+
+CREATE INDEX video_checks_dead ON video_checks(video) WHERE status_ = 'DEAD';
@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+
+DROP TABLE record_video_probes;
+DROP TABLE record_video_verifications;
+DROP TYPE VIDEO_VERIFICATION_STATUS;
@@ -0,0 +1,27 @@
+-- Your SQL goes here
+
+CREATE TYPE VIDEO_VERIFICATION_STATUS AS ENUM ('UNVERIFIED', 'FLAGGED');
+
+-- Submissions whose video has not (yet) been verified to exist. Submissions are verified by probing their video until
+-- either the video host responds successfully (at which point the row is deleted), the video host repeatedly responds
+-- with a definitive client error (at which point the submission is deleted), or the verification window runs out (at
+-- which point the submission is flagged for manual review by list helpers)
+CREATE TABLE record_video_verifications (
+    record INTEGER PRIMARY KEY REFERENCES records(id) ON DELETE CASCADE,
+    status_ VIDEO_VERIFICATION_STATUS NOT NULL DEFAULT 'UNVERIFIED',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    definitive_failures INTEGER NOT NULL DEFAULT 0,
+    started_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT (NOW() AT TIME ZONE 'utc'),
+    next_attempt TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT (NOW() AT TIME ZONE 'utc')
+);
+
+CREATE INDEX record_video_verifications_due ON record_video_verifications(next_attempt) WHERE status_ = 'UNVERIFIED';
+
+-- Audit log entries for each time a submission's video was probed during verification
+CREATE TABLE record_video_probes (
+    id INTEGER NOT NULL, -- REFERENCES records(id)
+
+    video VARCHAR(200) NOT NULL,
+    response SMALLINT NULL, -- the HTTP status code the video host responded with, if any
+    error TEXT NULL
+) INHERITS (audit_log2);
@@ -0,0 +1,11 @@
+-- Your SQL goes here
+
+-- The list helper currently reviewing a record. Records that aren't being reviewed by anyone have no entry here.
+-- Assignments are automatically released after some time (see config::record_assignment_timeout)
+CREATE TABLE record_assignments (
+    record INTEGER PRIMARY KEY REFERENCES records(id) ON DELETE CASCADE,
+    member INTEGER NOT NULL REFERENCES members(member_id) ON DELETE CASCADE,
+    assigned_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT (NOW() AT TIME ZONE 'utc')
+);
+
+CREATE INDEX record_assignments_member ON record_assignments(member);
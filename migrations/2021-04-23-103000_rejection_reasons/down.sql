@@ -0,0 +1,8 @@
+-- This file should undo anything in `up.sql`
+
+DROP TABLE record_rejection_reason_changes;
+DROP FUNCTION audit_record_rejection_reason_change() CASCADE;
+DROP FUNCTION audit_record_rejection_reason_removal() CASCADE;
+
+DROP TABLE record_rejection_reasons;
+DROP TABLE rejection_reasons;
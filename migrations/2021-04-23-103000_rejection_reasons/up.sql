@@ -0,0 +1,52 @@
+-- Your SQL goes here
+
+-- Catalogue of reasons for which records get rejected, managed by list administrators
+CREATE TABLE rejection_reasons (
+    id SERIAL PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    description TEXT NULL
+);
+
+-- The reason each rejected record was rejected for. Not a column on the records table, since we do not want changes to
+-- the reason to generate record_modifications audit log entries (they are logged in record_rejection_reason_changes
+-- instead). Entries are removed when the record stops being rejected, or when the reason is removed from the catalogue
+CREATE TABLE record_rejection_reasons (
+    record INTEGER PRIMARY KEY REFERENCES records(id) ON DELETE CASCADE,
+    reason INTEGER NOT NULL REFERENCES rejection_reasons(id) ON DELETE CASCADE
+);
+
+CREATE INDEX record_rejection_reasons_reason ON record_rejection_reasons(reason);
+
+-- Every change to a record's rejection reason. The reason is NULL if it was removed from the record
+CREATE TABLE record_rejection_reason_changes (
+    id INTEGER NOT NULL, -- REFERENCES records(id)
+
+    reason INTEGER NULL -- REFERENCES rejection_reasons(id), but reasons can be removed from the catalogue
+) INHERITS (audit_log2);
+
+CREATE FUNCTION audit_record_rejection_reason_change() RETURNS trigger AS $record_rejection_reason_change_trigger$
+    BEGIN
+        INSERT INTO record_rejection_reason_changes (userid, id, reason)
+            (SELECT id, NEW.record, NEW.reason FROM active_user LIMIT 1);
+        RETURN NULL;
+    END;
+$record_rejection_reason_change_trigger$ LANGUAGE plpgsql;
+
+CREATE FUNCTION audit_record_rejection_reason_removal() RETURNS trigger AS $record_rejection_reason_removal_trigger$
+    BEGIN
+        -- If the record itself was deleted, the deletion is already logged in record_deletions
+        IF EXISTS (SELECT 1 FROM records WHERE id = OLD.record) THEN
+            INSERT INTO record_rejection_reason_changes (userid, id, reason)
+                (SELECT id, OLD.record, NULL FROM active_user LIMIT 1);
+        END IF;
+
+        RETURN NULL;
+    END;
+$record_rejection_reason_removal_trigger$ LANGUAGE plpgsql;
+
+CREATE TRIGGER record_rejection_reason_addition_trigger AFTER INSERT ON record_rejection_reasons FOR EACH ROW
+    EXECUTE PROCEDURE audit_record_rejection_reason_change();
+CREATE TRIGGER record_rejection_reason_change_trigger AFTER UPDATE ON record_rejection_reasons FOR EACH ROW
+    WHEN (OLD.reason IS DISTINCT FROM NEW.reason) EXECUTE PROCEDURE audit_record_rejection_reason_change();
+CREATE TRIGGER record_rejection_reason_removal_trigger AFTER DELETE ON record_rejection_reasons FOR EACH ROW
+    EXECUTE PROCEDURE audit_record_rejection_reason_removal();
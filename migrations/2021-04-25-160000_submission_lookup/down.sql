@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE record_notes DROP COLUMN is_public;
+
+DROP TABLE record_lookup_tokens;
@@ -0,0 +1,13 @@
+-- Your SQL goes here
+
+-- Random tokens handed out to submitters, with which they can look up the status of their submission without having
+-- to authenticate. Generated by the server. Tokens are kept when their record is deleted, so that the submitter's link
+-- works again should the record be restored (which happens with its original ID)
+CREATE TABLE record_lookup_tokens (
+    record INTEGER PRIMARY KEY, -- REFERENCES records(id)
+    token TEXT NOT NULL UNIQUE
+);
+
+-- Notes are internal to the list team unless explicitly made public. Public notes are shown on the submission status
+-- page
+ALTER TABLE record_notes ADD COLUMN is_public BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP FUNCTION prune_video_key() CASCADE;
+DROP TABLE video_keys;
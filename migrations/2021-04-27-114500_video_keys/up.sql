@@ -0,0 +1,34 @@
+-- Your SQL goes here
+
+-- The canonical (host, video id) key of every video used by a record or as a demon's verification video (see
+-- video::key), so that different links to the same video can be recognized as duplicates. Keys are kept in their own
+-- table, instead of as a column on `records` and `demons`, since computing them for existing videos would otherwise
+-- generate audit log entries for every single record and demon. Keys for videos that predate this table are computed
+-- at startup.
+CREATE TABLE video_keys (
+    video TEXT PRIMARY KEY,
+    key TEXT NOT NULL
+);
+
+CREATE INDEX video_keys_key ON video_keys(key);
+
+-- Keys of videos that are no longer used by any record or demon are removed, regardless of whether the video was
+-- changed, removed, or the record/demon using it deleted (which also happens in bulk, e.g. when a demon's requirement
+-- is raised)
+CREATE FUNCTION prune_video_key() RETURNS trigger AS $prune_video_key_trigger$
+    BEGIN
+        IF OLD.video IS NOT NULL THEN
+            DELETE FROM video_keys
+            WHERE video = OLD.video::TEXT
+              AND NOT EXISTS (SELECT 1 FROM records WHERE records.video = OLD.video)
+              AND NOT EXISTS (SELECT 1 FROM demons WHERE demons.video = OLD.video);
+        END IF;
+
+        RETURN NULL;
+    END;
+$prune_video_key_trigger$ LANGUAGE plpgsql;
+
+CREATE TRIGGER record_video_key_trigger AFTER UPDATE OF video OR DELETE ON records FOR EACH ROW
+    EXECUTE PROCEDURE prune_video_key();
+CREATE TRIGGER demon_video_key_trigger AFTER UPDATE OF video OR DELETE ON demons FOR EACH ROW
+    EXECUTE PROCEDURE prune_video_key();
@@ -0,0 +1,16 @@
+-- This file should undo anything in `up.sql`
+
+CREATE OR REPLACE FUNCTION audit_record_deletion() RETURNS trigger AS $record_deletion_trigger$
+    BEGIN
+        INSERT INTO record_modifications (userid, id, progress, video, status_, player, demon)
+            (SELECT id, OLD.id, OLD.progress, OLD.video, OLD.status_, OLD.player, OLD.demon
+            FROM active_user LIMIT 1);
+
+        INSERT INTO record_deletions (userid, id)
+            (SELECT id, OLD.id FROM active_user LIMIT 1);
+
+        RETURN NULL;
+    END;
+$record_deletion_trigger$ LANGUAGE plpgsql;
+
+ALTER TABLE record_deletions DROP COLUMN submitter;
@@ -0,0 +1,18 @@
+-- Your SQL goes here
+
+-- The submitter is the only column of a record not contained in the record_modifications entry created upon deletion, but
+-- it is required for restoring a deleted record
+ALTER TABLE record_deletions ADD COLUMN submitter INTEGER NULL;
+
+CREATE OR REPLACE FUNCTION audit_record_deletion() RETURNS trigger AS $record_deletion_trigger$
+    BEGIN
+        INSERT INTO record_modifications (userid, id, progress, video, status_, player, demon)
+            (SELECT id, OLD.id, OLD.progress, OLD.video, OLD.status_, OLD.player, OLD.demon
+            FROM active_user LIMIT 1);
+
+        INSERT INTO record_deletions (userid, id, submitter)
+            (SELECT id, OLD.id, OLD.submitter FROM active_user LIMIT 1);
+
+        RETURN NULL;
+    END;
+$record_deletion_trigger$ LANGUAGE plpgsql;
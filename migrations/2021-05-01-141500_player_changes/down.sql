@@ -0,0 +1,7 @@
+-- This file should undo anything in `up.sql`
+
+DROP TRIGGER player_nationality_modification_trigger ON players;
+DROP FUNCTION audit_player_nationality_modification();
+
+DROP TABLE player_nationality_modifications;
+DROP TABLE player_merges;
@@ -0,0 +1,34 @@
+-- Your SQL goes here
+
+-- Merges of two players. The merged player is deleted afterwards, which is logged as usual. Inserted by the application,
+-- since a merge consists of many individual updates.
+CREATE TABLE player_merges (
+    id INTEGER NOT NULL, -- the player that was merged into, REFERENCES players(id)
+    merged_id INTEGER NOT NULL,
+    merged_name CITEXT NOT NULL,
+    records_moved INTEGER NOT NULL,
+    creators_moved INTEGER NOT NULL,
+    creators_deleted INTEGER NOT NULL,
+    verifications_moved INTEGER NOT NULL,
+    publications_moved INTEGER NOT NULL
+) INHERITS (audit_log2);
+
+-- player_modifications doesn't track the nationality. Unlike there, entries are only created if the nationality actually
+-- changed, so a NULL value means the player previously had no nationality set
+CREATE TABLE player_nationality_modifications (
+    id INTEGER NOT NULL, -- REFERENCES players(id)
+    nationality VARCHAR(2) NULL
+) INHERITS (audit_log2);
+
+CREATE FUNCTION audit_player_nationality_modification() RETURNS trigger AS $player_nationality_modification_trigger$
+    BEGIN
+        IF (OLD.nationality IS DISTINCT FROM NEW.nationality) THEN
+            INSERT INTO player_nationality_modifications (userid, id, nationality)
+                (SELECT id, NEW.id, OLD.nationality FROM active_user LIMIT 1);
+        END IF;
+
+        RETURN NEW;
+    END;
+$player_nationality_modification_trigger$ LANGUAGE plpgsql;
+
+CREATE TRIGGER player_nationality_modification_trigger AFTER UPDATE ON players FOR EACH ROW EXECUTE PROCEDURE audit_player_nationality_modification();
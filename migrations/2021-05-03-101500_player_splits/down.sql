@@ -0,0 +1,15 @@
+-- This file should undo anything in `up.sql`
+
+DROP TABLE player_splits;
+
+ALTER TABLE player_merges
+    DROP COLUMN merged_banned,
+    DROP COLUMN merged_link_banned,
+    DROP COLUMN merged_nationality,
+    DROP COLUMN target_name,
+    DROP COLUMN moved_records,
+    DROP COLUMN deleted_records,
+    DROP COLUMN moved_creators,
+    DROP COLUMN deleted_creators,
+    DROP COLUMN verified_demons,
+    DROP COLUMN published_demons;
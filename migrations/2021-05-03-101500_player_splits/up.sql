@@ -0,0 +1,29 @@
+-- Your SQL goes here
+
+-- Everything needed for undoing a merge. All columns are NULL for merges that happened before these were logged, which
+-- therefore cannot be undone.
+ALTER TABLE player_merges
+    ADD COLUMN merged_banned BOOLEAN NULL,
+    ADD COLUMN merged_link_banned BOOLEAN NULL,
+    ADD COLUMN merged_nationality VARCHAR(2) NULL,
+    -- The name of the player merged into before the merge. Renaming a player to the name of an existing player merges the
+    -- two, with the merged player's name taking over.
+    ADD COLUMN target_name CITEXT NULL,
+    -- The IDs of the merged player's records that still existed after the merge
+    ADD COLUMN moved_records INTEGER[] NULL,
+    -- The IDs of the records of either player that were deleted while merging their records (e.g. a rejected record
+    -- replacing the other player's records on the same demon). These can be restored via the record audit log.
+    ADD COLUMN deleted_records INTEGER[] NULL,
+    -- The IDs of the demons whose creator entries were moved over to the player merged into
+    ADD COLUMN moved_creators INTEGER[] NULL,
+    -- The IDs of the demons the player merged into already was a creator of, whose creator entries for the merged player
+    -- were deleted
+    ADD COLUMN deleted_creators INTEGER[] NULL,
+    ADD COLUMN verified_demons INTEGER[] NULL,
+    ADD COLUMN published_demons INTEGER[] NULL;
+
+CREATE TABLE player_splits (
+    id INTEGER NOT NULL, -- the player that was split, REFERENCES players(id)
+    merge INTEGER NOT NULL, -- the audit_id of the player_merges entry that was undone
+    restored_id INTEGER NOT NULL
+) INHERITS (audit_log2);
@@ -0,0 +1,13 @@
+-- Your SQL goes here
+
+-- Daily snapshots of the player ranking (as given by the players_with_score view), taken by a background task
+CREATE TABLE player_ranking_snapshots (
+    player INTEGER NOT NULL REFERENCES players(id) ON DELETE CASCADE,
+    day DATE NOT NULL,
+    rank BIGINT NOT NULL,
+    score DOUBLE PRECISION NOT NULL,
+    index BIGINT NOT NULL,
+    PRIMARY KEY (player, day)
+);
+
+CREATE INDEX player_ranking_snapshots_day ON player_ranking_snapshots (day);
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+
+DROP FUNCTION players_with_score_at(TIMESTAMP WITHOUT TIME ZONE);
+DROP FUNCTION records_at(TIMESTAMP WITHOUT TIME ZONE);
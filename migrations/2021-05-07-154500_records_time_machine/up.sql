@@ -0,0 +1,104 @@
+-- Your SQL goes here
+
+-- The state of all records at the given point in time, reconstructed from the audit log. Every modification logs the old
+-- values of all changed fields, so the value of a field at some point in time is the value logged by the first
+-- modification changing it afterwards, or its current value if it hasn't changed since. Deletions additionally log a
+-- modification containing the full state of the deleted record.
+--
+-- Note that changes from NULL are not logged, so videos added to records that previously had none show up too early
+CREATE FUNCTION records_at(TIMESTAMP WITHOUT TIME ZONE)
+RETURNS TABLE (
+    id INTEGER,
+    progress SMALLINT,
+    video VARCHAR(200),
+    status_ RECORD_STATUS,
+    player INTEGER,
+    demon INTEGER
+)
+AS $$
+    WITH candidates AS (
+        SELECT id FROM records
+        UNION
+        SELECT id FROM record_deletions WHERE time >= $1
+    ), events AS (
+        SELECT id, audit_id, time, TRUE AS addition FROM record_additions
+        UNION ALL
+        SELECT id, audit_id, time, FALSE FROM record_deletions
+    )
+    SELECT candidates.id,
+           COALESCE((SELECT m.progress FROM record_modifications m WHERE m.id = candidates.id AND m.time >= $1 AND m.progress IS NOT NULL ORDER BY m.audit_id LIMIT 1), records.progress),
+           COALESCE((SELECT m.video FROM record_modifications m WHERE m.id = candidates.id AND m.time >= $1 AND m.video IS NOT NULL ORDER BY m.audit_id LIMIT 1), records.video),
+           COALESCE((SELECT m.status_ FROM record_modifications m WHERE m.id = candidates.id AND m.time >= $1 AND m.status_ IS NOT NULL ORDER BY m.audit_id LIMIT 1), records.status_),
+           COALESCE((SELECT m.player FROM record_modifications m WHERE m.id = candidates.id AND m.time >= $1 AND m.player IS NOT NULL ORDER BY m.audit_id LIMIT 1), records.player),
+           COALESCE((SELECT m.demon FROM record_modifications m WHERE m.id = candidates.id AND m.time >= $1 AND m.demon IS NOT NULL ORDER BY m.audit_id LIMIT 1), records.demon)
+    FROM candidates
+    LEFT OUTER JOIN records ON records.id = candidates.id
+    -- A record existed at the given time if the last addition or deletion before that time was an addition. If there
+    -- is none, it existed if it was deleted afterwards (meaning it was added before the audit log existed), or if it
+    -- still exists and was never deleted.
+    WHERE COALESCE(
+        (SELECT addition FROM events WHERE events.id = candidates.id AND time < $1 ORDER BY audit_id DESC LIMIT 1),
+        (SELECT NOT addition FROM events WHERE events.id = candidates.id AND time >= $1 ORDER BY audit_id LIMIT 1),
+        records.id IS NOT NULL
+    )
+$$
+LANGUAGE SQL
+STABLE;
+
+-- The players_with_score view, evaluated at the given point in time. Uses the records and demon positions at that time,
+-- but the current creators, verifiers, publishers, nationalities and bans.
+CREATE FUNCTION players_with_score_at(TIMESTAMP WITHOUT TIME ZONE)
+RETURNS TABLE (
+    id INTEGER,
+    name CITEXT,
+    rank BIGINT,
+    score DOUBLE PRECISION,
+    index BIGINT,
+    iso_country_code VARCHAR(2),
+    nation CITEXT
+)
+AS $$
+    SELECT players.id,
+           players.name,
+           RANK() OVER (ORDER BY scores.total_score DESC) AS rank,
+           CASE WHEN scores.total_score IS NULL THEN 0.0::FLOAT ELSE scores.total_score END AS score,
+           ROW_NUMBER() OVER (ORDER BY scores.total_score DESC) AS index,
+           nationalities.iso_country_code,
+           nationalities.nation
+    FROM (
+        SELECT pseudo_records.player,
+               SUM(record_score(pseudo_records.progress::FLOAT, pseudo_records.position::FLOAT, 100::FLOAT, pseudo_records.requirement)) AS total_score
+        FROM (
+            SELECT records.player,
+                   records.progress,
+                   demons.position_ AS position,
+                   demons.requirement
+            FROM records_at($1) AS records
+            INNER JOIN list_at($1) AS demons ON demons.id = records.demon
+            WHERE demons.position_ <= 100
+              AND records.status_ = 'APPROVED'
+            UNION SELECT demons.verifier AS player,
+                   CASE WHEN demons.position_ > 100 THEN 0.0::FLOAT ELSE 100.0::FLOAT END AS progress,
+                   demons.position_,
+                   100.0::FLOAT
+            FROM list_at($1) AS demons
+            UNION SELECT demons.publisher AS player,
+                   0.0::FLOAT AS progress,
+                   demons.position_,
+                   100.0::FLOAT
+            FROM list_at($1) AS demons
+            UNION SELECT creators.creator AS player,
+                   0.0::FLOAT AS progress,
+                   1.0::FLOAT AS position, -- see players_with_score
+                   100.0::FLOAT
+            FROM creators
+            INNER JOIN list_at($1) AS demons ON demons.id = creators.demon
+        ) AS pseudo_records
+        GROUP BY pseudo_records.player
+    ) scores
+    INNER JOIN players ON scores.player = players.id
+    LEFT OUTER JOIN nationalities ON players.nationality = nationalities.iso_country_code
+    WHERE NOT players.banned
+$$
+LANGUAGE SQL
+STABLE;
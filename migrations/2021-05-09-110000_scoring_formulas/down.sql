@@ -0,0 +1,113 @@
+-- This file should undo anything in `up.sql`
+
+CREATE OR REPLACE VIEW players_with_score AS
+SELECT players.id,
+	players.name,
+	RANK() OVER(
+													ORDER BY scores.total_score DESC) AS rank,
+	CASE
+					WHEN scores.total_score IS NULL THEN 0.0::FLOAT
+					ELSE scores.total_score
+	END AS score,
+	ROW_NUMBER() OVER(
+																			ORDER BY scores.total_score DESC) AS index,
+	nationalities.iso_country_code,
+	nationalities.nation
+FROM
+		( SELECT pseudo_records.player,
+				SUM(record_score(pseudo_records.progress::FLOAT, pseudo_records.position::FLOAT, 100::FLOAT, pseudo_records.requirement)) as total_score
+			FROM
+					( SELECT player,
+							progress,
+							position,
+							requirement
+						FROM records
+						INNER JOIN demons ON demons.id = demon
+						WHERE demons.position <= 100
+								AND status_ = 'APPROVED'
+						UNION SELECT verifier as player,
+							CASE
+											WHEN demons.position > 100 THEN 0.0::FLOAT
+											ELSE 100.0::FLOAT
+							END as progress,
+							position,
+							100.0::FLOAT
+						FROM demons
+						UNION SELECT publisher as player,
+							0.0::FLOAT as progress,
+							position,
+							100.0::FLOAT
+						FROM demons
+						UNION SELECT creator as player,
+							0.0::FLOAT as progress,
+							1.0::FLOAT as position, -- yeah.
+							100.0::FLOAT
+						FROM creators ) AS pseudo_records
+			GROUP BY player ) scores
+INNER JOIN players ON scores.player = players.id
+LEFT OUTER JOIN nationalities ON players.nationality = nationalities.iso_country_code
+WHERE NOT players.banned;
+
+-- The players_with_score view, evaluated at the given point in time. Uses the records and demon positions at that time,
+-- but the current creators, verifiers, publishers, nationalities and bans.
+CREATE OR REPLACE FUNCTION players_with_score_at(TIMESTAMP WITHOUT TIME ZONE)
+RETURNS TABLE (
+    id INTEGER,
+    name CITEXT,
+    rank BIGINT,
+    score DOUBLE PRECISION,
+    index BIGINT,
+    iso_country_code VARCHAR(2),
+    nation CITEXT
+)
+AS $$
+    SELECT players.id,
+           players.name,
+           RANK() OVER (ORDER BY scores.total_score DESC) AS rank,
+           CASE WHEN scores.total_score IS NULL THEN 0.0::FLOAT ELSE scores.total_score END AS score,
+           ROW_NUMBER() OVER (ORDER BY scores.total_score DESC) AS index,
+           nationalities.iso_country_code,
+           nationalities.nation
+    FROM (
+        SELECT pseudo_records.player,
+               SUM(record_score(pseudo_records.progress::FLOAT, pseudo_records.position::FLOAT, 100::FLOAT, pseudo_records.requirement)) AS total_score
+        FROM (
+            SELECT records.player,
+                   records.progress,
+                   demons.position_ AS position,
+                   demons.requirement
+            FROM records_at($1) AS records
+            INNER JOIN list_at($1) AS demons ON demons.id = records.demon
+            WHERE demons.position_ <= 100
+              AND records.status_ = 'APPROVED'
+            UNION SELECT demons.verifier AS player,
+                   CASE WHEN demons.position_ > 100 THEN 0.0::FLOAT ELSE 100.0::FLOAT END AS progress,
+                   demons.position_,
+                   100.0::FLOAT
+            FROM list_at($1) AS demons
+            UNION SELECT demons.publisher AS player,
+                   0.0::FLOAT AS progress,
+                   demons.position_,
+                   100.0::FLOAT
+            FROM list_at($1) AS demons
+            UNION SELECT creators.creator AS player,
+                   0.0::FLOAT AS progress,
+                   1.0::FLOAT AS position, -- see players_with_score
+                   100.0::FLOAT
+            FROM creators
+            INNER JOIN list_at($1) AS demons ON demons.id = creators.demon
+        ) AS pseudo_records
+        GROUP BY pseudo_records.player
+    ) scores
+    INNER JOIN players ON scores.player = players.id
+    LEFT OUTER JOIN nationalities ON players.nationality = nationalities.iso_country_code
+    WHERE NOT players.banned
+$$
+LANGUAGE SQL
+STABLE;
+
+DROP FUNCTION players_with_score_under(INTEGER);
+DROP FUNCTION scoring_formula_at(TIMESTAMP WITHOUT TIME ZONE);
+DROP TABLE scoring_formula_activations;
+DROP FUNCTION formula_score(FLOAT, FLOAT, FLOAT, scoring_formulas);
+DROP TABLE scoring_formulas;
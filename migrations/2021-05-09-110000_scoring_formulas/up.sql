@@ -0,0 +1,188 @@
+-- Your SQL goes here
+
+-- Versions of the formula used to compute the score a record is worth. A formula cannot be changed after it was created,
+-- changing the scoring means creating a new version and activating it. Exactly one version is active at any time.
+--
+-- For a demon at position p, a 100% record is worth
+--
+--     max_score * EXP((1 - p) * LN(min_score_ratio) / (1 - curve_length))
+--
+-- points, meaning the demon at position 1 is worth max_score points and the one at position curve_length is worth
+-- min_score_ratio * max_score points. Records with progress between the demon's requirement and 100% are worth
+-- (partial_base + partial_weight * (progress - requirement) / (100 - requirement)) times that. Records on demons
+-- below position scored_positions are worth nothing.
+CREATE TABLE scoring_formulas (
+    id SERIAL PRIMARY KEY,
+    description TEXT NOT NULL,
+    max_score DOUBLE PRECISION NOT NULL CHECK (max_score > 0),
+    min_score_ratio DOUBLE PRECISION NOT NULL CHECK (min_score_ratio > 0 AND min_score_ratio <= 1),
+    curve_length SMALLINT NOT NULL CHECK (curve_length > 1),
+    scored_positions SMALLINT NOT NULL CHECK (scored_positions > 0),
+    partial_base DOUBLE PRECISION NOT NULL CHECK (partial_base >= 0),
+    partial_weight DOUBLE PRECISION NOT NULL CHECK (partial_weight >= 0),
+    active BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT (NOW() AT TIME ZONE 'utc'),
+    created_by INTEGER NULL REFERENCES members(member_id) ON DELETE SET NULL,
+    activated_at TIMESTAMP WITHOUT TIME ZONE NULL,
+    activated_by INTEGER NULL REFERENCES members(member_id) ON DELETE SET NULL
+);
+
+CREATE UNIQUE INDEX scoring_formulas_active ON scoring_formulas(active) WHERE active;
+
+-- The formula that has been hardcoded into record_score and Demon::score up until now
+INSERT INTO scoring_formulas (description, max_score, min_score_ratio, curve_length, scored_positions, partial_base, partial_weight, active, activated_at)
+VALUES ('Initial formula', 100.0, 1.0 / 30.0, 100, 100, 0.25, 0.25, TRUE, NOW() AT TIME ZONE 'utc');
+
+-- Every activation of a formula, so that past rankings can be computed with the formula active back then. Unlike
+-- scoring_formulas.activated_at, this isn't overwritten if a formula is activated again.
+CREATE TABLE scoring_formula_activations (
+    formula INTEGER NOT NULL REFERENCES scoring_formulas(id) ON DELETE CASCADE,
+    activated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT (NOW() AT TIME ZONE 'utc')
+);
+
+INSERT INTO scoring_formula_activations (formula, activated_at) SELECT id, activated_at FROM scoring_formulas;
+
+-- The formula active at the given point in time. Before the first activation, this is the initial formula
+CREATE FUNCTION scoring_formula_at(TIMESTAMP WITHOUT TIME ZONE) RETURNS INTEGER AS $$
+    SELECT formula FROM (
+        (SELECT formula, 0 AS priority, activated_at FROM scoring_formula_activations WHERE activated_at <= $1 ORDER BY activated_at DESC LIMIT 1)
+        UNION ALL
+        (SELECT formula, 1, activated_at FROM scoring_formula_activations ORDER BY activated_at LIMIT 1)
+    ) AS candidates ORDER BY priority LIMIT 1
+$$ LANGUAGE SQL STABLE;
+
+-- Keep in sync with ScoringFormula::score
+CREATE FUNCTION formula_score(progress FLOAT, position FLOAT, requirement FLOAT, formula scoring_formulas) RETURNS FLOAT AS $$
+    SELECT CASE
+        WHEN position > formula.scored_positions THEN
+            0.0
+        WHEN progress = 100 THEN
+            formula.max_score * EXP((1.0 - position) * LN(formula.min_score_ratio) / (1.0 - formula.curve_length))
+        WHEN progress < requirement THEN
+            0.0
+        ELSE
+            formula.max_score * EXP((1.0 - position) * LN(formula.min_score_ratio) / (1.0 - formula.curve_length))
+                * (formula.partial_base + formula.partial_weight * (progress - requirement) / (100 - requirement))
+    END
+$$ LANGUAGE SQL IMMUTABLE;
+
+-- The player ranking, as it would be if the given scoring formula were active
+CREATE FUNCTION players_with_score_under(INTEGER)
+RETURNS TABLE (
+    id INTEGER,
+    name CITEXT,
+    rank BIGINT,
+    score DOUBLE PRECISION,
+    index BIGINT,
+    iso_country_code VARCHAR(2),
+    nation CITEXT
+)
+AS $$
+    SELECT players.id,
+           players.name,
+           RANK() OVER (ORDER BY scores.total_score DESC) AS rank,
+           CASE WHEN scores.total_score IS NULL THEN 0.0::FLOAT ELSE scores.total_score END AS score,
+           ROW_NUMBER() OVER (ORDER BY scores.total_score DESC) AS index,
+           nationalities.iso_country_code,
+           nationalities.nation
+    FROM (
+        SELECT pseudo_records.player,
+               SUM(formula_score(pseudo_records.progress::FLOAT, pseudo_records.position::FLOAT, pseudo_records.requirement, formula)) AS total_score
+        FROM (
+            SELECT player,
+                   progress,
+                   position,
+                   requirement
+            FROM records
+            INNER JOIN demons ON demons.id = demon
+            WHERE demons.position <= (SELECT scored_positions FROM scoring_formulas WHERE scoring_formulas.id = $1)
+              AND status_ = 'APPROVED'
+            UNION SELECT verifier AS player,
+                   100.0::FLOAT AS progress,
+                   position,
+                   100.0::FLOAT
+            FROM demons
+            UNION SELECT publisher AS player,
+                   0.0::FLOAT AS progress,
+                   position,
+                   100.0::FLOAT
+            FROM demons
+            UNION SELECT creator AS player,
+                   0.0::FLOAT AS progress,
+                   1.0::FLOAT AS position, -- see players_with_score
+                   100.0::FLOAT
+            FROM creators
+        ) AS pseudo_records
+        CROSS JOIN scoring_formulas AS formula
+        WHERE formula.id = $1
+        GROUP BY pseudo_records.player
+    ) scores
+    INNER JOIN players ON scores.player = players.id
+    LEFT OUTER JOIN nationalities ON players.nationality = nationalities.iso_country_code
+    WHERE NOT players.banned
+$$
+LANGUAGE SQL
+STABLE;
+
+CREATE OR REPLACE VIEW players_with_score AS
+SELECT * FROM players_with_score_under((SELECT id FROM scoring_formulas WHERE active));
+
+-- Past rankings are computed using the formula that was active at the given point in time, so activating a new formula
+-- doesn't change them
+CREATE OR REPLACE FUNCTION players_with_score_at(TIMESTAMP WITHOUT TIME ZONE)
+RETURNS TABLE (
+    id INTEGER,
+    name CITEXT,
+    rank BIGINT,
+    score DOUBLE PRECISION,
+    index BIGINT,
+    iso_country_code VARCHAR(2),
+    nation CITEXT
+)
+AS $$
+    SELECT players.id,
+           players.name,
+           RANK() OVER (ORDER BY scores.total_score DESC) AS rank,
+           CASE WHEN scores.total_score IS NULL THEN 0.0::FLOAT ELSE scores.total_score END AS score,
+           ROW_NUMBER() OVER (ORDER BY scores.total_score DESC) AS index,
+           nationalities.iso_country_code,
+           nationalities.nation
+    FROM (
+        SELECT pseudo_records.player,
+               SUM(formula_score(pseudo_records.progress::FLOAT, pseudo_records.position::FLOAT, pseudo_records.requirement, formula)) AS total_score
+        FROM (
+            SELECT records.player,
+                   records.progress,
+                   demons.position_ AS position,
+                   demons.requirement
+            FROM records_at($1) AS records
+            INNER JOIN list_at($1) AS demons ON demons.id = records.demon
+            WHERE demons.position_ <= (SELECT scored_positions FROM scoring_formulas WHERE scoring_formulas.id = scoring_formula_at($1))
+              AND records.status_ = 'APPROVED'
+            UNION SELECT demons.verifier AS player,
+                   100.0::FLOAT AS progress,
+                   demons.position_,
+                   100.0::FLOAT
+            FROM list_at($1) AS demons
+            UNION SELECT demons.publisher AS player,
+                   0.0::FLOAT AS progress,
+                   demons.position_,
+                   100.0::FLOAT
+            FROM list_at($1) AS demons
+            UNION SELECT creators.creator AS player,
+                   0.0::FLOAT AS progress,
+                   1.0::FLOAT AS position, -- see players_with_score
+                   100.0::FLOAT
+            FROM creators
+            INNER JOIN list_at($1) AS demons ON demons.id = creators.demon
+        ) AS pseudo_records
+        CROSS JOIN scoring_formulas AS formula
+        WHERE formula.id = scoring_formula_at($1)
+        GROUP BY pseudo_records.player
+    ) scores
+    INNER JOIN players ON scores.player = players.id
+    LEFT OUTER JOIN nationalities ON players.nationality = nationalities.iso_country_code
+    WHERE NOT players.banned
+$$
+LANGUAGE SQL
+STABLE;
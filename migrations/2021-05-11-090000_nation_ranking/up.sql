@@ -0,0 +1,28 @@
+-- Your SQL goes here
+
+-- Ranking of all nations with at least one player with a non-zero score. A nation's score is the sum of its players'
+-- scores with diminishing returns: the nation's n-th best player contributes only 0.9^(n-1) of their score. This way,
+-- nations with many players still rank higher, but a single very good player counts for more than lots of players with
+-- only a handful of points each.
+CREATE VIEW nations_with_score AS
+SELECT RANK() OVER (ORDER BY scores.total_score DESC) AS rank,
+       scores.total_score AS score,
+       ROW_NUMBER() OVER (ORDER BY scores.total_score DESC) AS index,
+       nationalities.iso_country_code,
+       nationalities.nation,
+       scores.players
+FROM (
+    SELECT members.iso_country_code,
+           SUM(members.score * POWER(0.9, members.member_index - 1)) AS total_score,
+           COUNT(*) AS players
+    FROM (
+        SELECT iso_country_code,
+               score,
+               ROW_NUMBER() OVER (PARTITION BY iso_country_code ORDER BY score DESC) AS member_index
+        FROM players_with_score
+        WHERE iso_country_code IS NOT NULL
+          AND score > 0
+    ) AS members
+    GROUP BY members.iso_country_code
+) AS scores
+INNER JOIN nationalities ON nationalities.iso_country_code = scores.iso_country_code;
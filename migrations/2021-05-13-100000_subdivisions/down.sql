@@ -0,0 +1,9 @@
+-- This file should undo anything in `up.sql`
+
+DROP TABLE player_subdivision_modifications;
+DROP FUNCTION audit_player_subdivision_modification() CASCADE;
+
+ALTER TABLE player_merges DROP COLUMN merged_subdivision;
+ALTER TABLE players DROP COLUMN subdivision;
+
+DROP TABLE subdivisions;
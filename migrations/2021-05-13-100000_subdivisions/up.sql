@@ -0,0 +1,144 @@
+-- Your SQL goes here
+
+-- First-level ISO 3166-2 subdivisions (states, provinces, ...) of nations. iso_code is the part of the ISO 3166-2 code
+-- after the hyphen, so California (US-CA) has nation 'US' and iso_code 'CA'. Only nations with a community large
+-- enough for regional leaderboards are listed here, more can be added as needed.
+CREATE TABLE subdivisions (
+    nation VARCHAR(2) NOT NULL REFERENCES nationalities(iso_country_code) ON DELETE CASCADE,
+    iso_code VARCHAR(3) NOT NULL,
+    name CITEXT NOT NULL,
+    PRIMARY KEY (nation, iso_code),
+    UNIQUE (nation, name)
+);
+
+-- Since the foreign key covers both columns, a player's subdivision always belongs to their nationality. Note that this
+-- means the subdivision has to be reset before (or together with) the nationality is changed
+ALTER TABLE players
+    ADD COLUMN subdivision VARCHAR(3) NULL,
+    ADD FOREIGN KEY (nationality, subdivision) REFERENCES subdivisions(nation, iso_code);
+
+-- See player_nationality_modifications. Entries are only created if the subdivision actually changed, and contain the old
+-- subdivision together with the nation it belonged to. Both are NULL if the player previously had no subdivision set
+CREATE TABLE player_subdivision_modifications (
+    id INTEGER NOT NULL, -- REFERENCES players(id)
+    nation VARCHAR(2) NULL,
+    subdivision VARCHAR(3) NULL
+) INHERITS (audit_log2);
+
+CREATE FUNCTION audit_player_subdivision_modification() RETURNS trigger AS $player_subdivision_modification_trigger$
+    BEGIN
+        IF (OLD.subdivision IS DISTINCT FROM NEW.subdivision OR (OLD.subdivision IS NOT NULL AND OLD.nationality <> NEW.nationality)) THEN
+            INSERT INTO player_subdivision_modifications (userid, id, nation, subdivision)
+                (SELECT id, NEW.id, CASE WHEN OLD.subdivision IS NULL THEN NULL ELSE OLD.nationality END, OLD.subdivision FROM active_user LIMIT 1);
+        END IF;
+
+        RETURN NEW;
+    END;
+$player_subdivision_modification_trigger$ LANGUAGE plpgsql;
+
+CREATE TRIGGER player_subdivision_modification_trigger AFTER UPDATE ON players FOR EACH ROW EXECUTE PROCEDURE audit_player_subdivision_modification();
+
+-- So that undoing a merge restores the merged player's subdivision (see player_splits)
+ALTER TABLE player_merges ADD COLUMN merged_subdivision VARCHAR(3) NULL;
+
+INSERT INTO subdivisions (nation, iso_code, name) VALUES
+    ('US', 'AL', 'Alabama'),
+    ('US', 'AK', 'Alaska'),
+    ('US', 'AZ', 'Arizona'),
+    ('US', 'AR', 'Arkansas'),
+    ('US', 'CA', 'California'),
+    ('US', 'CO', 'Colorado'),
+    ('US', 'CT', 'Connecticut'),
+    ('US', 'DE', 'Delaware'),
+    ('US', 'DC', 'District of Columbia'),
+    ('US', 'FL', 'Florida'),
+    ('US', 'GA', 'Georgia'),
+    ('US', 'HI', 'Hawaii'),
+    ('US', 'ID', 'Idaho'),
+    ('US', 'IL', 'Illinois'),
+    ('US', 'IN', 'Indiana'),
+    ('US', 'IA', 'Iowa'),
+    ('US', 'KS', 'Kansas'),
+    ('US', 'KY', 'Kentucky'),
+    ('US', 'LA', 'Louisiana'),
+    ('US', 'ME', 'Maine'),
+    ('US', 'MD', 'Maryland'),
+    ('US', 'MA', 'Massachusetts'),
+    ('US', 'MI', 'Michigan'),
+    ('US', 'MN', 'Minnesota'),
+    ('US', 'MS', 'Mississippi'),
+    ('US', 'MO', 'Missouri'),
+    ('US', 'MT', 'Montana'),
+    ('US', 'NE', 'Nebraska'),
+    ('US', 'NV', 'Nevada'),
+    ('US', 'NH', 'New Hampshire'),
+    ('US', 'NJ', 'New Jersey'),
+    ('US', 'NM', 'New Mexico'),
+    ('US', 'NY', 'New York'),
+    ('US', 'NC', 'North Carolina'),
+    ('US', 'ND', 'North Dakota'),
+    ('US', 'OH', 'Ohio'),
+    ('US', 'OK', 'Oklahoma'),
+    ('US', 'OR', 'Oregon'),
+    ('US', 'PA', 'Pennsylvania'),
+    ('US', 'RI', 'Rhode Island'),
+    ('US', 'SC', 'South Carolina'),
+    ('US', 'SD', 'South Dakota'),
+    ('US', 'TN', 'Tennessee'),
+    ('US', 'TX', 'Texas'),
+    ('US', 'UT', 'Utah'),
+    ('US', 'VT', 'Vermont'),
+    ('US', 'VA', 'Virginia'),
+    ('US', 'WA', 'Washington'),
+    ('US', 'WV', 'West Virginia'),
+    ('US', 'WI', 'Wisconsin'),
+    ('US', 'WY', 'Wyoming');
+
+INSERT INTO subdivisions (nation, iso_code, name) VALUES
+    ('CA', 'AB', 'Alberta'),
+    ('CA', 'BC', 'British Columbia'),
+    ('CA', 'MB', 'Manitoba'),
+    ('CA', 'NB', 'New Brunswick'),
+    ('CA', 'NL', 'Newfoundland and Labrador'),
+    ('CA', 'NS', 'Nova Scotia'),
+    ('CA', 'NT', 'Northwest Territories'),
+    ('CA', 'NU', 'Nunavut'),
+    ('CA', 'ON', 'Ontario'),
+    ('CA', 'PE', 'Prince Edward Island'),
+    ('CA', 'QC', 'Quebec'),
+    ('CA', 'SK', 'Saskatchewan'),
+    ('CA', 'YT', 'Yukon');
+
+INSERT INTO subdivisions (nation, iso_code, name) VALUES
+    ('AU', 'ACT', 'Australian Capital Territory'),
+    ('AU', 'NSW', 'New South Wales'),
+    ('AU', 'NT', 'Northern Territory'),
+    ('AU', 'QLD', 'Queensland'),
+    ('AU', 'SA', 'South Australia'),
+    ('AU', 'TAS', 'Tasmania'),
+    ('AU', 'VIC', 'Victoria'),
+    ('AU', 'WA', 'Western Australia');
+
+INSERT INTO subdivisions (nation, iso_code, name) VALUES
+    ('DE', 'BW', 'Baden-Württemberg'),
+    ('DE', 'BY', 'Bayern'),
+    ('DE', 'BE', 'Berlin'),
+    ('DE', 'BB', 'Brandenburg'),
+    ('DE', 'HB', 'Bremen'),
+    ('DE', 'HH', 'Hamburg'),
+    ('DE', 'HE', 'Hessen'),
+    ('DE', 'MV', 'Mecklenburg-Vorpommern'),
+    ('DE', 'NI', 'Niedersachsen'),
+    ('DE', 'NW', 'Nordrhein-Westfalen'),
+    ('DE', 'RP', 'Rheinland-Pfalz'),
+    ('DE', 'SL', 'Saarland'),
+    ('DE', 'SN', 'Sachsen'),
+    ('DE', 'ST', 'Sachsen-Anhalt'),
+    ('DE', 'SH', 'Schleswig-Holstein'),
+    ('DE', 'TH', 'Thüringen');
+
+INSERT INTO subdivisions (nation, iso_code, name) VALUES
+    ('GB', 'ENG', 'England'),
+    ('GB', 'NIR', 'Northern Ireland'),
+    ('GB', 'SCT', 'Scotland'),
+    ('GB', 'WLS', 'Wales');
@@ -0,0 +1,37 @@
+-- This file should undo anything in `up.sql`
+
+ALTER TABLE player_merges
+    DROP COLUMN merged_hide_videos,
+    DROP COLUMN merged_claims,
+    DROP COLUMN merged_claims_verified;
+CREATE OR REPLACE FUNCTION audit_player_modification() RETURNS trigger as $player_modification_trigger$
+    DECLARE
+        name_change CITEXT;
+        banned_change BOOLEAN;
+    BEGIN
+        IF (OLD.name <> NEW.name) THEN
+            name_change = OLD.name;
+        END IF;
+
+        IF (OLD.banned <> NEW.banned) THEN
+            banned_change = OLD.banned;
+        END IF;
+
+        INSERT INTO player_modifications (userid, id, name, banned)
+        (SELECT id, NEW.id, name_change, banned_change FROM active_user LIMIT 1);
+
+        RETURN NEW;
+    END;
+$player_modification_trigger$ LANGUAGE plpgsql;
+
+ALTER TABLE player_modifications DROP COLUMN hide_videos;
+ALTER TABLE players DROP COLUMN hide_videos;
+
+DROP TABLE player_claims;
+DROP TABLE player_claim_additions;
+DROP TABLE player_claim_modifications;
+DROP TABLE player_claim_deletions;
+
+DROP FUNCTION audit_player_claim_addition() CASCADE;
+DROP FUNCTION audit_player_claim_modification() CASCADE;
+DROP FUNCTION audit_player_claim_deletion() CASCADE;
@@ -0,0 +1,117 @@
+-- Your SQL goes here
+
+-- Links between user accounts and players. A user requests to claim a player, and the claim only becomes effective once
+-- a leaderboard moderator has verified it. Every user can only claim a single player (so requesting a claim on a
+-- different player replaces any unverified claim), and every player can only have a single verified claim.
+CREATE TABLE player_claims (
+    member_id INTEGER NOT NULL PRIMARY KEY REFERENCES members(member_id) ON DELETE CASCADE,
+    player_id INTEGER NOT NULL REFERENCES players(id) ON DELETE CASCADE,
+    verified BOOLEAN NOT NULL DEFAULT FALSE,
+    requested_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT (NOW() AT TIME ZONE 'utc')
+);
+
+CREATE UNIQUE INDEX player_claims_verified ON player_claims(player_id) WHERE verified;
+CREATE INDEX player_claims_player ON player_claims(player_id);
+
+CREATE TABLE player_claim_additions (
+    member_id INTEGER NOT NULL, -- REFERENCES members(member_id)
+    player_id INTEGER NOT NULL -- REFERENCES players(id)
+) INHERITS (audit_log2);
+
+CREATE FUNCTION audit_player_claim_addition() RETURNS trigger AS $player_claim_addition_trigger$
+    BEGIN
+        INSERT INTO player_claim_additions (userid, member_id, player_id)
+            (SELECT id, NEW.member_id, NEW.player_id FROM active_user LIMIT 1);
+
+        RETURN NEW;
+    END;
+$player_claim_addition_trigger$ LANGUAGE plpgsql;
+
+CREATE TRIGGER player_claim_addition_trigger AFTER INSERT ON player_claims FOR EACH ROW EXECUTE PROCEDURE audit_player_claim_addition();
+
+-- Claims are modified when they are verified, or when a user requests to claim a different player (which replaces their
+-- claim). player_id is the player claimed after the modification. As with the other modification tables, the old values of
+-- changed columns are logged in the remaining columns, unchanged columns are NULL
+CREATE TABLE player_claim_modifications (
+    member_id INTEGER NOT NULL, -- REFERENCES members(member_id)
+    player_id INTEGER NOT NULL, -- REFERENCES players(id)
+    previous_player_id INTEGER NULL, -- REFERENCES players(id)
+    verified BOOLEAN NULL
+) INHERITS (audit_log2);
+
+CREATE FUNCTION audit_player_claim_modification() RETURNS trigger AS $player_claim_modification_trigger$
+    DECLARE
+        player_id_change INTEGER;
+        verified_change BOOLEAN;
+    BEGIN
+        IF (OLD.player_id <> NEW.player_id) THEN
+            player_id_change = OLD.player_id;
+        END IF;
+
+        IF (OLD.verified <> NEW.verified) THEN
+            verified_change = OLD.verified;
+        END IF;
+
+        INSERT INTO player_claim_modifications (userid, member_id, player_id, previous_player_id, verified)
+            (SELECT id, NEW.member_id, NEW.player_id, player_id_change, verified_change FROM active_user LIMIT 1);
+
+        RETURN NEW;
+    END;
+$player_claim_modification_trigger$ LANGUAGE plpgsql;
+
+CREATE TRIGGER player_claim_modification_trigger AFTER UPDATE ON player_claims FOR EACH ROW EXECUTE PROCEDURE audit_player_claim_modification();
+
+CREATE TABLE player_claim_deletions (
+    member_id INTEGER NOT NULL, -- REFERENCES members(member_id)
+    player_id INTEGER NOT NULL, -- REFERENCES players(id)
+    verified BOOLEAN NOT NULL
+) INHERITS (audit_log2);
+
+CREATE FUNCTION audit_player_claim_deletion() RETURNS trigger AS $player_claim_deletion_trigger$
+    BEGIN
+        INSERT INTO player_claim_deletions (userid, member_id, player_id, verified)
+            (SELECT id, OLD.member_id, OLD.player_id, OLD.verified FROM active_user LIMIT 1);
+
+        RETURN NULL;
+    END;
+$player_claim_deletion_trigger$ LANGUAGE plpgsql;
+
+CREATE TRIGGER player_claim_deletion_trigger AFTER DELETE ON player_claims FOR EACH ROW EXECUTE PROCEDURE audit_player_claim_deletion();
+
+-- Whether the player chose to hide the videos of their records, which they can do once their claim was verified. Kept
+-- separate from link_banned, which is a moderation decision claimed players must not be able to lift
+ALTER TABLE players ADD COLUMN hide_videos BOOLEAN NOT NULL DEFAULT FALSE;
+
+ALTER TABLE player_modifications ADD COLUMN hide_videos BOOLEAN NULL;
+
+CREATE OR REPLACE FUNCTION audit_player_modification() RETURNS trigger as $player_modification_trigger$
+    DECLARE
+        name_change CITEXT;
+        banned_change BOOLEAN;
+        hide_videos_change BOOLEAN;
+    BEGIN
+        IF (OLD.name <> NEW.name) THEN
+            name_change = OLD.name;
+        END IF;
+
+        IF (OLD.banned <> NEW.banned) THEN
+            banned_change = OLD.banned;
+        END IF;
+
+        IF (OLD.hide_videos <> NEW.hide_videos) THEN
+            hide_videos_change = OLD.hide_videos;
+        END IF;
+
+        INSERT INTO player_modifications (userid, id, name, banned, hide_videos)
+        (SELECT id, NEW.id, name_change, banned_change, hide_videos_change FROM active_user LIMIT 1);
+
+        RETURN NEW;
+    END;
+$player_modification_trigger$ LANGUAGE plpgsql;
+
+-- So that undoing a merge restores the merged player's choice and claims (see player_splits). The claims are stored as the
+-- claiming members and whether their claims were verified before the merge, at matching indices
+ALTER TABLE player_merges
+    ADD COLUMN merged_hide_videos BOOLEAN NULL,
+    ADD COLUMN merged_claims INTEGER[] NULL,
+    ADD COLUMN merged_claims_verified BOOLEAN[] NULL;
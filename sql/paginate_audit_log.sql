@@ -0,0 +1,65 @@
+WITH entries AS (
+    SELECT time, audit_id, userid, 'record' AS object_type, id AS object_id, 'addition' AS action, NULL::TEXT AS data FROM record_additions
+    UNION ALL
+    SELECT time, audit_id, userid, 'record', id, 'modification', (to_jsonb(record_modifications) - ARRAY['time', 'audit_id', 'userid', 'id'])::TEXT FROM record_modifications
+    UNION ALL
+    SELECT time, audit_id, userid, 'record', id, 'video_probe', (to_jsonb(record_video_probes) - ARRAY['time', 'audit_id', 'userid', 'id'])::TEXT FROM record_video_probes
+    UNION ALL
+    SELECT time, audit_id, userid, 'record', id, 'rejection_reason_change', jsonb_build_object('reason', reason)::TEXT FROM record_rejection_reason_changes
+    UNION ALL
+    SELECT time, audit_id, userid, 'record', id, 'deletion', NULL FROM record_deletions
+    UNION ALL
+    SELECT time, audit_id, userid, 'record_note', id, 'addition', NULL FROM record_notes_additions
+    UNION ALL
+    SELECT time, audit_id, userid, 'record_note', id, 'modification', (to_jsonb(record_notes_modifications) - ARRAY['time', 'audit_id', 'userid', 'id'])::TEXT FROM record_notes_modifications
+    UNION ALL
+    SELECT time, audit_id, userid, 'record_note', id, 'deletion', NULL FROM record_notes_deletions
+    UNION ALL
+    SELECT time, audit_id, userid, 'demon', id, 'addition', NULL FROM demon_additions
+    UNION ALL
+    SELECT time, audit_id, userid, 'demon', id, 'modification', (to_jsonb(demon_modifications) - ARRAY['time', 'audit_id', 'userid', 'id'])::TEXT FROM demon_modifications
+    UNION ALL
+    SELECT time, audit_id, userid, 'creator', demon, 'addition', jsonb_build_object('creator', creator)::TEXT FROM creator_additions
+    UNION ALL
+    SELECT time, audit_id, userid, 'creator', demon, 'deletion', jsonb_build_object('creator', creator)::TEXT FROM creator_deletions
+    UNION ALL
+    SELECT time, audit_id, userid, 'player', id, 'addition', NULL FROM player_additions
+    UNION ALL
+    SELECT time, audit_id, userid, 'player', id, 'modification', (to_jsonb(player_modifications) - ARRAY['time', 'audit_id', 'userid', 'id'])::TEXT FROM player_modifications
+    UNION ALL
+    SELECT time, audit_id, userid, 'player', id, 'modification', jsonb_build_object('nationality', nationality)::TEXT FROM player_nationality_modifications
+    UNION ALL
+    SELECT time, audit_id, userid, 'player', id, 'modification', jsonb_build_object('nation', nation, 'subdivision', subdivision)::TEXT FROM player_subdivision_modifications
+    UNION ALL
+    SELECT time, audit_id, userid, 'player', id, 'merge', (to_jsonb(player_merges) - ARRAY['time', 'audit_id', 'userid', 'id'])::TEXT FROM player_merges
+    UNION ALL
+    SELECT time, audit_id, userid, 'player', id, 'split', jsonb_build_object('merge', merge, 'restored_id', restored_id)::TEXT FROM player_splits
+    UNION ALL
+    SELECT time, audit_id, userid, 'player', id, 'deletion', NULL FROM player_deletions
+    UNION ALL
+    SELECT time, audit_id, userid, 'player_claim', player_id, 'addition', jsonb_build_object('member_id', member_id)::TEXT FROM player_claim_additions
+    UNION ALL
+    SELECT time, audit_id, userid, 'player_claim', player_id, 'modification', (to_jsonb(player_claim_modifications) - ARRAY['time', 'audit_id', 'userid', 'player_id'])::TEXT FROM player_claim_modifications
+    UNION ALL
+    SELECT time, audit_id, userid, 'player_claim', player_id, 'deletion', (to_jsonb(player_claim_deletions) - ARRAY['time', 'audit_id', 'userid', 'player_id'])::TEXT FROM player_claim_deletions
+    UNION ALL
+    SELECT time, audit_id, userid, 'submitter', submitter, 'modification', (to_jsonb(submitter_modifications) - ARRAY['time', 'audit_id', 'userid', 'submitter'])::TEXT FROM submitter_modifications
+    UNION ALL
+    SELECT time, audit_id, userid, 'user', id, 'addition', NULL FROM user_additions
+    UNION ALL
+    SELECT time, audit_id, userid, 'user', id, 'modification', (to_jsonb(user_modifications) - ARRAY['time', 'audit_id', 'userid', 'id'])::TEXT FROM user_modifications
+    UNION ALL
+    SELECT time, audit_id, userid, 'user', id, 'deletion', NULL FROM user_deletions
+)
+SELECT entries.*, members.name AS username
+FROM entries
+LEFT OUTER JOIN members ON members.member_id = entries.userid
+WHERE (audit_id < $1 OR $1 IS NULL)
+  AND (audit_id > $2 OR $2 IS NULL)
+  AND (userid = $3 OR $3 IS NULL)
+  AND (object_type = $4 OR $4 IS NULL)
+  AND (object_id = $5 OR $5 IS NULL)
+  AND (time >= $6 OR $6 IS NULL)
+  AND (time < $7 OR $7 IS NULL)
+ORDER BY audit_id {}
+LIMIT $8
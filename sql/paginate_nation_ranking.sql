@@ -0,0 +1,7 @@
+SELECT rank, score, index, nation::TEXT, iso_country_code::TEXT, players
+FROM nations_with_score
+WHERE (index < $1 OR $1 IS NULL)
+  AND (index > $2 OR $2 IS NULL)
+  AND (STRPOS(nation, $3::CITEXT) > 0 OR $3 is NULL)
+ORDER BY rank {}
+LIMIT $4
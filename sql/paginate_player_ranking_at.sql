@@ -0,0 +1,14 @@
+SELECT players.id, players.name::TEXT, rank, score, index, nationalities.nation::TEXT, nationalities.iso_country_code::TEXT, subdivisions.iso_code::TEXT AS subdivision_code,
+       subdivisions.name::TEXT AS subdivision_name
+FROM player_ranking_snapshots
+INNER JOIN players ON players.id = player_ranking_snapshots.player
+LEFT OUTER JOIN nationalities ON players.nationality = nationalities.iso_country_code
+LEFT OUTER JOIN subdivisions ON subdivisions.nation = players.nationality AND subdivisions.iso_code = players.subdivision
+WHERE day = (SELECT MAX(day) FROM player_ranking_snapshots WHERE day <= $7::DATE)
+  AND (index < $1 OR $1 IS NULL)
+  AND (index > $2 OR $2 IS NULL)
+  AND (STRPOS(players.name, $3::CITEXT) > 0 OR $3 is NULL)
+  AND (nationalities.nation = $4 OR nationalities.iso_country_code = $4 OR (nationalities.nation IS NULL AND $5) OR ($4 IS NULL AND NOT $5))
+  AND (subdivisions.iso_code = UPPER($8) OR subdivisions.name = $8::CITEXT OR $8 IS NULL)
+ORDER BY rank {}
+LIMIT $6
\ No newline at end of file
@@ -0,0 +1,12 @@
+SELECT ranking.id, ranking.name::TEXT, rank, score, index, ranking.nation::TEXT, ranking.iso_country_code::TEXT, subdivisions.iso_code::TEXT AS subdivision_code,
+       subdivisions.name::TEXT AS subdivision_name
+FROM players_with_score_under($7) AS ranking
+INNER JOIN players ON players.id = ranking.id
+LEFT OUTER JOIN subdivisions ON subdivisions.nation = players.nationality AND subdivisions.iso_code = players.subdivision
+WHERE (index < $1 OR $1 IS NULL)
+  AND (index > $2 OR $2 IS NULL)
+  AND (STRPOS(ranking.name, $3::CITEXT) > 0 OR $3 is NULL)
+  AND (ranking.nation = $4 OR ranking.iso_country_code = $4 OR (ranking.nation IS NULL AND $5) OR ($4 IS NULL AND NOT $5))
+  AND (subdivisions.iso_code = UPPER($8) OR subdivisions.name = $8::CITEXT OR $8 IS NULL)
+ORDER BY rank {}
+LIMIT $6
\ No newline at end of file
@@ -0,0 +1,37 @@
+WITH matching_records AS (
+    SELECT id FROM records WHERE (player = $3 OR $3 IS NULL) AND (demon = $4 OR $4 IS NULL)
+    UNION
+    -- Deleted records, as well as records that used to belong to the player/demon. Note that on deletion, the
+    -- record's final state is stored in record_modifications
+    SELECT id FROM record_modifications WHERE (player = $3 OR $3 IS NULL) AND (demon = $4 OR $4 IS NULL)
+),
+entries AS (
+    SELECT time, audit_id, userid, id AS record_id, 'ADDITION' AS type_, NULL::SMALLINT AS progress, NULL::TEXT AS video,
+           NULL::TEXT AS status, NULL::INTEGER AS player, NULL::INTEGER AS demon, NULL::SMALLINT AS response, NULL::TEXT AS error,
+           NULL::INTEGER AS reason
+    FROM record_additions
+    UNION ALL
+    SELECT time, audit_id, userid, id, 'MODIFICATION', progress, video::TEXT, status_::TEXT, player, demon, NULL, NULL, NULL
+    FROM record_modifications
+    UNION ALL
+    SELECT time, audit_id, userid, id, 'VIDEO_PROBE', NULL, video, NULL, NULL, NULL, response, error, NULL
+    FROM record_video_probes
+    UNION ALL
+    SELECT time, audit_id, userid, id, 'REJECTION_REASON_CHANGE', NULL, NULL, NULL, NULL, NULL, NULL, NULL, reason
+    FROM record_rejection_reason_changes
+    UNION ALL
+    SELECT time, audit_id, userid, id, 'DELETION', NULL, NULL, NULL, NULL, NULL, NULL, NULL, NULL
+    FROM record_deletions
+)
+SELECT entries.*, members.name AS username, players.name::TEXT AS player_name, demons.name::TEXT AS demon_name,
+       rejection_reasons.name AS reason_name
+FROM entries
+LEFT OUTER JOIN members ON members.member_id = entries.userid
+LEFT OUTER JOIN players ON players.id = entries.player
+LEFT OUTER JOIN demons ON demons.id = entries.demon
+LEFT OUTER JOIN rejection_reasons ON rejection_reasons.id = entries.reason
+WHERE (audit_id < $1 OR $1 IS NULL)
+  AND (audit_id > $2 OR $2 IS NULL)
+  AND record_id IN (SELECT id FROM matching_records)
+ORDER BY audit_id {}
+LIMIT $5
@@ -1,4 +1,4 @@
-SELECT progress, CASE WHEN players.link_banned THEN NULL ELSE records.video::text END, status_::text AS "status!: String" ,
+SELECT progress, CASE WHEN players.link_banned OR players.hide_videos THEN NULL ELSE records.video::text END, status_::text AS "status!: String" ,
        players.id AS player_id, players.name AS "player_name: String", players.banned AS player_banned,
        demons.id AS demon_id, demons.name AS "demon_name: String", demons.position,
        submitters.submitter_id AS submitter_id, submitters.banned AS submitter_banned
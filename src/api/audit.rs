@@ -0,0 +1,30 @@
+//! Handlers for all endpoints under the `/api/v1/audit` prefix
+
+use crate::{
+    extractor::auth::TokenAuth,
+    model::{audit::AuditLogPagination, demonlist::record::audit},
+    permissions::Permissions,
+    state::PointercrateState,
+    ApiResult,
+};
+use actix_web::{web::Query, HttpResponse};
+use actix_web_codegen::get;
+
+#[get("/")]
+pub async fn paginate(
+    TokenAuth(user): TokenAuth, state: PointercrateState, mut pagination: Query<AuditLogPagination>,
+) -> ApiResult<HttpResponse> {
+    // Administrator does not imply ListAdministrator (nor the other way around), but both get to see
+    // the full log
+    if !user.inner().has_permission(Permissions::Administrator) {
+        user.inner().require_permissions(Permissions::ListAdministrator)?;
+    }
+
+    let mut connection = state.connection().await?;
+
+    let mut entries = pagination.page(&mut connection).await?;
+
+    let (max_id, min_id) = audit::extremal_audit_ids(&mut connection).await?;
+
+    pagination_response!("/api/v1/audit/", entries, pagination, min_id, max_id, before_id, after_id, audit_id)
+}
@@ -6,7 +6,11 @@ use crate::{
         if_match::IfMatch,
         ip::Ip,
     },
-    model::user::{AuthenticatedUser, Authorization, PatchMe, Registration},
+    model::{
+        demonlist::player::{PatchClaimedPlayer, PlayerClaim},
+        user::{AuthenticatedUser, Authorization, PatchMe, Registration},
+        webhook::{delivery, WebhookEvent},
+    },
     ratelimit::RatelimitScope,
     state::PointercrateState,
     util::HttpResponseBuilderExt,
@@ -83,3 +87,51 @@ pub async fn delete_me(if_match: IfMatch, BasicAuth(user): BasicAuth, state: Poi
 
     Ok(HttpResponse::NoContent().finish())
 }
+
+#[get("/me/player/")]
+pub async fn get_me_player(TokenAuth(user): TokenAuth, state: PointercrateState) -> ApiResult<HttpResponse> {
+    let mut connection = state.connection().await?;
+
+    let player = PlayerClaim::by_user(user.inner().id, &mut connection)
+        .await?
+        .upgrade(&mut connection)
+        .await?;
+
+    Ok(HttpResponse::Ok().json_with_etag(&player))
+}
+
+#[patch("/me/player/")]
+pub async fn patch_me_player(
+    TokenAuth(user): TokenAuth, if_match: IfMatch, state: PointercrateState, patch: Json<PatchClaimedPlayer>,
+) -> ApiResult<HttpResponse> {
+    let mut connection = state.audited_transaction(&user).await?;
+
+    let player = PlayerClaim::by_user(user.inner().id, &mut connection)
+        .await?
+        .upgrade(&mut connection)
+        .await?;
+
+    if_match.require_etag_match(&player)?;
+
+    let player = player.apply_patch(patch.into_inner(), &mut connection).await?;
+
+    delivery::dispatch(WebhookEvent::PlayerModified, &player.player, false, &mut connection).await?;
+
+    connection.commit().await?;
+
+    Ok(HttpResponse::Ok().json_with_etag(&player))
+}
+
+#[delete("/me/player/")]
+pub async fn delete_me_player(TokenAuth(user): TokenAuth, state: PointercrateState) -> ApiResult<HttpResponse> {
+    let mut connection = state.audited_transaction(&user).await?;
+
+    PlayerClaim::by_user(user.inner().id, &mut connection)
+        .await?
+        .delete(&mut connection)
+        .await?;
+
+    connection.commit().await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -1,6 +1,9 @@
 use crate::{
     extractor::auth::TokenAuth,
-    model::demonlist::demon::{FullDemon, PostDemon},
+    model::{
+        demonlist::demon::{FullDemon, PostDemon},
+        webhook::{delivery, WebhookEvent},
+    },
     permissions::Permissions,
     state::PointercrateState,
     util::HttpResponseBuilderExt,
@@ -17,6 +20,8 @@ pub async fn post(TokenAuth(user): TokenAuth, state: PointercrateState, data: Js
 
     let demon = FullDemon::create_from(data.into_inner(), &mut connection).await?;
 
+    delivery::dispatch(WebhookEvent::DemonAdded, &demon, false, &mut connection).await?;
+
     connection.commit().await?;
 
     Ok(HttpResponse::Created().json_with_etag(&demon))
@@ -25,10 +30,13 @@ pub async fn post(TokenAuth(user): TokenAuth, state: PointercrateState, data: Js
 pub mod v1 {
     use crate::{
         extractor::{auth::TokenAuth, if_match::IfMatch},
-        model::demonlist::{
-            creator::{Creator, PostCreator},
-            demon::{Demon, DemonPositionPagination, FullDemon, PatchDemon},
-            player::DatabasePlayer,
+        model::{
+            demonlist::{
+                creator::{Creator, PostCreator},
+                demon::{Demon, DemonPositionPagination, FullDemon, PatchDemon},
+                player::DatabasePlayer,
+            },
+            webhook::{delivery, WebhookEvent},
         },
         permissions::Permissions,
         state::PointercrateState,
@@ -82,8 +90,18 @@ pub mod v1 {
 
         if_match.require_etag_match(&demon)?;
 
+        let old_position = demon.demon.base.position;
+
         let demon = demon.apply_patch(patch.into_inner(), &mut connection).await?;
 
+        let event = if demon.demon.base.position != old_position {
+            WebhookEvent::DemonMoved
+        } else {
+            WebhookEvent::DemonModified
+        };
+
+        delivery::dispatch(event, &demon, false, &mut connection).await?;
+
         connection.commit().await?;
 
         Ok(HttpResponse::Ok().json_with_etag(&demon))
@@ -133,10 +151,14 @@ pub mod v1 {
 pub mod v2 {
     use crate::{
         extractor::{auth::TokenAuth, if_match::IfMatch},
-        model::demonlist::{
-            creator::{Creator, PostCreator},
-            demon::{Demon, DemonIdPagination, DemonPositionPagination, FullDemon, PatchDemon},
-            player::DatabasePlayer,
+        model::{
+            demonlist::{
+                creator::{Creator, PostCreator},
+                demon::{audit, Demon, DemonIdPagination, DemonPositionPagination, FullDemon, MinimalDemon, PatchDemon},
+                player::DatabasePlayer,
+                TimeMachine,
+            },
+            webhook::{delivery, WebhookEvent},
         },
         permissions::Permissions,
         state::PointercrateState,
@@ -180,14 +202,31 @@ pub mod v2 {
     }
 
     #[get("/{demon_id}/")]
-    pub async fn get(state: PointercrateState, id: Path<i32>) -> ApiResult<HttpResponse> {
+    pub async fn get(state: PointercrateState, id: Path<i32>, time_machine: Query<TimeMachine>) -> ApiResult<HttpResponse> {
         let mut connection = state.connection().await?;
 
-        let demon = FullDemon::by_id(id.into_inner(), &mut connection).await?;
+        let mut demon = FullDemon::by_id(id.into_inner(), &mut connection).await?;
+
+        if let Some(at) = time_machine.at {
+            demon.load_records_at(at, &mut connection).await?;
+        }
 
         Ok(HttpResponse::Ok().json_with_etag(&demon))
     }
 
+    #[get("/{demon_id}/audit/")]
+    pub async fn audit_log(TokenAuth(user): TokenAuth, state: PointercrateState, id: Path<i32>) -> ApiResult<HttpResponse> {
+        user.inner().require_permissions(Permissions::ListHelper)?;
+
+        let mut connection = state.connection().await?;
+
+        // Demons added before the audit log existed might not have any entries
+        let demon = MinimalDemon::by_id(id.into_inner(), &mut connection).await?;
+        let log = audit::entries_for_demon(demon.id, &mut connection).await?;
+
+        Ok(HttpResponse::Ok().json(log))
+    }
+
     #[patch("/{demon_id}/")]
     pub async fn patch(
         TokenAuth(user): TokenAuth, if_match: IfMatch, state: PointercrateState, patch: Json<PatchDemon>, id: Path<i32>,
@@ -201,8 +240,18 @@ pub mod v2 {
 
         if_match.require_etag_match(&demon)?;
 
+        let old_position = demon.demon.base.position;
+
         let demon = demon.apply_patch(patch.into_inner(), &mut connection).await?;
 
+        let event = if demon.demon.base.position != old_position {
+            WebhookEvent::DemonMoved
+        } else {
+            WebhookEvent::DemonModified
+        };
+
+        delivery::dispatch(event, &demon, false, &mut connection).await?;
+
         connection.commit().await?;
 
         Ok(HttpResponse::Ok().json_with_etag(&demon))
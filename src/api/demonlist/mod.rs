@@ -2,4 +2,6 @@ pub mod demon;
 pub mod misc;
 pub mod player;
 pub mod record;
+pub mod rejection_reason;
+pub mod scoring_formula;
 pub mod submitter;
@@ -1,6 +1,16 @@
 use crate::{
+    error::PointercrateError,
     extractor::{auth::TokenAuth, if_match::IfMatch},
-    model::demonlist::player::{PatchPlayer, Player, PlayerPagination, RankedPlayer, RankingPagination},
+    model::{
+        demonlist::{
+            player::{
+                audit, history::history_of, DatabasePlayer, DryRun, PatchPlayer, Player, PlayerClaim, PlayerPagination, RankedPlayer,
+                RankingPagination,
+            },
+            TimeMachine,
+        },
+        webhook::{delivery, WebhookEvent},
+    },
     permissions::Permissions,
     state::PointercrateState,
     util::HttpResponseBuilderExt,
@@ -10,7 +20,8 @@ use actix_web::{
     web::{Json, Path, Query},
     HttpResponse,
 };
-use actix_web_codegen::{get, patch};
+use actix_web_codegen::{delete, get, patch, post};
+use serde_json::json;
 
 #[get("/")]
 pub async fn paginate(state: PointercrateState, mut pagination: Query<PlayerPagination>) -> ApiResult<HttpResponse> {
@@ -27,7 +38,10 @@ pub async fn ranking(state: PointercrateState, mut pagination: Query<RankingPagi
     let mut connection = state.connection().await?;
 
     let mut demons = pagination.page(&mut connection).await?;
-    let max_index = RankedPlayer::max_index(&mut connection).await?;
+    let max_index = match pagination.at {
+        Some(at) => RankedPlayer::max_index_at(at, &mut connection).await?,
+        None => RankedPlayer::max_index(&mut connection).await?,
+    };
 
     pagination_response!(
         "/api/v1/players/ranking/",
@@ -42,20 +56,100 @@ pub async fn ranking(state: PointercrateState, mut pagination: Query<RankingPagi
 }
 
 #[get("/{player_id}/")]
-pub async fn get(state: PointercrateState, path: Path<i32>) -> ApiResult<HttpResponse> {
+pub async fn get(state: PointercrateState, path: Path<i32>, time_machine: Query<TimeMachine>) -> ApiResult<HttpResponse> {
     let mut connection = state.connection().await?;
 
-    let player = Player::by_id(path.into_inner(), &mut connection)
+    let mut player = Player::by_id(path.into_inner(), &mut connection)
         .await?
         .upgrade(&mut connection)
         .await?;
 
+    if let Some(at) = time_machine.at {
+        player.load_records_at(at, &mut connection).await?;
+    }
+
     Ok(HttpResponse::Ok().json_with_etag(&player))
 }
 
+#[get("/{player_id}/history/")]
+pub async fn history(state: PointercrateState, path: Path<i32>) -> ApiResult<HttpResponse> {
+    let mut connection = state.connection().await?;
+
+    let player = Player::by_id(path.into_inner(), &mut connection).await?;
+    let snapshots = history_of(player.base.id, &mut connection).await?;
+
+    Ok(HttpResponse::Ok().json(snapshots))
+}
+
+#[get("/{player_id}/changes/")]
+pub async fn changes(TokenAuth(user): TokenAuth, state: PointercrateState, path: Path<i32>) -> ApiResult<HttpResponse> {
+    user.inner().require_permissions(Permissions::ListModerator)?;
+
+    let mut connection = state.connection().await?;
+
+    // Players that were merged into others no longer exist, but their log does
+    let player_id = path.into_inner();
+    let log = audit::entries_for_player(player_id, &mut connection).await?;
+
+    if log.is_empty() {
+        Err(PointercrateError::ModelNotFound {
+            model: "Player",
+            identified_by: player_id.to_string(),
+        }
+        .into())
+    } else {
+        Ok(HttpResponse::Ok().json(log))
+    }
+}
+
+#[get("/{player_id}/merge-preview/{merged_id}/")]
+pub async fn merge_preview(TokenAuth(user): TokenAuth, state: PointercrateState, ids: Path<(i32, i32)>) -> ApiResult<HttpResponse> {
+    user.inner().require_permissions(Permissions::ListModerator)?;
+
+    // The merge is actually performed to generate the preview, so it needs to be logged just like a
+    // real one would be (and then rolled back)
+    let mut connection = state.audited_transaction(&user).await?;
+
+    let (player_id, merged_id) = ids.into_inner();
+
+    let mut player = Player::by_id(player_id, &mut connection).await?.upgrade(&mut connection).await?;
+    let merged = DatabasePlayer::by_id(merged_id, &mut connection).await?;
+
+    let preview = player.preview_merge(merged, &mut connection).await?;
+
+    connection.rollback().await?;
+
+    Ok(HttpResponse::Ok().json(json!({"data": player, "preview": preview})))
+}
+
+#[post("/{player_id}/changes/{audit_id}/split/")]
+pub async fn split(TokenAuth(user): TokenAuth, state: PointercrateState, ids: Path<(i32, i32)>) -> ApiResult<HttpResponse> {
+    user.inner().require_permissions(Permissions::ListAdministrator)?;
+
+    let mut connection = state.audited_transaction(&user).await?;
+
+    let (player_id, audit_id) = ids.into_inner();
+
+    let mut player = DatabasePlayer::by_id(player_id, &mut connection).await?;
+    let (restored, deleted_records) = player.split(audit_id, &mut connection).await?;
+
+    let player = Player::by_id(player.id, &mut connection).await?.upgrade(&mut connection).await?;
+    let restored = Player::by_id(restored.id, &mut connection).await?.upgrade(&mut connection).await?;
+
+    delivery::dispatch(WebhookEvent::PlayerModified, &player, false, &mut connection).await?;
+
+    connection.commit().await?;
+
+    Ok(HttpResponse::Created()
+        .header("Location", format!("/api/v1/players/{}/", restored.player.base.id))
+        .etag(&restored)
+        .json(json!({"data": restored, "deleted_records": deleted_records})))
+}
+
 #[patch("/{player_id}/")]
 pub async fn patch(
     TokenAuth(user): TokenAuth, if_match: IfMatch, state: PointercrateState, data: Json<PatchPlayer>, path: Path<i32>,
+    options: Query<DryRun>,
 ) -> ApiResult<HttpResponse> {
     user.inner().require_permissions(Permissions::ListModerator)?;
 
@@ -68,9 +162,91 @@ pub async fn patch(
 
     if_match.require_etag_match(&player)?;
 
+    if options.dry_run {
+        let (player, preview) = player.preview_patch(data.into_inner(), &mut connection).await?;
+
+        connection.rollback().await?;
+
+        return Ok(HttpResponse::Ok().json(json!({"data": player, "preview": preview})))
+    }
+
+    let was_banned = player.player.base.banned;
+
     let player = player.apply_patch(data.into_inner(), &mut connection).await?;
 
+    let event = match (was_banned, player.player.base.banned) {
+        (false, true) => WebhookEvent::PlayerBanned,
+        (true, false) => WebhookEvent::PlayerUnbanned,
+        _ => WebhookEvent::PlayerModified,
+    };
+
+    delivery::dispatch(event, &player, false, &mut connection).await?;
+
     connection.commit().await?;
 
     Ok(HttpResponse::Ok().json_with_etag(&player))
 }
+
+#[get("/{player_id}/claims/")]
+pub async fn claims(TokenAuth(user): TokenAuth, state: PointercrateState, path: Path<i32>) -> ApiResult<HttpResponse> {
+    user.inner().require_permissions(Permissions::LeaderboardModerator)?;
+
+    let mut connection = state.connection().await?;
+
+    let player = DatabasePlayer::by_id(path.into_inner(), &mut connection).await?;
+    let claims = PlayerClaim::of_player(player.id, &mut connection).await?;
+
+    Ok(HttpResponse::Ok().json(claims))
+}
+
+#[post("/{player_id}/claims/")]
+pub async fn request_claim(TokenAuth(user): TokenAuth, state: PointercrateState, path: Path<i32>) -> ApiResult<HttpResponse> {
+    let mut connection = state.audited_transaction(&user).await?;
+
+    let player = DatabasePlayer::by_id(path.into_inner(), &mut connection).await?;
+    let claim = PlayerClaim::request(user.inner(), player, &mut connection).await?;
+
+    connection.commit().await?;
+
+    Ok(HttpResponse::Created()
+        .header("Location", format!("/api/v1/players/{}/claims/{}/", claim.player.id, claim.user.id))
+        .json_with_etag(&claim))
+}
+
+#[post("/{player_id}/claims/{user_id}/verify/")]
+pub async fn verify_claim(TokenAuth(user): TokenAuth, state: PointercrateState, ids: Path<(i32, i32)>) -> ApiResult<HttpResponse> {
+    user.inner().require_permissions(Permissions::LeaderboardModerator)?;
+
+    let mut connection = state.audited_transaction(&user).await?;
+
+    let (player_id, user_id) = ids.into_inner();
+
+    let mut claim = PlayerClaim::by_player_and_user(player_id, user_id, &mut connection).await?;
+
+    claim.verify(&mut connection).await?;
+
+    connection.commit().await?;
+
+    Ok(HttpResponse::Ok().json_with_etag(&claim))
+}
+
+#[delete("/{player_id}/claims/{user_id}/")]
+pub async fn delete_claim(TokenAuth(user): TokenAuth, state: PointercrateState, ids: Path<(i32, i32)>) -> ApiResult<HttpResponse> {
+    let (player_id, user_id) = ids.into_inner();
+
+    // Users can always withdraw their own claims
+    if user.inner().id != user_id {
+        user.inner().require_permissions(Permissions::LeaderboardModerator)?;
+    }
+
+    let mut connection = state.audited_transaction(&user).await?;
+
+    PlayerClaim::by_player_and_user(player_id, user_id, &mut connection)
+        .await?
+        .delete(&mut connection)
+        .await?;
+
+    connection.commit().await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
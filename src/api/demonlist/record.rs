@@ -2,24 +2,31 @@ use crate::{
     config,
     error::{JsonError, PointercrateError},
     extractor::{auth::TokenAuth, if_match::IfMatch, ip::Ip},
-    model::demonlist::{
-        record::{
-            audit,
-            note::{NewNote, Note, PatchNote},
-            FullRecord, PatchRecord, RecordPagination, RecordStatus, Submission,
+    model::{
+        demonlist::{
+            record::{
+                audit::{self, RecordAuditPagination},
+                note::{NewNote, Note, PatchNote},
+                BulkOperation, BulkOutcome, BulkPatch, FullRecord, PatchRecord, RecordPagination, RecordStatus, Submission,
+                SubmissionStatus, MAX_BULK_OPERATIONS,
+            },
+            submitter::Submitter,
         },
-        submitter::Submitter,
+        user::AuthenticatedUser,
+        webhook::{delivery, WebhookEvent},
     },
     permissions::Permissions,
     state::{audit_connection, PointercrateState},
     util::HttpResponseBuilderExt,
-    ApiResult,
+    ApiResult, Result,
 };
 use actix_web::{
     web::{Json, Path, Query},
     HttpResponse,
 };
 use actix_web_codegen::{delete, get, patch, post};
+use serde_json::json;
+use sqlx::{Connection, PgConnection};
 
 #[get("/")]
 pub async fn paginate(
@@ -27,17 +34,24 @@ pub async fn paginate(
 ) -> ApiResult<HttpResponse> {
     let mut connection = state.connection().await?;
 
-    // If the submitter or status fields are set (unless status is set to approved and submitter is
-    // unset, in which case, proceed), you need to be a list mod. If you aren't authenticated, we return
-    // a 401 UNAUTHORIZED, otherwise a 403 FORBIDDEN
+    // If the submitter, dead_video or status fields are set (unless status is set to approved and
+    // submitter is unset, in which case, proceed), you need to be a list mod. If you aren't
+    // authenticated, we return a 401 UNAUTHORIZED, otherwise a 403 FORBIDDEN
 
-    if pagination.submitter.is_some() {
+    if pagination.submitter.is_some() || pagination.dead_video.is_some() {
         match user {
             Ok(TokenAuth(ref user)) => user.inner().require_permissions(Permissions::ListModerator)?,
             Err(error) => return Err(error),
         }
     }
 
+    if pagination.reviewer.is_some() {
+        match user {
+            Ok(TokenAuth(ref user)) => user.inner().require_permissions(Permissions::ListHelper)?,
+            Err(error) => return Err(error),
+        }
+    }
+
     match user {
         Ok(TokenAuth(user)) if user.inner().extended_list_access() => (),
         Ok(TokenAuth(user)) => user.inner().require_permissions(Permissions::ExtendedAccess)?,
@@ -86,11 +100,27 @@ pub async fn submit(
         FullRecord::create_from(submitter, submission.into_inner(), &mut connection, None).await?
     };
 
+    let event = match record.status {
+        RecordStatus::Approved => Some(WebhookEvent::RecordApproved),
+        RecordStatus::Rejected => Some(WebhookEvent::RecordRejected),
+        _ if record.video.is_some() => Some(WebhookEvent::RecordSubmitted),
+        _ => None,
+    };
+
+    if let Some(event) = event {
+        // Messages about submissions are held back until the background task spawned below verified the
+        // video
+        delivery::dispatch(event, &record, record.status == RecordStatus::Submitted, &mut connection).await?;
+    }
+
+    let lookup_token = record.create_lookup_token(&mut connection).await?;
+
     connection.commit().await?;
 
     let response = HttpResponse::Created()
         .header("Location", format!("/api/v1/records/{}/", record.id))
-        .json_with_etag(&record);
+        .etag(&record)
+        .json(json!({"data": record, "lookup_token": lookup_token}));
 
     // spawn background task to validate record
     if record.status == RecordStatus::Submitted {
@@ -111,20 +141,54 @@ pub async fn get(user: ApiResult<TokenAuth>, state: PointercrateState, record_id
                 user.inner().require_permissions(Permissions::ExtendedAccess)?;
             }
             if !user.inner().has_permission(Permissions::ListHelper) {
-                record.notes.clear()
+                record.notes.clear();
+                record.assignee = None;
             }
         },
         _ => {
             if record.status != RecordStatus::Approved {
                 return Err(JsonError(PointercrateError::Unauthorized))
             }
-            record.notes.clear()
+            record.notes.clear();
+            record.assignee = None;
         },
     }
 
     Ok(HttpResponse::Ok().json_with_etag(&record))
 }
 
+#[get("/lookup/{token}/")]
+pub async fn lookup(state: PointercrateState, token: Path<String>) -> ApiResult<HttpResponse> {
+    let mut connection = state.connection().await?;
+    let submission = SubmissionStatus::by_token(&token.into_inner(), &mut connection).await?;
+
+    Ok(HttpResponse::Ok().json_with_etag(&submission))
+}
+
+#[get("/audit/")]
+pub async fn paginate_audit_log(
+    TokenAuth(user): TokenAuth, state: PointercrateState, mut pagination: Query<RecordAuditPagination>,
+) -> ApiResult<HttpResponse> {
+    user.inner().require_permissions(Permissions::ListModerator)?;
+
+    let mut connection = state.connection().await?;
+
+    let mut entries = pagination.page(&mut connection).await?;
+
+    let (max_id, min_id) = audit::extremal_audit_ids(&mut connection).await?;
+
+    pagination_response!(
+        "/api/v1/records/audit/",
+        entries,
+        pagination,
+        min_id,
+        max_id,
+        before_id,
+        after_id,
+        audit_id
+    )
+}
+
 #[get("/{record_id}/audit/")]
 pub async fn audit_log(TokenAuth(user): TokenAuth, state: PointercrateState, record_id: Path<i32>) -> ApiResult<HttpResponse> {
     let mut connection = state.connection().await?;
@@ -145,17 +209,119 @@ pub async fn audit_log(TokenAuth(user): TokenAuth, state: PointercrateState, rec
     }
 }
 
+#[post("/{record_id}/audit/{audit_id}/revert/")]
+pub async fn revert(TokenAuth(user): TokenAuth, state: PointercrateState, ids: Path<(i32, i32)>) -> ApiResult<HttpResponse> {
+    user.inner().require_permissions(Permissions::ListModerator)?;
+
+    let mut connection = state.audited_transaction(&user).await?;
+
+    let (record_id, audit_id) = ids.into_inner();
+
+    // If the record no longer exists, the entry has to be its deletion
+    let (record, old_status) = match FullRecord::by_id(record_id, &mut connection).await {
+        Ok(record) => {
+            let old_status = record.status;
+
+            (record.revert(audit_id, &mut connection).await?, Some(old_status))
+        },
+        Err(PointercrateError::ModelNotFound { .. }) => (FullRecord::restore(record_id, audit_id, &mut connection).await?, None),
+        Err(error) => return Err(error.into()),
+    };
+
+    let event = match record.status {
+        RecordStatus::Approved if old_status != Some(RecordStatus::Approved) => WebhookEvent::RecordApproved,
+        RecordStatus::Rejected if old_status != Some(RecordStatus::Rejected) => WebhookEvent::RecordRejected,
+        _ => WebhookEvent::RecordModified,
+    };
+
+    delivery::dispatch(event, &record, false, &mut connection).await?;
+
+    connection.commit().await?;
+
+    Ok(HttpResponse::Ok().json_with_etag(&record))
+}
+
 #[patch("/{record_id}/")]
 pub async fn patch(
     TokenAuth(user): TokenAuth, if_match: IfMatch, state: PointercrateState, record_id: Path<i32>, data: Json<PatchRecord>,
 ) -> ApiResult<HttpResponse> {
     let mut connection = state.audited_transaction(&user).await?;
 
-    //user.inner().require_permissions(Permissions::ListHelper)?;
-
     // FIXME: prevent lost updates by using SELECT ... FOR UPDATE
-    let mut record = FullRecord::by_id(record_id.into_inner(), &mut connection).await?;
+    let record = FullRecord::by_id(record_id.into_inner(), &mut connection).await?;
+    let record = patch_record(&user, record, &if_match, data.into_inner(), &mut connection).await?;
+
+    connection.commit().await?;
+
+    Ok(HttpResponse::Ok().json_with_etag(&record))
+}
+
+#[post("/bulk/")]
+pub async fn bulk_patch(TokenAuth(user): TokenAuth, state: PointercrateState, data: Json<BulkPatch>) -> ApiResult<HttpResponse> {
+    user.inner().require_permissions(Permissions::ListHelper)?;
+
+    let BulkPatch { operations, atomic } = data.into_inner();
 
+    if operations.len() > MAX_BULK_OPERATIONS {
+        return Err(JsonError(PointercrateError::BadRequest {
+            message: format!("At most {} records can be modified in a single request", MAX_BULK_OPERATIONS),
+        }))
+    }
+
+    let mut connection = state.audited_transaction(&user).await?;
+    let mut outcomes = Vec::new();
+    let mut failed = false;
+
+    for BulkOperation { id, etag, patch } in operations {
+        if failed && atomic {
+            outcomes.push(BulkOutcome::Skipped { id });
+
+            continue
+        }
+
+        // Each operation runs in its own savepoint, so that a failing operation doesn't leave behind
+        // half of its changes
+        let mut savepoint = connection.begin().await?;
+
+        let result = match etag.parse::<IfMatch>() {
+            Ok(if_match) =>
+                match FullRecord::by_id(id, &mut savepoint).await {
+                    Ok(record) => patch_record(&user, record, &if_match, patch, &mut savepoint).await,
+                    Err(error) => Err(error),
+                },
+            Err(error) => Err(error),
+        };
+
+        match result {
+            Ok(record) => {
+                savepoint.commit().await?;
+                outcomes.push(BulkOutcome::applied(record));
+            },
+            Err(error) => {
+                savepoint.rollback().await?;
+                outcomes.push(BulkOutcome::failed(id, error));
+                failed = true;
+            },
+        }
+    }
+
+    if failed && atomic {
+        connection.rollback().await?;
+        outcomes = outcomes.into_iter().map(BulkOutcome::roll_back).collect();
+    } else {
+        connection.commit().await?;
+    }
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "data": outcomes })))
+}
+
+/// Applies the given patch to the given record on behalf of the given user, after checking they
+/// have the permissions needed for doing so
+///
+/// Also dispatches the webhook messages about the modification. Must be called inside a transaction
+async fn patch_record(
+    user: &AuthenticatedUser, record: FullRecord, if_match: &IfMatch, mut data: PatchRecord, connection: &mut PgConnection,
+) -> Result<FullRecord> {
     if record.demon.position > config::extended_list_size() {
         // only list mods can modify legacy records
         user.inner().require_permissions(Permissions::ListModerator)?;
@@ -165,15 +331,46 @@ pub async fn patch(
 
     if_match.require_etag_match(&record)?;
 
-    record = record.apply_patch(data.into_inner(), &mut connection).await?;
+    // Putting a record under consideration means someone is now taking a closer look at it, so unless
+    // it was explicitly assigned to someone else, the user doing so claims it
+    if data.status == Some(RecordStatus::UnderConsideration) && data.assignee.is_none() && record.assignee.is_none() {
+        data.assignee = Some(Some(user.inner().id));
+    }
 
-    connection.commit().await?;
+    if let Some(ref assignee) = data.assignee {
+        // Only list mods can take over records claimed by other helpers, or assign records to someone
+        // other than themselves
+        if !user.inner().has_permission(Permissions::ListModerator) {
+            if let Some(ref current) = record.assignee {
+                if current.id != user.inner().id {
+                    return Err(PointercrateError::RecordAssigned {
+                        id: current.id,
+                        name: current.name.clone(),
+                    })
+                }
+            }
 
-    let response = HttpResponse::Ok().json_with_etag(&record);
+            if let Some(assignee) = assignee {
+                if *assignee != user.inner().id {
+                    user.inner().require_permissions(Permissions::ListModerator)?;
+                }
+            }
+        }
+    }
 
-    actix_rt::spawn(record.edit_webhook(state));
+    let old_status = record.status;
 
-    Ok(response)
+    let record = record.apply_patch(data, &mut *connection).await?;
+
+    let event = match record.status {
+        RecordStatus::Approved if old_status != RecordStatus::Approved => WebhookEvent::RecordApproved,
+        RecordStatus::Rejected if old_status != RecordStatus::Rejected => WebhookEvent::RecordRejected,
+        _ => WebhookEvent::RecordModified,
+    };
+
+    delivery::dispatch(event, &record, false, connection).await?;
+
+    Ok(record)
 }
 
 #[delete("/{record_id}/")]
@@ -204,6 +401,8 @@ pub async fn delete(
 
     if_match.require_etag_match(&record)?;
 
+    delivery::dispatch(WebhookEvent::RecordDeleted, &record, false, &mut connection).await?;
+
     record.delete(&mut connection).await?;
 
     connection.commit().await?;
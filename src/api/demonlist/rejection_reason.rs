@@ -0,0 +1,82 @@
+use crate::{
+    extractor::{auth::TokenAuth, if_match::IfMatch},
+    model::demonlist::rejection_reason::{PatchRejectionReason, PostRejectionReason, RejectionReason},
+    permissions::Permissions,
+    state::PointercrateState,
+    util::HttpResponseBuilderExt,
+    ApiResult,
+};
+use actix_web::{
+    web::{Json, Path},
+    HttpResponse,
+};
+use actix_web_codegen::{delete, get, patch, post};
+
+#[get("/")]
+pub async fn list(state: PointercrateState) -> ApiResult<HttpResponse> {
+    let mut connection = state.connection().await?;
+
+    Ok(HttpResponse::Ok().json(RejectionReason::all(&mut connection).await?))
+}
+
+#[post("/")]
+pub async fn post(TokenAuth(user): TokenAuth, state: PointercrateState, data: Json<PostRejectionReason>) -> ApiResult<HttpResponse> {
+    user.inner().require_permissions(Permissions::ListAdministrator)?;
+
+    let mut connection = state.audited_transaction(&user).await?;
+
+    let reason = RejectionReason::create_from(data.into_inner(), &mut connection).await?;
+
+    connection.commit().await?;
+
+    Ok(HttpResponse::Created()
+        .header("Location", format!("/api/v1/rejection-reasons/{}/", reason.id))
+        .json_with_etag(&reason))
+}
+
+#[get("/{reason_id}/")]
+pub async fn get(state: PointercrateState, reason_id: Path<i32>) -> ApiResult<HttpResponse> {
+    let mut connection = state.connection().await?;
+
+    let reason = RejectionReason::by_id(reason_id.into_inner(), &mut connection).await?;
+
+    Ok(HttpResponse::Ok().json_with_etag(&reason))
+}
+
+#[patch("/{reason_id}/")]
+pub async fn patch(
+    TokenAuth(user): TokenAuth, if_match: IfMatch, state: PointercrateState, reason_id: Path<i32>, data: Json<PatchRejectionReason>,
+) -> ApiResult<HttpResponse> {
+    user.inner().require_permissions(Permissions::ListAdministrator)?;
+
+    let mut connection = state.audited_transaction(&user).await?;
+
+    let reason = RejectionReason::by_id(reason_id.into_inner(), &mut connection).await?;
+
+    if_match.require_etag_match(&reason)?;
+
+    let reason = reason.apply_patch(data.into_inner(), &mut connection).await?;
+
+    connection.commit().await?;
+
+    Ok(HttpResponse::Ok().json_with_etag(&reason))
+}
+
+#[delete("/{reason_id}/")]
+pub async fn delete(
+    TokenAuth(user): TokenAuth, if_match: IfMatch, state: PointercrateState, reason_id: Path<i32>,
+) -> ApiResult<HttpResponse> {
+    user.inner().require_permissions(Permissions::ListAdministrator)?;
+
+    let mut connection = state.audited_transaction(&user).await?;
+
+    let reason = RejectionReason::by_id(reason_id.into_inner(), &mut connection).await?;
+
+    if_match.require_etag_match(&reason)?;
+
+    reason.delete(&mut connection).await?;
+
+    connection.commit().await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,97 @@
+use crate::{
+    extractor::auth::TokenAuth,
+    model::demonlist::{
+        player::{RankedPlayer, RankingPagination},
+        scoring_formula::{PostScoringFormula, ScoringFormula},
+    },
+    permissions::Permissions,
+    state::PointercrateState,
+    util::HttpResponseBuilderExt,
+    ApiResult,
+};
+use actix_web::{
+    web::{Json, Path, Query},
+    HttpResponse,
+};
+use actix_web_codegen::{get, post};
+
+#[get("/")]
+pub async fn list(state: PointercrateState) -> ApiResult<HttpResponse> {
+    let mut connection = state.connection().await?;
+
+    Ok(HttpResponse::Ok().json(ScoringFormula::all(&mut connection).await?))
+}
+
+#[post("/")]
+pub async fn post(TokenAuth(user): TokenAuth, state: PointercrateState, data: Json<PostScoringFormula>) -> ApiResult<HttpResponse> {
+    user.inner().require_permissions(Permissions::ListAdministrator)?;
+
+    let mut connection = state.audited_transaction(&user).await?;
+
+    let formula = ScoringFormula::create_from(data.into_inner(), user.inner().id, &mut connection).await?;
+
+    connection.commit().await?;
+
+    Ok(HttpResponse::Created()
+        .header("Location", format!("/api/v1/scoring-formulas/{}/", formula.id))
+        .json_with_etag(&formula))
+}
+
+#[get("/active/")]
+pub async fn active(state: PointercrateState) -> ApiResult<HttpResponse> {
+    let mut connection = state.connection().await?;
+
+    let formula = ScoringFormula::active(&mut connection).await?;
+
+    Ok(HttpResponse::Ok().json_with_etag(&formula))
+}
+
+#[get("/{formula_id}/")]
+pub async fn get(state: PointercrateState, formula_id: Path<i32>) -> ApiResult<HttpResponse> {
+    let mut connection = state.connection().await?;
+
+    let formula = ScoringFormula::by_id(formula_id.into_inner(), &mut connection).await?;
+
+    Ok(HttpResponse::Ok().json_with_etag(&formula))
+}
+
+#[get("/{formula_id}/ranking/")]
+pub async fn ranking(
+    TokenAuth(user): TokenAuth, state: PointercrateState, formula_id: Path<i32>, mut pagination: Query<RankingPagination>,
+) -> ApiResult<HttpResponse> {
+    user.inner().require_permissions(Permissions::ListAdministrator)?;
+
+    let mut connection = state.connection().await?;
+
+    let formula = ScoringFormula::by_id(formula_id.into_inner(), &mut connection).await?;
+
+    pagination.formula = Some(formula.id);
+
+    let mut players = pagination.page(&mut connection).await?;
+    let max_index = RankedPlayer::max_index_under(formula.id, &mut connection).await?;
+
+    pagination_response!(
+        format!("/api/v1/scoring-formulas/{}/ranking/", formula.id),
+        players,
+        pagination,
+        1,
+        max_index,
+        before_index,
+        after_index,
+        index
+    )
+}
+
+#[post("/{formula_id}/activate/")]
+pub async fn activate(TokenAuth(user): TokenAuth, state: PointercrateState, formula_id: Path<i32>) -> ApiResult<HttpResponse> {
+    user.inner().require_permissions(Permissions::ListAdministrator)?;
+
+    let mut connection = state.audited_transaction(&user).await?;
+
+    let formula = ScoringFormula::by_id(formula_id.into_inner(), &mut connection).await?;
+    let formula = formula.activate(user.inner().id, &mut connection).await?;
+
+    connection.commit().await?;
+
+    Ok(HttpResponse::Ok().json_with_etag(&formula))
+}
@@ -1,6 +1,9 @@
 use crate::{
     extractor::{auth::TokenAuth, if_match::IfMatch},
-    model::demonlist::submitter::{PatchSubmitter, Submitter, SubmitterPagination},
+    model::{
+        demonlist::submitter::{PatchSubmitter, Submitter, SubmitterPagination},
+        webhook::{delivery, WebhookEvent},
+    },
     permissions::Permissions,
     state::PointercrateState,
     util::HttpResponseBuilderExt,
@@ -59,8 +62,16 @@ pub async fn patch(
 
     if_match.require_etag_match(&submitter)?;
 
+    let was_banned = submitter.banned;
+
     let submitter = submitter.apply_patch(patch.into_inner(), &mut connection).await?;
 
+    match (was_banned, submitter.banned) {
+        (false, true) => delivery::dispatch(WebhookEvent::SubmitterBanned, &submitter, false, &mut connection).await?,
+        (true, false) => delivery::dispatch(WebhookEvent::SubmitterUnbanned, &submitter, false, &mut connection).await?,
+        _ => (),
+    }
+
     connection.commit().await?;
 
     Ok(HttpResponse::Ok().json_with_etag(&submitter))
@@ -0,0 +1,69 @@
+use crate::{
+    cistring::CiStr,
+    model::nationality::{HeatmapOptions, NationAggregate, NationRankingPagination, NationalityStatistics, RankedNation},
+    state::PointercrateState,
+    ApiResult,
+};
+use actix_web::{
+    web::{Path, Query},
+    HttpResponse,
+};
+use actix_web_codegen::get;
+use serde_json::json;
+
+#[get("/")]
+pub async fn list(state: PointercrateState) -> ApiResult<HttpResponse> {
+    let mut connection = state.connection().await?;
+
+    Ok(HttpResponse::Ok().json(NationalityStatistics::all(&mut connection).await?))
+}
+
+#[get("/heatmap/")]
+pub async fn heatmap(state: PointercrateState, options: Query<HeatmapOptions>) -> ApiResult<HttpResponse> {
+    let mut connection = state.connection().await?;
+
+    Ok(HttpResponse::Ok().json(NationAggregate::all(&options, &mut connection).await?))
+}
+
+#[get("/{code}/")]
+pub async fn get(state: PointercrateState, code: Path<String>) -> ApiResult<HttpResponse> {
+    let mut connection = state.connection().await?;
+
+    let nationality = NationalityStatistics::by_country_code_or_name(CiStr::from_str(&code.into_inner()), &mut connection)
+        .await?
+        .upgrade(&mut connection)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(json!({ "data": nationality })))
+}
+
+#[get("/ranking/")]
+pub async fn ranking(state: PointercrateState, mut pagination: Query<NationRankingPagination>) -> ApiResult<HttpResponse> {
+    let mut connection = state.connection().await?;
+
+    let mut nations = pagination.page(&mut connection).await?;
+    let max_index = RankedNation::max_index(&mut connection).await?;
+
+    pagination_response!(
+        "/api/v1/nationalities/ranking/",
+        nations,
+        pagination,
+        1,
+        max_index,
+        before_index,
+        after_index,
+        index
+    )
+}
+
+#[get("/ranking/{code}/")]
+pub async fn ranked_nation(state: PointercrateState, code: Path<String>) -> ApiResult<HttpResponse> {
+    let mut connection = state.connection().await?;
+
+    let nation = RankedNation::by_country_code_or_name(CiStr::from_str(&code.into_inner()), &mut connection)
+        .await?
+        .upgrade(&mut connection)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(json!({ "data": nation })))
+}
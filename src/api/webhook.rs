@@ -0,0 +1,141 @@
+use crate::{
+    extractor::{auth::TokenAuth, if_match::IfMatch},
+    model::webhook::{
+        delivery::{DeliveryPagination, WebhookDelivery},
+        PatchWebhook, PostWebhook, Webhook,
+    },
+    permissions::Permissions,
+    state::PointercrateState,
+    util::HttpResponseBuilderExt,
+    ApiResult,
+};
+use actix_web::{
+    web::{Json, Path, Query},
+    HttpResponse,
+};
+use actix_web_codegen::{delete, get, patch, post};
+
+#[get("/")]
+pub async fn list(TokenAuth(user): TokenAuth, state: PointercrateState) -> ApiResult<HttpResponse> {
+    user.inner().require_permissions(Permissions::ListAdministrator)?;
+
+    let mut connection = state.connection().await?;
+
+    let webhooks = Webhook::all(&mut connection)
+        .await?
+        .into_iter()
+        .map(|webhook| webhook.with_signing_key(&state.secret))
+        .collect::<Vec<_>>();
+
+    Ok(HttpResponse::Ok().json(webhooks))
+}
+
+#[post("/")]
+pub async fn post(TokenAuth(user): TokenAuth, state: PointercrateState, data: Json<PostWebhook>) -> ApiResult<HttpResponse> {
+    user.inner().require_permissions(Permissions::ListAdministrator)?;
+
+    let mut connection = state.transaction().await?;
+
+    let webhook = Webhook::create_from(data.into_inner(), &mut connection)
+        .await?
+        .with_signing_key(&state.secret);
+
+    connection.commit().await?;
+
+    Ok(HttpResponse::Created()
+        .header("Location", format!("/api/v1/webhooks/{}/", webhook.id))
+        .json_with_etag(&webhook))
+}
+
+#[get("/{webhook_id}/")]
+pub async fn get(TokenAuth(user): TokenAuth, state: PointercrateState, webhook_id: Path<i32>) -> ApiResult<HttpResponse> {
+    user.inner().require_permissions(Permissions::ListAdministrator)?;
+
+    let mut connection = state.connection().await?;
+
+    let webhook = Webhook::by_id(webhook_id.into_inner(), &mut connection)
+        .await?
+        .with_signing_key(&state.secret);
+
+    Ok(HttpResponse::Ok().json_with_etag(&webhook))
+}
+
+#[patch("/{webhook_id}/")]
+pub async fn patch(
+    TokenAuth(user): TokenAuth, if_match: IfMatch, state: PointercrateState, webhook_id: Path<i32>, data: Json<PatchWebhook>,
+) -> ApiResult<HttpResponse> {
+    user.inner().require_permissions(Permissions::ListAdministrator)?;
+
+    let mut connection = state.transaction().await?;
+
+    let webhook = Webhook::by_id(webhook_id.into_inner(), &mut connection)
+        .await?
+        .with_signing_key(&state.secret);
+
+    if_match.require_etag_match(&webhook)?;
+
+    let webhook = webhook
+        .apply_patch(data.into_inner(), &mut connection)
+        .await?
+        .with_signing_key(&state.secret);
+
+    connection.commit().await?;
+
+    Ok(HttpResponse::Ok().json_with_etag(&webhook))
+}
+
+#[delete("/{webhook_id}/")]
+pub async fn delete(
+    TokenAuth(user): TokenAuth, if_match: IfMatch, state: PointercrateState, webhook_id: Path<i32>,
+) -> ApiResult<HttpResponse> {
+    user.inner().require_permissions(Permissions::ListAdministrator)?;
+
+    let mut connection = state.transaction().await?;
+
+    let webhook = Webhook::by_id(webhook_id.into_inner(), &mut connection)
+        .await?
+        .with_signing_key(&state.secret);
+
+    if_match.require_etag_match(&webhook)?;
+
+    webhook.delete(&mut connection).await?;
+
+    connection.commit().await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[get("/deliveries/")]
+pub async fn paginate_deliveries(
+    TokenAuth(user): TokenAuth, state: PointercrateState, mut pagination: Query<DeliveryPagination>,
+) -> ApiResult<HttpResponse> {
+    user.inner().require_permissions(Permissions::ListAdministrator)?;
+
+    let mut connection = state.connection().await?;
+
+    let mut deliveries = pagination.page(&mut connection).await?;
+
+    let (max_id, min_id) = WebhookDelivery::extremal_delivery_ids(&mut connection).await?;
+
+    pagination_response!(
+        "/api/v1/webhooks/deliveries/",
+        deliveries,
+        pagination,
+        min_id,
+        max_id,
+        before_id,
+        after_id,
+        id
+    )
+}
+
+#[get("/deliveries/{delivery_id}/")]
+pub async fn get_delivery(TokenAuth(user): TokenAuth, state: PointercrateState, delivery_id: Path<i32>) -> ApiResult<HttpResponse> {
+    user.inner().require_permissions(Permissions::ListAdministrator)?;
+
+    let mut connection = state.connection().await?;
+
+    let delivery = WebhookDelivery::by_id(delivery_id.into_inner(), &mut connection).await?;
+
+    Ok(HttpResponse::Ok().json_with_etag(&delivery))
+}
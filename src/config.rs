@@ -48,3 +48,19 @@ pub fn port() -> u16 {
 pub fn database_url() -> String {
     std::env::var("DATABASE_URL").expect("DATABASE_URL is not set")
 }
+
+/// The number of days after which the videos of approved records and demons are checked again
+pub fn video_recheck_interval() -> i32 {
+    from_env_or_default("VIDEO_RECHECK_INTERVAL", 7)
+}
+
+/// The number of hours for which we retry verifying a submission's video before flagging it for
+/// manual review
+pub fn video_verification_window() -> i32 {
+    from_env_or_default("VIDEO_VERIFICATION_WINDOW", 24)
+}
+
+/// The number of hours after which a list helper's claim on a record is automatically released
+pub fn record_assignment_timeout() -> i32 {
+    from_env_or_default("RECORD_ASSIGNMENT_TIMEOUT", 24)
+}
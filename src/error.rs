@@ -1,7 +1,7 @@
 //! Moduling containing the [`PointercrateError`] enum.
 
 use crate::{
-    model::demonlist::{demon::MinimalDemon, record::RecordStatus},
+    model::demonlist::{demon::MinimalDemon, record::RecordStatus, video_key::VideoConflict},
     permissions::Permissions,
     ratelimit::RatelimitScope,
     util::preferred_mime_type,
@@ -119,6 +119,13 @@ pub enum PointercrateError {
     #[display(fmt = "You are banned from submitting records to the demonlist!")]
     BannedFromSubmissions,
 
+    /// `403 FORBIDDEN` error returned if a user tries to modify the player they claimed before
+    /// their claim was verified
+    ///
+    /// Error Code `40305`
+    #[display(fmt = "Your claim on this player has not been verified yet")]
+    ClaimNotVerified,
+
     /// `404 NOT FOUND`
     ///
     /// Error Code `40400`
@@ -186,11 +193,56 @@ pub enum PointercrateError {
     #[display(fmt = "This player is already registered as a creator on this demon")]
     CreatorExists,
 
-    /// `409 CONFLICT` variant
+    /// `409 CONFLICT` error returned if someone tries to add a video to a record or demon that is
+    /// already used by a different record or demon
     ///
     /// Error Code `40906`
-    #[display(fmt = "This video is already used by record #{}", id)]
-    DuplicateVideo { id: i32 },
+    #[display(fmt = "This video is already used by {}", conflict)]
+    DuplicateVideo {
+        /// The object already using the video
+        conflict: VideoConflict,
+    },
+
+    /// `409 CONFLICT` error returned if a list helper tries to claim a record that is already
+    /// being reviewed by someone else
+    ///
+    /// Error Code `40907`
+    #[display(fmt = "This record is already being reviewed by {}", name)]
+    RecordAssigned {
+        /// The ID of the [`User`] the record is assigned to
+        id: i32,
+
+        /// The name of the [`User`] the record is assigned to
+        name: String,
+    },
+
+    /// `409 CONFLICT` error returned if someone tries to undo a player merge that has already been
+    /// undone
+    ///
+    /// Error Code `40908`
+    #[display(fmt = "This merge has already been undone")]
+    MergeAlreadyUndone,
+
+    /// `409 CONFLICT` error returned if someone tries to claim a player, or verify a claim on a
+    /// player, that has already been claimed by a different user
+    ///
+    /// Error Code `40909`
+    #[display(fmt = "This player has already been claimed by a different user")]
+    PlayerClaimed,
+
+    /// `409 CONFLICT` error returned if a user whose claim on a player has been verified tries to
+    /// claim a different player
+    ///
+    /// Error Code `40910`
+    #[display(fmt = "You have already claimed a different player")]
+    ClaimExists,
+
+    /// `409 CONFLICT` error returned if someone tries to add a rejection reason with a name that's
+    /// already used by a different reason
+    ///
+    /// Error Code `40911`
+    #[display(fmt = "A rejection reason with this name already exists")]
+    RejectionReasonExists,
 
     /// `411 LENGTH REQUIRED`
     ///
@@ -379,6 +431,60 @@ pub enum PointercrateError {
     #[display(fmt = "Notes mustn't be empty!")]
     NoteEmpty,
 
+    /// `422 UNPROCESSABLE ENTITY` variant
+    ///
+    /// Error Code `42231`
+    #[display(fmt = "Records can only be assigned to users with LIST_HELPER permissions")]
+    InvalidAssignee,
+
+    /// `422 UNPROCESSABLE ENTITY` variant
+    ///
+    /// Error Code `42232`
+    #[display(fmt = "A rejection reason can only be given for rejected records")]
+    ReasonWithoutRejection,
+
+    /// `422 UNPROCESSABLE ENTITY` variant returned if someone tries to revert an audit log entry
+    /// that isn't a modification or deletion of a record
+    ///
+    /// Error Code `42233`
+    #[display(fmt = "Only modifications and deletions of records can be reverted")]
+    IrrevertibleEntry,
+
+    /// `422 UNPROCESSABLE ENTITY` variant returned if someone tries to restore a record that was
+    /// deleted before the submitters of deleted records were logged
+    ///
+    /// Error Code `42234`
+    #[display(fmt = "The submitter of this deleted record is unknown, so it cannot be restored")]
+    SubmitterUnknown,
+
+    /// `422 UNPROCESSABLE ENTITY` variant returned if someone tries to undo a player merge that
+    /// happened before the information required for undoing merges was logged
+    ///
+    /// Error Code `42235`
+    #[display(fmt = "This merge happened before merges could be undone")]
+    IrreversibleMerge,
+
+    /// `422 UNPROCESSABLE ENTITY` variant returned if the parameters of a new scoring formula are
+    /// out of range
+    ///
+    /// Error Code `42236`
+    #[display(fmt = "Invalid scoring formula: {}", reason)]
+    InvalidScoringFormula { reason: &'static str },
+
+    /// `422 UNPROCESSABLE ENTITY` variant returned if someone tries to set the subdivision of a
+    /// player without a nationality
+    ///
+    /// Error Code `42237`
+    #[display(fmt = "A subdivision can only be set for players with a nationality")]
+    SubdivisionWithoutNationality,
+
+    /// `422 UNPROCESSABLE ENTITY` variant returned if the level range requested for the nation
+    /// heatmap is empty or out of bounds
+    ///
+    /// Error Code `42238`
+    #[display(fmt = "The heatmap levels need to satisfy 0 <= low_level <= high_level <= 1000")]
+    InvalidHeatmapLevels,
+
     /// `428 PRECONDITION REQUIRED`
     ///
     /// Error Code `42800`
@@ -462,6 +568,7 @@ impl PointercrateError {
             PointercrateError::DeleteSelf => 40302,
             PointercrateError::PatchSelf => 40303,
             PointercrateError::BannedFromSubmissions => 40304,
+            PointercrateError::ClaimNotVerified => 40305,
 
             PointercrateError::NotFound => 40400,
             PointercrateError::ModelNotFound { .. } => 40401,
@@ -473,6 +580,11 @@ impl PointercrateError {
             PointercrateError::DemonExists { .. } => 40904,
             PointercrateError::CreatorExists => 40905,
             PointercrateError::DuplicateVideo { .. } => 40906,
+            PointercrateError::RecordAssigned { .. } => 40907,
+            PointercrateError::MergeAlreadyUndone => 40908,
+            PointercrateError::PlayerClaimed => 40909,
+            PointercrateError::ClaimExists => 40910,
+            PointercrateError::RejectionReasonExists => 40911,
 
             PointercrateError::LengthRequired => 41100,
 
@@ -503,6 +615,14 @@ impl PointercrateError {
             PointercrateError::DemonNameNotUnique { .. } => 42228,
             PointercrateError::MutuallyExclusive => 42229,
             PointercrateError::NoteEmpty => 42230,
+            PointercrateError::InvalidAssignee => 42231,
+            PointercrateError::ReasonWithoutRejection => 42232,
+            PointercrateError::IrrevertibleEntry => 42233,
+            PointercrateError::SubmitterUnknown => 42234,
+            PointercrateError::IrreversibleMerge => 42235,
+            PointercrateError::InvalidScoringFormula { .. } => 42236,
+            PointercrateError::SubdivisionWithoutNationality => 42237,
+            PointercrateError::InvalidHeatmapLevels => 42238,
 
             PointercrateError::PreconditionRequired => 42800,
 
@@ -1,14 +1,14 @@
-use crate::error::{JsonError, PointercrateError};
+use crate::{
+    error::{JsonError, PointercrateError},
+    util,
+};
 use actix_web::{
     dev::{Payload, PayloadStream},
     FromRequest, HttpRequest,
 };
 use derive_more::Display;
 use futures::future::{err, ready, Ready};
-use std::{
-    collections::hash_map::DefaultHasher,
-    hash::{Hash, Hasher},
-};
+use std::{hash::Hash, str::FromStr};
 
 #[derive(Debug, Display)]
 #[display(fmt = "'object hash equal to any of {:?}'", _0)]
@@ -16,10 +16,7 @@ pub struct IfMatch(Vec<u64>);
 
 impl IfMatch {
     pub fn require_etag_match<H: Hash>(&self, h: &H) -> Result<(), PointercrateError> {
-        let mut hasher = DefaultHasher::new();
-        h.hash(&mut hasher);
-
-        if self.0.contains(&hasher.finish()) {
+        if self.0.contains(&util::etag(h)) {
             Ok(())
         } else {
             Err(PointercrateError::PreconditionFailed)
@@ -42,16 +39,20 @@ impl FromRequest for IfMatch {
             None => return err(PointercrateError::PreconditionRequired.into()),
         };
 
-        ready(
-            header
-                .split(',')
-                .map(|hash| {
-                    hash.parse()
-                        .map_err(|_| PointercrateError::InvalidHeaderValue { header: "If-Match" })
-                })
-                .collect::<Result<_, _>>()
-                .map(IfMatch)
-                .map_err(JsonError),
-        )
+        ready(header.parse().map_err(JsonError))
+    }
+}
+
+impl FromStr for IfMatch {
+    type Err = PointercrateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(|hash| {
+                hash.parse()
+                    .map_err(|_| PointercrateError::InvalidHeaderValue { header: "If-Match" })
+            })
+            .collect::<Result<_, _>>()
+            .map(IfMatch)
     }
 }
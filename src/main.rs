@@ -16,9 +16,9 @@ use actix_web::{
     App, HttpRequest, HttpServer,
 };
 use api::{
-    auth,
-    demonlist::{demon, misc, player, record, submitter},
-    user,
+    audit, auth,
+    demonlist::{demon, misc, player, record, rejection_reason, scoring_formula, submitter},
+    nationality, user, webhook,
 };
 use std::net::SocketAddr;
 
@@ -54,6 +54,13 @@ async fn main() -> std::io::Result<()> {
 
     let application_state = PointercrateState::initialize().await;
 
+    actix_rt::spawn(model::webhook::delivery::deliver_webhooks(application_state.clone()));
+    actix_rt::spawn(model::demonlist::video_check::recheck_videos(application_state.clone()));
+    actix_rt::spawn(model::demonlist::record::verify_videos(application_state.clone()));
+    actix_rt::spawn(model::demonlist::record::release_stale_assignments(application_state.clone()));
+    actix_rt::spawn(model::demonlist::video_key::backfill_video_keys(application_state.clone()));
+    actix_rt::spawn(model::demonlist::player::history::snapshot_ranking(application_state.clone()));
+
     HttpServer::new(move || {
         let json_config =
             JsonConfig::default().error_handler(|error, request| PointercrateError::from(error).dynamic(request.headers()).into());
@@ -86,6 +93,7 @@ async fn main() -> std::io::Result<()> {
             .service(view::login::register)
             .service(view::demonlist::demon_permalink)
             .service(view::demonlist::stats_viewer2)
+            .service(view::demonlist::submission_status)
             .service(view::demonlist::page)
             .service(view::demonlist::index)
             .service(view::account::index)
@@ -103,8 +111,12 @@ async fn main() -> std::io::Result<()> {
                             .service(auth::get_me)
                             .service(auth::invalidate)
                             .service(auth::login)
-                            .service(auth::patch_me),
+                            .service(auth::patch_me)
+                            .service(auth::get_me_player)
+                            .service(auth::patch_me_player)
+                            .service(auth::delete_me_player),
                     )
+                    .service(scope("/audit").service(audit::paginate))
                     .service(
                         scope("/users")
                             .service(user::paginate)
@@ -130,21 +142,69 @@ async fn main() -> std::io::Result<()> {
                     .service(
                         scope("/records")
                             .service(record::delete)
+                            // must be registered before record::get, as '/audit/' would otherwise be parsed as a record ID
+                            .service(record::paginate_audit_log)
                             .service(record::get)
+                            .service(record::lookup)
                             .service(record::paginate)
                             .service(record::patch)
+                            .service(record::bulk_patch)
                             .service(record::submit)
                             .service(record::add_note)
                             .service(record::patch_note)
                             .service(record::delete_note)
-                            .service(record::audit_log),
+                            .service(record::audit_log)
+                            .service(record::revert),
                     )
                     .service(
                         scope("/players")
                             .service(player::patch)
                             .service(player::paginate)
                             .service(player::ranking)
-                            .service(player::get),
+                            .service(player::get)
+                            .service(player::history)
+                            .service(player::changes)
+                            .service(player::merge_preview)
+                            .service(player::split)
+                            .service(player::claims)
+                            .service(player::request_claim)
+                            .service(player::verify_claim)
+                            .service(player::delete_claim),
+                    )
+                    .service(
+                        scope("/nationalities")
+                            .service(nationality::list)
+                            .service(nationality::heatmap)
+                            .service(nationality::ranking)
+                            .service(nationality::ranked_nation)
+                            .service(nationality::get),
+                    )
+                    .service(
+                        scope("/webhooks")
+                            .service(webhook::list)
+                            .service(webhook::post)
+                            .service(webhook::paginate_deliveries)
+                            .service(webhook::get_delivery)
+                            .service(webhook::get)
+                            .service(webhook::patch)
+                            .service(webhook::delete),
+                    )
+                    .service(
+                        scope("/rejection-reasons")
+                            .service(rejection_reason::list)
+                            .service(rejection_reason::post)
+                            .service(rejection_reason::get)
+                            .service(rejection_reason::patch)
+                            .service(rejection_reason::delete),
+                    )
+                    .service(
+                        scope("/scoring-formulas")
+                            .service(scoring_formula::list)
+                            .service(scoring_formula::post)
+                            .service(scoring_formula::active)
+                            .service(scoring_formula::get)
+                            .service(scoring_formula::ranking)
+                            .service(scoring_formula::activate),
                     ),
             )
             .service(
@@ -152,6 +212,7 @@ async fn main() -> std::io::Result<()> {
                     scope("/demons")
                         .service(demon::v2::paginate_listed)
                         .service(demon::v2::get)
+                        .service(demon::v2::audit_log)
                         .service(demon::v2::paginate)
                         .service(demon::v2::patch)
                         .service(demon::v2::delete_creator)
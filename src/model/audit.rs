@@ -0,0 +1,223 @@
+//! Module containing the global audit log
+//!
+//! All changes to records, notes, demons, creators, players, player claims, submitters and users
+//! are logged by database triggers into a set of tables inheriting from `audit_log2`. This module
+//! merges all of them into a single, chronological log (ordered by the `audit_id` shared among all
+//! these tables), which administrators can filter by the acting user, the kind and ID of the object
+//! affected and the time of the change.
+//!
+//! Object specific logs with more structured entries exist as well, see for instance
+//! [`record::audit`](crate::model::demonlist::record::audit).
+
+use crate::{error::PointercrateError, util::non_nullable, Result};
+use chrono::NaiveDateTime;
+use futures::StreamExt;
+use log::error;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{postgres::PgRow, PgConnection, Row};
+
+/// The kind of object an audit log entry is about
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditObject {
+    Record,
+    RecordNote,
+    Demon,
+
+    /// Entries about the creators of a demon. The object ID of such entries is the ID of the demon
+    Creator,
+    Player,
+
+    /// Entries about claims on a player. The object ID of such entries is the ID of the (newly)
+    /// claimed player
+    PlayerClaim,
+    Submitter,
+    User,
+}
+
+impl AuditObject {
+    fn to_sql(&self) -> &'static str {
+        match self {
+            AuditObject::Record => "record",
+            AuditObject::RecordNote => "record_note",
+            AuditObject::Demon => "demon",
+            AuditObject::Creator => "creator",
+            AuditObject::Player => "player",
+            AuditObject::PlayerClaim => "player_claim",
+            AuditObject::Submitter => "submitter",
+            AuditObject::User => "user",
+        }
+    }
+
+    fn from_sql(sql: &str) -> Self {
+        match sql {
+            "record" => AuditObject::Record,
+            "record_note" => AuditObject::RecordNote,
+            "demon" => AuditObject::Demon,
+            "creator" => AuditObject::Creator,
+            "player" => AuditObject::Player,
+            "player_claim" => AuditObject::PlayerClaim,
+            "submitter" => AuditObject::Submitter,
+            "user" => AuditObject::User,
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Addition,
+    Modification,
+    Deletion,
+
+    /// A check of a record's video performed by the server
+    VideoProbe,
+
+    /// The rejection reason of a record was set, changed or removed
+    RejectionReasonChange,
+
+    /// Another player was merged into a player
+    Merge,
+
+    /// A merge of another player into a player was undone
+    Split,
+}
+
+impl AuditAction {
+    fn from_sql(sql: &str) -> Self {
+        match sql {
+            "addition" => AuditAction::Addition,
+            "modification" => AuditAction::Modification,
+            "deletion" => AuditAction::Deletion,
+            "video_probe" => AuditAction::VideoProbe,
+            "rejection_reason_change" => AuditAction::RejectionReasonChange,
+            "merge" => AuditAction::Merge,
+            "split" => AuditAction::Split,
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct AuditUser {
+    pub id: i32,
+
+    /// `None` if the user has been deleted since, or if the action was performed by the server
+    /// itself (in which case the ID is 0)
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AuditLogEntry {
+    pub time: NaiveDateTime,
+    pub audit_id: i32,
+    pub user: AuditUser,
+    pub object: AuditObject,
+    pub object_id: i32,
+    pub action: AuditAction,
+
+    /// Additional data about the action. For modifications, these are the _old_ values of all
+    /// changed fields (unchanged fields are `null`). For deletions of records and players the
+    /// complete old state is logged as a modification directly preceding the deletion.
+    pub data: Option<Value>,
+}
+
+#[derive(Deserialize, Debug, Clone, Serialize)]
+pub struct AuditLogPagination {
+    #[serde(rename = "before", default, deserialize_with = "non_nullable")]
+    pub before_id: Option<i32>,
+
+    #[serde(rename = "after", default, deserialize_with = "non_nullable")]
+    pub after_id: Option<i32>,
+
+    #[serde(default, deserialize_with = "non_nullable")]
+    pub limit: Option<u8>,
+
+    /// The ID of the user that performed the actions
+    #[serde(default, deserialize_with = "non_nullable")]
+    user: Option<i32>,
+
+    #[serde(default, deserialize_with = "non_nullable")]
+    object: Option<AuditObject>,
+
+    #[serde(default, deserialize_with = "non_nullable")]
+    object_id: Option<i32>,
+
+    /// Only list entries created at or after this time
+    #[serde(default, deserialize_with = "non_nullable")]
+    since: Option<NaiveDateTime>,
+
+    /// Only list entries created before this time
+    #[serde(default, deserialize_with = "non_nullable")]
+    until: Option<NaiveDateTime>,
+}
+
+impl AuditLogPagination {
+    pub async fn page(&self, connection: &mut PgConnection) -> Result<Vec<AuditLogEntry>> {
+        if let Some(limit) = self.limit {
+            if limit < 1 || limit > 100 {
+                return Err(PointercrateError::InvalidPaginationLimit)
+            }
+        }
+
+        if let (Some(after), Some(before)) = (self.before_id, self.after_id) {
+            if after < before {
+                return Err(PointercrateError::AfterSmallerBefore)
+            }
+        }
+
+        let order = if self.before_id.is_some() && self.after_id.is_none() {
+            "DESC"
+        } else {
+            "ASC"
+        };
+
+        let query = format!(include_str!("../../sql/paginate_audit_log.sql"), order);
+
+        let mut stream = sqlx::query(&query)
+            .bind(self.before_id)
+            .bind(self.after_id)
+            .bind(self.user)
+            .bind(self.object.map(|object| object.to_sql()))
+            .bind(self.object_id)
+            .bind(self.since)
+            .bind(self.until)
+            .bind(self.limit.unwrap_or(50) as i32 + 1)
+            .fetch(connection);
+
+        let mut entries = Vec::new();
+
+        while let Some(row) = stream.next().await {
+            entries.push(entry_from_row(row?)?)
+        }
+
+        Ok(entries)
+    }
+}
+
+fn entry_from_row(row: PgRow) -> Result<AuditLogEntry> {
+    let data = match row.try_get::<Option<&str>, _>("data")? {
+        Some(data) =>
+            Some(serde_json::from_str(data).map_err(|err| {
+                error!("Audit log data is not valid JSON: {:?}", err);
+
+                PointercrateError::InternalServerError
+            })?),
+        None => None,
+    };
+
+    Ok(AuditLogEntry {
+        time: row.try_get("time")?,
+        audit_id: row.try_get("audit_id")?,
+        user: AuditUser {
+            id: row.try_get("userid")?,
+            name: row.try_get("username")?,
+        },
+        object: AuditObject::from_sql(row.try_get("object_type")?),
+        object_id: row.try_get("object_id")?,
+        action: AuditAction::from_sql(row.try_get("action")?),
+        data,
+    })
+}
@@ -7,7 +7,7 @@ pub use self::{
 use crate::{
     cistring::{CiStr, CiString},
     error::PointercrateError,
-    model::demonlist::{player::DatabasePlayer, record::MinimalRecordP},
+    model::demonlist::{player::DatabasePlayer, record::MinimalRecordP, scoring_formula::ScoringFormula},
     Result,
 };
 use derive_more::Display;
@@ -16,6 +16,7 @@ use serde::Serialize;
 use sqlx::PgConnection;
 use std::hash::{Hash, Hasher};
 
+pub mod audit;
 mod get;
 mod paginate;
 mod patch;
@@ -215,13 +216,9 @@ impl Demon {
         Ok((row.max_id, row.min_id))
     }
 
-    pub fn score(&self, progress: i16) -> f64 {
-        let mut score = 100f64 * f64::exp((1f64 - f64::from(self.base.position)) * (1f64 / 30f64).ln() / (-99f64));
-
-        if progress != 100 {
-            score *= 0.25f64 + (f64::from(progress) - f64::from(self.requirement)) / (100f64 - f64::from(self.requirement)) * 0.25f64
-        }
-
-        score
+    /// Computes the amount of points a record with the given progress on this demon is worth
+    /// under the given scoring formula
+    pub fn score(&self, progress: i16, formula: &ScoringFormula) -> f64 {
+        formula.score(self.base.position, self.requirement, progress)
     }
 }
@@ -0,0 +1,209 @@
+use crate::{model::demonlist::record::audit::NamedId, Result};
+use chrono::NaiveDateTime;
+use futures::StreamExt;
+use serde::Serialize;
+use sqlx::PgConnection;
+
+/// The old values of all fields changed by a modification
+#[derive(Serialize)]
+pub struct DemonModificationData {
+    name: Option<String>,
+    position: Option<i16>,
+    requirement: Option<i16>,
+    video: Option<String>,
+    verifier: Option<NamedId>,
+    publisher: Option<NamedId>,
+}
+
+impl DemonModificationData {
+    fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.position.is_none()
+            && self.requirement.is_none()
+            && self.video.is_none()
+            && self.verifier.is_none()
+            && self.publisher.is_none()
+    }
+}
+
+#[derive(Serialize)]
+pub struct DemonEntry {
+    time: NaiveDateTime,
+    audit_id: i32,
+    demon_id: i32,
+    user: NamedId,
+    r#type: DemonEntryType,
+}
+
+#[derive(Serialize)]
+pub enum DemonEntryType {
+    Addition,
+    Modification(DemonModificationData),
+
+    /// The given player was added as a creator
+    CreatorAddition(NamedId),
+
+    /// The given player was removed from the creators
+    CreatorDeletion(NamedId),
+}
+
+/// Gets all audit log entries for the given demon, in chronological order
+pub async fn entries_for_demon(demon_id: i32, connection: &mut PgConnection) -> Result<Vec<DemonEntry>> {
+    let mut entries = Vec::new();
+
+    let addition_row = sqlx::query!(
+        r#"SELECT time, audit_id,
+                  userid,
+                  members.name AS "name?"
+                  FROM demon_additions LEFT OUTER JOIN members ON members.member_id = userid WHERE id = $1"#,
+        demon_id
+    )
+    .fetch_optional(&mut *connection)
+    .await?;
+
+    if let Some(addition) = addition_row {
+        entries.push(DemonEntry {
+            time: addition.time,
+            audit_id: addition.audit_id,
+            demon_id,
+            user: NamedId {
+                name: addition.name,
+                id: addition.userid,
+            },
+            r#type: DemonEntryType::Addition,
+        });
+    }
+
+    {
+        // Has to be in block because it doesn't unborrow the connection otherwise. No idea why
+        let mut modification_stream = sqlx::query!(
+            r#"SELECT time,
+                  audit_id,
+                  members.name AS "username?",
+                  userid,
+                  demon_modifications.name::TEXT,
+                  position,
+                  requirement,
+                  video::TEXT,
+                  verifier AS verifier_id,
+                  verifiers.name::TEXT AS verifier_name,
+                  publisher AS publisher_id,
+                  publishers.name::TEXT AS publisher_name
+                  FROM demon_modifications
+                  LEFT OUTER JOIN members ON members.member_id = userid
+                  LEFT OUTER JOIN players AS verifiers ON verifiers.id = verifier
+                  LEFT OUTER JOIN players AS publishers ON publishers.id = publisher
+                  WHERE demon_modifications.id = $1"#,
+            demon_id
+        )
+        .fetch(&mut *connection);
+
+        while let Some(modification) = modification_stream.next().await {
+            let modification = modification?;
+
+            let data = DemonModificationData {
+                name: modification.name,
+                // When moving a demon, it is temporarily moved to position -1 so that the other demons can be
+                // shifted around. The entries logged for this contain no useful information.
+                position: modification.position.filter(|&position| position > 0),
+                requirement: modification.requirement,
+                video: modification.video,
+                verifier: modification.verifier_id.map(|id| {
+                    NamedId {
+                        id,
+                        name: modification.verifier_name,
+                    }
+                }),
+                publisher: modification.publisher_id.map(|id| {
+                    NamedId {
+                        id,
+                        name: modification.publisher_name,
+                    }
+                }),
+            };
+
+            if data.is_empty() {
+                continue
+            }
+
+            entries.push(DemonEntry {
+                time: modification.time,
+                audit_id: modification.audit_id,
+                demon_id,
+                user: NamedId {
+                    name: modification.username,
+                    id: modification.userid,
+                },
+                r#type: DemonEntryType::Modification(data),
+            })
+        }
+    }
+
+    let creator_additions = sqlx::query!(
+        r#"SELECT time, audit_id,
+                  userid,
+                  members.name AS "username?",
+                  creator,
+                  players.name::TEXT AS creator_name
+                  FROM creator_additions
+                  LEFT OUTER JOIN members ON members.member_id = userid
+                  LEFT OUTER JOIN players ON players.id = creator
+                  WHERE demon = $1"#,
+        demon_id
+    )
+    .fetch_all(&mut *connection)
+    .await?;
+
+    for addition in creator_additions {
+        entries.push(DemonEntry {
+            time: addition.time,
+            audit_id: addition.audit_id,
+            demon_id,
+            user: NamedId {
+                name: addition.username,
+                id: addition.userid,
+            },
+            r#type: DemonEntryType::CreatorAddition(NamedId {
+                id: addition.creator,
+                name: addition.creator_name,
+            }),
+        });
+    }
+
+    let creator_deletions = sqlx::query!(
+        r#"SELECT time, audit_id,
+                  userid,
+                  members.name AS "username?",
+                  creator,
+                  players.name::TEXT AS creator_name
+                  FROM creator_deletions
+                  LEFT OUTER JOIN members ON members.member_id = userid
+                  LEFT OUTER JOIN players ON players.id = creator
+                  WHERE demon = $1"#,
+        demon_id
+    )
+    .fetch_all(&mut *connection)
+    .await?;
+
+    for deletion in creator_deletions {
+        entries.push(DemonEntry {
+            time: deletion.time,
+            audit_id: deletion.audit_id,
+            demon_id,
+            user: NamedId {
+                name: deletion.username,
+                id: deletion.userid,
+            },
+            r#type: DemonEntryType::CreatorDeletion(NamedId {
+                id: deletion.creator,
+                name: deletion.creator_name,
+            }),
+        });
+    }
+
+    // All audit log tables share the audit_id sequence, so this is the order in which the entries
+    // were created
+    entries.sort_by_key(|entry| entry.audit_id);
+
+    Ok(entries)
+}
@@ -5,10 +5,11 @@ use crate::{
         creator::creators_of,
         demon::{Demon, FullDemon, MinimalDemon},
         player::DatabasePlayer,
-        record::approved_records_on,
+        record::{approved_records_on, approved_records_on_at},
     },
     Result,
 };
+use chrono::NaiveDateTime;
 use futures::StreamExt;
 use sqlx::{Error, PgConnection};
 
@@ -76,6 +77,13 @@ impl FullDemon {
     pub async fn by_position(position: i16, connection: &mut PgConnection) -> Result<FullDemon> {
         Demon::by_position(position, connection).await?.upgrade(connection).await
     }
+
+    /// Replaces this demon's records with the approved records it had at the given point in time
+    pub async fn load_records_at(&mut self, at: NaiveDateTime, connection: &mut PgConnection) -> Result<()> {
+        self.records = approved_records_on_at(&self.demon.base, at, connection).await?;
+
+        Ok(())
+    }
 }
 
 // FIXME: optimally, we want to only have one of these
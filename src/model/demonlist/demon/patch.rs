@@ -2,7 +2,11 @@ use super::{Demon, FullDemon};
 use crate::{
     cistring::CiString,
     error::PointercrateError,
-    model::demonlist::{demon::MinimalDemon, player::DatabasePlayer},
+    model::demonlist::{
+        demon::MinimalDemon,
+        player::DatabasePlayer,
+        video_key::{self, VideoOwner},
+    },
     util::{non_nullable, nullable},
     Result,
 };
@@ -133,6 +137,8 @@ impl Demon {
     pub async fn set_video(&mut self, video: String, connection: &mut PgConnection) -> Result<()> {
         let video = crate::video::validate(&video)?;
 
+        video_key::ensure_unused(&video, Some(VideoOwner::Demon(self.base.id)), &mut *connection).await?;
+
         sqlx::query!("UPDATE demons SET video = $1::text WHERE id = $2", video, self.base.id)
             .execute(connection)
             .await?;
@@ -4,6 +4,7 @@ use crate::{
         creator::Creator,
         demon::{Demon, FullDemon, MinimalDemon},
         player::DatabasePlayer,
+        video_key,
     },
     Result,
 };
@@ -36,6 +37,10 @@ impl FullDemon {
 
         Demon::validate_position(data.position, connection).await?;
 
+        if let Some(ref video) = video {
+            video_key::ensure_unused(video, None, connection).await?;
+        }
+
         let publisher = DatabasePlayer::by_name_or_create(data.publisher.as_ref(), connection).await?;
         let verifier = DatabasePlayer::by_name_or_create(data.verifier.as_ref(), connection).await?;
 
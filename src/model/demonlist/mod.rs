@@ -2,4 +2,20 @@ pub mod creator;
 pub mod demon;
 pub mod player;
 pub mod record;
+pub mod rejection_reason;
+pub mod scoring_formula;
 pub mod submitter;
+pub mod video_check;
+pub mod video_key;
+
+use crate::util::non_nullable;
+use chrono::NaiveDateTime;
+use serde::Deserialize;
+
+/// Query parameters for viewing objects as they were at some point in the past (the "time
+/// machine")
+#[derive(Debug, Deserialize, Default)]
+pub struct TimeMachine {
+    #[serde(default, deserialize_with = "non_nullable")]
+    pub at: Option<NaiveDateTime>,
+}
@@ -1,6 +1,8 @@
 pub use self::{
+    claim::{ClaimedPlayer, PatchClaimedPlayer, PlayerClaim},
     paginate::{PlayerPagination, RankingPagination},
     patch::PatchPlayer,
+    preview::{DryRun, MergePreview},
 };
 use crate::{
     cistring::CiString,
@@ -15,9 +17,14 @@ use serde::Serialize;
 use sqlx::PgConnection;
 use std::hash::{Hash, Hasher};
 
+pub mod audit;
+mod claim;
 mod get;
+pub mod history;
 mod paginate;
 mod patch;
+mod preview;
+mod split;
 
 #[derive(Debug, Hash, Eq, PartialEq, Serialize, Display, Clone)]
 #[display(fmt = "{} (ID: {})", name, id)]
@@ -73,6 +80,17 @@ impl RankedPlayer {
             .await?
             .max_index)
     }
+
+    /// Gets the highest index in the ranking as it would be under the given scoring formula
+    pub async fn max_index_under(formula_id: i32, connection: &mut PgConnection) -> Result<i64> {
+        Ok(sqlx::query!(
+            r#"SELECT COALESCE(MAX(index), 0) AS "max_index!: i64" FROM players_with_score_under($1)"#,
+            formula_id
+        )
+        .fetch_one(connection)
+        .await?
+        .max_index)
+    }
 }
 
 impl Player {
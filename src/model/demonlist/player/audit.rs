@@ -0,0 +1,333 @@
+use crate::{model::demonlist::record::audit::NamedId, Result};
+use chrono::NaiveDateTime;
+use serde::Serialize;
+use sqlx::PgConnection;
+
+/// The old values of all fields changed by a modification
+#[derive(Serialize)]
+pub struct PlayerModificationData {
+    name: Option<String>,
+    banned: Option<bool>,
+    hide_videos: Option<bool>,
+}
+
+/// Information about another player having been merged into this one
+#[derive(Serialize)]
+pub struct MergeData {
+    /// The player that was merged into this one (and deleted afterwards)
+    merged: NamedId,
+    records_moved: i32,
+    creators_moved: i32,
+
+    /// Creator entries of the merged player on demons this player was already a creator of
+    creators_deleted: i32,
+    verifications_moved: i32,
+    publications_moved: i32,
+}
+
+#[derive(Serialize)]
+pub struct PlayerEntry {
+    time: NaiveDateTime,
+    audit_id: i32,
+    player_id: i32,
+    user: NamedId,
+    r#type: PlayerEntryType,
+}
+
+#[derive(Serialize)]
+pub enum PlayerEntryType {
+    Addition,
+    Modification(PlayerModificationData),
+
+    /// The player's nationality changed. Contains the country code of the old nationality, or
+    /// `None` if the player previously had no nationality set
+    NationalityModification(Option<String>),
+
+    /// The player's subdivision changed. Contains the full ISO 3166-2 code of the old subdivision
+    /// (e.g. `US-CA`), or `None` if the player previously had no subdivision set
+    SubdivisionModification(Option<String>),
+
+    /// Another player was merged into this one
+    Merge(MergeData),
+
+    /// This player was merged into the given player
+    MergedInto(NamedId),
+
+    /// The merge of the given player into this one was undone
+    Split(NamedId),
+
+    /// The merge of this player into the given player was undone, recreating this player
+    SplitFrom(NamedId),
+    Deletion,
+}
+
+/// Gets all audit log entries for the given player, in chronological order
+pub async fn entries_for_player(player_id: i32, connection: &mut PgConnection) -> Result<Vec<PlayerEntry>> {
+    let mut entries = Vec::new();
+
+    let addition_rows = sqlx::query!(
+        r#"SELECT time, audit_id,
+                  userid,
+                  members.name AS "name?"
+                  FROM player_additions LEFT OUTER JOIN members ON members.member_id = userid WHERE id = $1"#,
+        player_id
+    )
+    .fetch_all(&mut *connection)
+    .await?;
+
+    for addition in addition_rows {
+        entries.push(PlayerEntry {
+            time: addition.time,
+            audit_id: addition.audit_id,
+            player_id,
+            user: NamedId {
+                name: addition.name,
+                id: addition.userid,
+            },
+            r#type: PlayerEntryType::Addition,
+        });
+    }
+
+    // Changing only the nationality still creates (empty) modification entries
+    let modification_rows = sqlx::query!(
+        r#"SELECT time, audit_id,
+                  userid,
+                  members.name AS "username?",
+                  player_modifications.name::TEXT,
+                  banned,
+                  hide_videos
+                  FROM player_modifications LEFT OUTER JOIN members ON members.member_id = userid
+                  WHERE id = $1 AND (player_modifications.name IS NOT NULL OR banned IS NOT NULL OR hide_videos IS NOT NULL)"#,
+        player_id
+    )
+    .fetch_all(&mut *connection)
+    .await?;
+
+    for modification in modification_rows {
+        entries.push(PlayerEntry {
+            time: modification.time,
+            audit_id: modification.audit_id,
+            player_id,
+            user: NamedId {
+                name: modification.username,
+                id: modification.userid,
+            },
+            r#type: PlayerEntryType::Modification(PlayerModificationData {
+                name: modification.name,
+                banned: modification.banned,
+                hide_videos: modification.hide_videos,
+            }),
+        });
+    }
+
+    let nationality_rows = sqlx::query!(
+        r#"SELECT time, audit_id,
+                  userid,
+                  members.name AS "username?",
+                  nationality::TEXT
+                  FROM player_nationality_modifications LEFT OUTER JOIN members ON members.member_id = userid WHERE id = $1"#,
+        player_id
+    )
+    .fetch_all(&mut *connection)
+    .await?;
+
+    for modification in nationality_rows {
+        entries.push(PlayerEntry {
+            time: modification.time,
+            audit_id: modification.audit_id,
+            player_id,
+            user: NamedId {
+                name: modification.username,
+                id: modification.userid,
+            },
+            r#type: PlayerEntryType::NationalityModification(modification.nationality),
+        });
+    }
+
+    let subdivision_rows = sqlx::query!(
+        r#"SELECT time, audit_id,
+                  userid,
+                  members.name AS "username?",
+                  nation || '-' || subdivision AS subdivision
+                  FROM player_subdivision_modifications LEFT OUTER JOIN members ON members.member_id = userid WHERE id = $1"#,
+        player_id
+    )
+    .fetch_all(&mut *connection)
+    .await?;
+
+    for modification in subdivision_rows {
+        entries.push(PlayerEntry {
+            time: modification.time,
+            audit_id: modification.audit_id,
+            player_id,
+            user: NamedId {
+                name: modification.username,
+                id: modification.userid,
+            },
+            r#type: PlayerEntryType::SubdivisionModification(modification.subdivision),
+        });
+    }
+
+    let merge_rows = sqlx::query!(
+        r#"SELECT time, audit_id,
+                  userid,
+                  members.name AS "username?",
+                  merged_id,
+                  merged_name::TEXT AS "merged_name!",
+                  records_moved,
+                  creators_moved,
+                  creators_deleted,
+                  verifications_moved,
+                  publications_moved
+                  FROM player_merges LEFT OUTER JOIN members ON members.member_id = userid WHERE id = $1"#,
+        player_id
+    )
+    .fetch_all(&mut *connection)
+    .await?;
+
+    for merge in merge_rows {
+        entries.push(PlayerEntry {
+            time: merge.time,
+            audit_id: merge.audit_id,
+            player_id,
+            user: NamedId {
+                name: merge.username,
+                id: merge.userid,
+            },
+            r#type: PlayerEntryType::Merge(MergeData {
+                merged: NamedId {
+                    id: merge.merged_id,
+                    name: Some(merge.merged_name),
+                },
+                records_moved: merge.records_moved,
+                creators_moved: merge.creators_moved,
+                creators_deleted: merge.creators_deleted,
+                verifications_moved: merge.verifications_moved,
+                publications_moved: merge.publications_moved,
+            }),
+        });
+    }
+
+    let merged_into_rows = sqlx::query!(
+        r#"SELECT time, audit_id,
+                  userid,
+                  members.name AS "username?",
+                  player_merges.id AS target_id,
+                  players.name::TEXT AS target_name
+                  FROM player_merges
+                  LEFT OUTER JOIN members ON members.member_id = userid
+                  LEFT OUTER JOIN players ON players.id = player_merges.id
+                  WHERE merged_id = $1"#,
+        player_id
+    )
+    .fetch_all(&mut *connection)
+    .await?;
+
+    // Since merges can be undone, a player might have been merged multiple times
+    for merge in merged_into_rows {
+        entries.push(PlayerEntry {
+            time: merge.time,
+            audit_id: merge.audit_id,
+            player_id,
+            user: NamedId {
+                name: merge.username,
+                id: merge.userid,
+            },
+            r#type: PlayerEntryType::MergedInto(NamedId {
+                id: merge.target_id,
+                name: merge.target_name,
+            }),
+        });
+    }
+
+    let split_rows = sqlx::query!(
+        r#"SELECT time, audit_id,
+                  userid,
+                  members.name AS "username?",
+                  restored_id,
+                  players.name::TEXT AS restored_name
+                  FROM player_splits
+                  LEFT OUTER JOIN members ON members.member_id = userid
+                  LEFT OUTER JOIN players ON players.id = restored_id
+                  WHERE player_splits.id = $1"#,
+        player_id
+    )
+    .fetch_all(&mut *connection)
+    .await?;
+
+    for split in split_rows {
+        entries.push(PlayerEntry {
+            time: split.time,
+            audit_id: split.audit_id,
+            player_id,
+            user: NamedId {
+                name: split.username,
+                id: split.userid,
+            },
+            r#type: PlayerEntryType::Split(NamedId {
+                id: split.restored_id,
+                name: split.restored_name,
+            }),
+        });
+    }
+
+    let split_from_rows = sqlx::query!(
+        r#"SELECT time, audit_id,
+                  userid,
+                  members.name AS "username?",
+                  player_splits.id AS target_id,
+                  players.name::TEXT AS target_name
+                  FROM player_splits
+                  LEFT OUTER JOIN members ON members.member_id = userid
+                  LEFT OUTER JOIN players ON players.id = player_splits.id
+                  WHERE restored_id = $1"#,
+        player_id
+    )
+    .fetch_all(&mut *connection)
+    .await?;
+
+    for split in split_from_rows {
+        entries.push(PlayerEntry {
+            time: split.time,
+            audit_id: split.audit_id,
+            player_id,
+            user: NamedId {
+                name: split.username,
+                id: split.userid,
+            },
+            r#type: PlayerEntryType::SplitFrom(NamedId {
+                id: split.target_id,
+                name: split.target_name,
+            }),
+        });
+    }
+
+    let deletion_rows = sqlx::query!(
+        r#"SELECT time, audit_id,
+                  userid,
+                  members.name AS "name?"
+                  FROM player_deletions LEFT OUTER JOIN members ON members.member_id = userid WHERE id = $1"#,
+        player_id
+    )
+    .fetch_all(&mut *connection)
+    .await?;
+
+    for deletion in deletion_rows {
+        entries.push(PlayerEntry {
+            time: deletion.time,
+            audit_id: deletion.audit_id,
+            player_id,
+            user: NamedId {
+                name: deletion.name,
+                id: deletion.userid,
+            },
+            r#type: PlayerEntryType::Deletion,
+        });
+    }
+
+    // All audit log tables share the audit_id sequence, so this is the order in which the entries
+    // were created
+    entries.sort_by_key(|entry| entry.audit_id);
+
+    Ok(entries)
+}
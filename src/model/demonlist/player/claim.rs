@@ -0,0 +1,297 @@
+//! Module containing code for linking user accounts to players
+//!
+//! A user can request to claim a player, which then has to be verified by a leaderboard moderator.
+//! Every user can only claim a single player, and only one claim per player can be verified. Once
+//! their claim is verified, a user can change some settings of their player themselves (see
+//! [`PatchClaimedPlayer`]) and see their records that are still waiting for review.
+
+use crate::{
+    cistring::CiString,
+    error::PointercrateError,
+    model::{
+        demonlist::{
+            player::{DatabasePlayer, FullPlayer, PatchPlayer, Player},
+            record::{approved_records_by, pending_records_by, MinimalRecordD},
+        },
+        user::User,
+    },
+    util::{non_nullable, nullable},
+    Result,
+};
+use chrono::NaiveDateTime;
+use log::info;
+use serde::{Deserialize, Serialize};
+use sqlx::{Error, PgConnection};
+use std::hash::{Hash, Hasher};
+
+/// The user that requested a claim
+#[derive(Debug, Serialize, Hash, PartialEq, Eq)]
+pub struct ClaimingUser {
+    pub id: i32,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Hash, PartialEq, Eq)]
+pub struct PlayerClaim {
+    pub user: ClaimingUser,
+    pub player: DatabasePlayer,
+    pub verified: bool,
+    pub requested_at: NaiveDateTime,
+}
+
+/// A user's view of the player they claimed
+#[derive(Debug, Serialize)]
+pub struct ClaimedPlayer {
+    #[serde(flatten)]
+    pub player: FullPlayer,
+
+    /// Whether the claim has been verified by a leaderboard moderator
+    pub verified: bool,
+
+    /// Whether the player chose to hide the videos of their records
+    pub hide_videos: bool,
+
+    /// The player's submitted and under consideration records. Only loaded for verified claims
+    pub pending: Vec<MinimalRecordD>,
+}
+
+impl Hash for ClaimedPlayer {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.player.hash(state);
+        self.verified.hash(state);
+        self.hide_videos.hash(state);
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PatchClaimedPlayer {
+    #[serde(default, deserialize_with = "nullable")]
+    nationality: Option<Option<CiString>>,
+
+    #[serde(default, deserialize_with = "nullable")]
+    subdivision: Option<Option<CiString>>,
+
+    #[serde(default, deserialize_with = "non_nullable")]
+    hide_videos: Option<bool>,
+}
+
+// Required until https://github.com/launchbadge/sqlx/pull/108 is merged
+struct FetchedClaim {
+    member_id: i32,
+    member_name: String,
+    player_id: i32,
+    player_name: String,
+    banned: bool,
+    verified: bool,
+    requested_at: NaiveDateTime,
+}
+
+impl From<FetchedClaim> for PlayerClaim {
+    fn from(row: FetchedClaim) -> Self {
+        PlayerClaim {
+            user: ClaimingUser {
+                id: row.member_id,
+                name: row.member_name,
+            },
+            player: DatabasePlayer {
+                id: row.player_id,
+                name: CiString(row.player_name),
+                banned: row.banned,
+            },
+            verified: row.verified,
+            requested_at: row.requested_at,
+        }
+    }
+}
+
+impl PlayerClaim {
+    /// Gets the claim the given user has requested, regardless of which player it is on
+    pub async fn by_user(user_id: i32, connection: &mut PgConnection) -> Result<PlayerClaim> {
+        let result = sqlx::query_as!(
+            FetchedClaim,
+            r#"SELECT player_claims.member_id, members.name AS member_name, player_claims.player_id, players.name AS "player_name: String",
+             players.banned, verified, requested_at FROM player_claims INNER JOIN members ON members.member_id = player_claims.member_id
+             INNER JOIN players ON players.id = player_claims.player_id WHERE player_claims.member_id = $1"#,
+            user_id
+        )
+        .fetch_one(connection)
+        .await;
+
+        match result {
+            Ok(row) => Ok(row.into()),
+            Err(Error::RowNotFound) =>
+                Err(PointercrateError::ModelNotFound {
+                    model: "PlayerClaim",
+                    identified_by: user_id.to_string(),
+                }),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Gets the claim the given user has requested on the given player
+    pub async fn by_player_and_user(player_id: i32, user_id: i32, connection: &mut PgConnection) -> Result<PlayerClaim> {
+        match PlayerClaim::by_user(user_id, connection).await {
+            Ok(claim) if claim.player.id == player_id => Ok(claim),
+            Ok(_) | Err(PointercrateError::ModelNotFound { .. }) =>
+                Err(PointercrateError::ModelNotFound {
+                    model: "PlayerClaim",
+                    identified_by: format!("{}/{}", player_id, user_id),
+                }),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Gets all claims on the given player, verified claims first
+    pub async fn of_player(player_id: i32, connection: &mut PgConnection) -> Result<Vec<PlayerClaim>> {
+        Ok(sqlx::query_as!(
+            FetchedClaim,
+            r#"SELECT player_claims.member_id, members.name AS member_name, player_claims.player_id, players.name AS "player_name: String",
+             players.banned, verified, requested_at FROM player_claims INNER JOIN members ON members.member_id = player_claims.member_id
+             INNER JOIN players ON players.id = player_claims.player_id WHERE player_claims.player_id = $1 ORDER BY verified DESC,
+             requested_at"#,
+            player_id
+        )
+        .fetch_all(connection)
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect())
+    }
+
+    /// Gets the ID of the user whose claim on the given player has been verified, if any
+    async fn verified_claimant(player_id: i32, connection: &mut PgConnection) -> Result<Option<i32>> {
+        Ok(
+            sqlx::query!("SELECT member_id FROM player_claims WHERE player_id = $1 AND verified", player_id)
+                .fetch_optional(connection)
+                .await?
+                .map(|row| row.member_id),
+        )
+    }
+
+    /// Requests a claim on the given player for the given user
+    ///
+    /// An unverified claim the user already has on a different player is replaced. Once a user's
+    /// claim has been verified, they cannot claim a different player anymore.
+    pub async fn request(user: &User, player: DatabasePlayer, connection: &mut PgConnection) -> Result<PlayerClaim> {
+        match PlayerClaim::by_user(user.id, connection).await {
+            Ok(existing) if existing.player.id == player.id => return Ok(existing),
+            Ok(existing) if existing.verified => return Err(PointercrateError::ClaimExists),
+            Ok(_) | Err(PointercrateError::ModelNotFound { .. }) => (),
+            Err(err) => return Err(err),
+        }
+
+        if PlayerClaim::verified_claimant(player.id, connection).await?.is_some() {
+            return Err(PointercrateError::PlayerClaimed)
+        }
+
+        let requested_at = sqlx::query!(
+            "INSERT INTO player_claims (member_id, player_id) VALUES ($1, $2) ON CONFLICT (member_id) DO UPDATE SET player_id = \
+             EXCLUDED.player_id, verified = FALSE, requested_at = EXCLUDED.requested_at RETURNING requested_at",
+            user.id,
+            player.id
+        )
+        .fetch_one(connection)
+        .await?
+        .requested_at;
+
+        info!("User {} requested to claim player {}", user, player);
+
+        Ok(PlayerClaim {
+            user: ClaimingUser {
+                id: user.id,
+                name: user.name.clone(),
+            },
+            player,
+            verified: false,
+            requested_at,
+        })
+    }
+
+    /// Verifies this claim, linking the player to the user who claimed it
+    pub async fn verify(&mut self, connection: &mut PgConnection) -> Result<()> {
+        if self.verified {
+            return Ok(())
+        }
+
+        if PlayerClaim::verified_claimant(self.player.id, connection).await?.is_some() {
+            return Err(PointercrateError::PlayerClaimed)
+        }
+
+        sqlx::query!("UPDATE player_claims SET verified = TRUE WHERE member_id = $1", self.user.id)
+            .execute(connection)
+            .await?;
+
+        info!("Verified claim of user {} on player {}", self.user.id, self.player);
+
+        self.verified = true;
+
+        Ok(())
+    }
+
+    pub async fn delete(self, connection: &mut PgConnection) -> Result<()> {
+        sqlx::query!("DELETE FROM player_claims WHERE member_id = $1", self.user.id)
+            .execute(connection)
+            .await?;
+
+        info!("Deleted claim of user {} on player {}", self.user.id, self.player);
+
+        Ok(())
+    }
+
+    /// Loads the claimed player. Pending records are only included if the claim has been verified
+    pub async fn upgrade(self, connection: &mut PgConnection) -> Result<ClaimedPlayer> {
+        let player = Player::by_id(self.player.id, connection).await?.upgrade(connection).await?;
+        let hide_videos = sqlx::query!("SELECT hide_videos FROM players WHERE id = $1", self.player.id)
+            .fetch_one(&mut *connection)
+            .await?
+            .hide_videos;
+
+        let pending = if self.verified {
+            pending_records_by(&self.player, connection).await?
+        } else {
+            Vec::new()
+        };
+
+        Ok(ClaimedPlayer {
+            player,
+            verified: self.verified,
+            hide_videos,
+            pending,
+        })
+    }
+}
+
+impl ClaimedPlayer {
+    pub async fn apply_patch(mut self, patch: PatchClaimedPlayer, connection: &mut PgConnection) -> Result<Self> {
+        if !self.verified {
+            return Err(PointercrateError::ClaimNotVerified)
+        }
+
+        let patch_player = PatchPlayer {
+            nationality: patch.nationality,
+            subdivision: patch.subdivision,
+            ..Default::default()
+        };
+
+        self.player = self.player.apply_patch(patch_player, connection).await?;
+
+        if let Some(hide_videos) = patch.hide_videos {
+            if hide_videos != self.hide_videos {
+                sqlx::query!(
+                    "UPDATE players SET hide_videos = $1 WHERE id = $2",
+                    hide_videos,
+                    self.player.player.base.id
+                )
+                .execute(&mut *connection)
+                .await?;
+
+                self.hide_videos = hide_videos;
+
+                // The videos of approved records are hidden for such players
+                self.player.records = approved_records_by(&self.player.player.base, connection).await?;
+            }
+        }
+
+        Ok(self)
+    }
+}
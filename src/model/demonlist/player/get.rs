@@ -6,12 +6,13 @@ use crate::{
             creator::created_by,
             demon::{published_by, verified_by},
             player::{DatabasePlayer, FullPlayer, Player},
-            record::approved_records_by,
+            record::{approved_records_by, approved_records_by_at},
         },
-        nationality::Nationality,
+        nationality::{Nationality, Subdivision},
     },
     Result,
 };
+use chrono::NaiveDateTime;
 use sqlx::{Error, PgConnection};
 
 // Required until https://github.com/launchbadge/sqlx/pull/108 is merged
@@ -21,6 +22,18 @@ struct FetchedPlayer {
     banned: bool,
     nation: Option<String>,
     iso_country_code: Option<String>,
+    subdivision_code: Option<String>,
+    subdivision_name: Option<String>,
+}
+
+impl FullPlayer {
+    /// Replaces this player's records with the approved records they had at the given point in
+    /// time
+    pub async fn load_records_at(&mut self, at: NaiveDateTime, connection: &mut PgConnection) -> Result<()> {
+        self.records = approved_records_by_at(&self.player.base, at, connection).await?;
+
+        Ok(())
+    }
 }
 
 impl Player {
@@ -42,8 +55,10 @@ impl Player {
     pub async fn by_id(id: i32, connection: &mut PgConnection) -> Result<Player> {
         let result = sqlx::query_as!(
             FetchedPlayer,
-            r#"SELECT id, name AS "name: String", banned, nation::text, iso_country_code::text FROM players LEFT OUTER JOIN nationalities ON 
-             players.nationality = nationalities.iso_country_code WHERE id = $1"#,
+            r#"SELECT id, players.name AS "name: String", banned, nationalities.nation::text, iso_country_code::text, subdivisions.iso_code::text AS 
+             subdivision_code, subdivisions.name::text AS subdivision_name FROM players LEFT OUTER JOIN nationalities ON players.nationality = 
+             nationalities.iso_country_code LEFT OUTER JOIN subdivisions ON subdivisions.nation = players.nationality AND subdivisions.iso_code 
+             = players.subdivision WHERE id = $1"#,
             id
         )
         .fetch_one(connection)
@@ -55,6 +70,7 @@ impl Player {
                     Some(Nationality {
                         iso_country_code,
                         nation: CiString(nation),
+                        subdivision: Subdivision::from_columns(row.subdivision_code, row.subdivision_name),
                     })
                 } else {
                     None
@@ -0,0 +1,108 @@
+//! Module containing the historical player ranking
+//!
+//! The `players_with_score` view only reflects the current state of the list. To be able to tell
+//! how a player's rank evolved, a background task (see [`snapshot_ranking`]) stores the rank and
+//! score of every ranked player once a day in the `player_ranking_snapshots` table.
+//!
+//! For points in time before the first snapshot, the ranking is instead reconstructed from the
+//! record audit log (see the `players_with_score_at` SQL function), which is a lot slower.
+
+use crate::{model::demonlist::player::RankedPlayer, state::PointercrateState, Result};
+use chrono::{NaiveDate, NaiveDateTime};
+use log::{error, info};
+use serde::Serialize;
+use sqlx::{Done, PgConnection};
+use std::time::Duration;
+
+/// How often we check whether today's snapshot has already been taken
+const POLL_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// A player's rank and score on some day
+#[derive(Debug, Serialize)]
+pub struct RankingSnapshot {
+    pub day: NaiveDate,
+    pub rank: i64,
+    pub score: f64,
+}
+
+/// Background task taking a snapshot of the player ranking once a day
+///
+/// Should be spawned exactly once at startup. Never returns.
+pub async fn snapshot_ranking(state: PointercrateState) {
+    info!("Starting task for taking daily ranking snapshots");
+
+    let mut interval = actix_rt::time::interval(POLL_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        if let Err(error) = take_snapshot(&state).await {
+            error!("INTERNAL SERVER ERROR: Failure to take ranking snapshot: {:?}", error);
+        }
+    }
+}
+
+async fn take_snapshot(state: &PointercrateState) -> Result<()> {
+    let mut connection = state.connection().await?;
+
+    let inserted = sqlx::query!(
+        "INSERT INTO player_ranking_snapshots (player, day, rank, score, index) SELECT id, (NOW() AT TIME ZONE 'utc')::DATE, rank, score, \
+         index FROM players_with_score WHERE NOT EXISTS (SELECT 1 FROM player_ranking_snapshots WHERE day = (NOW() AT TIME ZONE \
+         'utc')::DATE)"
+    )
+    .execute(&mut connection)
+    .await?
+    .rows_affected();
+
+    if inserted > 0 {
+        info!("Took ranking snapshot of {} players", inserted);
+    }
+
+    Ok(())
+}
+
+/// Gets the snapshots of the given player's rank and score, in chronological order
+pub async fn history_of(player_id: i32, connection: &mut PgConnection) -> Result<Vec<RankingSnapshot>> {
+    Ok(sqlx::query_as!(
+        RankingSnapshot,
+        "SELECT day, rank, score FROM player_ranking_snapshots WHERE player = $1 ORDER BY day",
+        player_id
+    )
+    .fetch_all(connection)
+    .await?)
+}
+
+/// Checks whether a ranking snapshot was taken at or before the given point in time
+pub async fn snapshot_exists_at(at: NaiveDateTime, connection: &mut PgConnection) -> Result<bool> {
+    Ok(sqlx::query!(
+        r#"SELECT EXISTS (SELECT 1 FROM player_ranking_snapshots WHERE day <= $1::DATE) AS "exists!: bool""#,
+        at
+    )
+    .fetch_one(connection)
+    .await?
+    .exists)
+}
+
+impl RankedPlayer {
+    /// Gets the largest index in the ranking as it was at the given point in time
+    pub async fn max_index_at(at: NaiveDateTime, connection: &mut PgConnection) -> Result<i64> {
+        if !snapshot_exists_at(at, &mut *connection).await? {
+            return Ok(sqlx::query!(
+                r#"SELECT COALESCE(MAX(index), 0) AS "max_index!: i64" FROM players_with_score_at($1)"#,
+                at
+            )
+            .fetch_one(connection)
+            .await?
+            .max_index)
+        }
+
+        Ok(sqlx::query!(
+            r#"SELECT COALESCE(MAX(index), 0) AS "max_index!: i64" FROM player_ranking_snapshots WHERE day = (SELECT MAX(day) FROM
+             player_ranking_snapshots WHERE day <= $1::DATE)"#,
+            at
+        )
+        .fetch_one(connection)
+        .await?
+        .max_index)
+    }
+}
@@ -2,12 +2,13 @@ use crate::{
     cistring::CiString,
     error::PointercrateError,
     model::{
-        demonlist::player::{DatabasePlayer, Player, RankedPlayer},
-        nationality::Nationality,
+        demonlist::player::{history::snapshot_exists_at, DatabasePlayer, Player, RankedPlayer},
+        nationality::{Nationality, Subdivision},
     },
     util::{non_nullable, nullable},
     Result,
 };
+use chrono::NaiveDateTime;
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 use sqlx::{postgres::PgConnection, Row};
@@ -76,6 +77,7 @@ impl PlayerPagination {
                     Some(Nationality {
                         iso_country_code: country_code,
                         nation: CiString(nation),
+                        subdivision: Subdivision::from_columns(row.get("subdivision_code"), row.get("subdivision_name")),
                     }),
                 _ => None,
             };
@@ -111,6 +113,22 @@ pub struct RankingPagination {
     nation: Option<Option<String>>,
     #[serde(default, deserialize_with = "non_nullable")]
     name_contains: Option<CiString>,
+
+    /// Only show players from the subdivision with the given code or name. Since subdivision codes
+    /// are only unique within a nation, this should be combined with `nation`
+    #[serde(default, deserialize_with = "non_nullable")]
+    subdivision: Option<CiString>,
+
+    /// Show the ranking as of the latest daily snapshot taken at or before this time, instead of
+    /// the current one. If there is no such snapshot, the ranking is reconstructed from the audit
+    /// log. Note that players are still filtered by their current name and nationality
+    #[serde(default, deserialize_with = "non_nullable")]
+    pub at: Option<NaiveDateTime>,
+
+    /// Show the ranking as it would be under the scoring formula with this id. Not a query
+    /// parameter, set by the scoring formula preview endpoint
+    #[serde(skip)]
+    pub formula: Option<i32>,
 }
 
 impl RankingPagination {
@@ -127,16 +145,34 @@ impl RankingPagination {
             "ASC"
         };
 
-        let query = format!(include_str!("../../../../sql/paginate_player_ranking.sql"), order);
+        let query = match (self.at, self.formula) {
+            (Some(_), Some(_)) => return Err(PointercrateError::MutuallyExclusive),
+            (None, Some(_)) => format!(include_str!("../../../../sql/paginate_player_ranking_under.sql"), order),
+            (Some(at), None) if snapshot_exists_at(at, &mut *connection).await? =>
+                format!(include_str!("../../../../sql/paginate_player_ranking_at.sql"), order),
+            (Some(_), None) => format!(include_str!("../../../../sql/paginate_player_ranking_reconstructed.sql"), order),
+            (None, None) => format!(include_str!("../../../../sql/paginate_player_ranking.sql"), order),
+        };
 
-        let mut stream = sqlx::query(&query)
+        let mut query = sqlx::query(&query)
             .bind(self.before_index)
             .bind(self.after_index)
             .bind(self.name_contains.as_ref().map(|s| s.as_str()))
             .bind(&self.nation)
             .bind(self.nation == Some(None))
-            .bind(self.limit.unwrap_or(50) as i32 + 1)
-            .fetch(connection);
+            .bind(self.limit.unwrap_or(50) as i32 + 1);
+
+        if let Some(at) = self.at {
+            query = query.bind(at);
+        }
+
+        if let Some(formula) = self.formula {
+            query = query.bind(formula);
+        }
+
+        let query = query.bind(self.subdivision.as_ref().map(|s| s.as_str()));
+
+        let mut stream = query.fetch(connection);
 
         let mut players = Vec::new();
 
@@ -148,6 +184,7 @@ impl RankingPagination {
                     Some(Nationality {
                         iso_country_code: country_code,
                         nation: CiString(nation),
+                        subdivision: Subdivision::from_columns(row.get("subdivision_code"), row.get("subdivision_name")),
                     }),
                 _ => None,
             };
@@ -6,7 +6,7 @@ use crate::{
             player::{DatabasePlayer, FullPlayer, Player},
             record::{approved_records_by, FullRecord},
         },
-        nationality::Nationality,
+        nationality::{Nationality, Subdivision},
     },
     util::{non_nullable, nullable},
     Result,
@@ -18,13 +18,16 @@ use sqlx::{Done, PgConnection};
 #[derive(Debug, Deserialize, Default)]
 pub struct PatchPlayer {
     #[serde(default, deserialize_with = "non_nullable")]
-    name: Option<CiString>,
+    pub(super) name: Option<CiString>,
 
     #[serde(default, deserialize_with = "non_nullable")]
     banned: Option<bool>,
 
     #[serde(default, deserialize_with = "nullable")]
-    nationality: Option<Option<CiString>>,
+    pub(super) nationality: Option<Option<CiString>>,
+
+    #[serde(default, deserialize_with = "nullable")]
+    pub(super) subdivision: Option<Option<CiString>>,
 }
 
 impl FullPlayer {
@@ -39,6 +42,20 @@ impl FullPlayer {
             }
         }
 
+        if let Some(subdivision) = patch.subdivision {
+            match subdivision {
+                Some(ident) => {
+                    let subdivision = match self.player.nationality {
+                        Some(ref nationality) => nationality.subdivision_by_code_or_name(ident.as_ref(), connection).await?,
+                        None => return Err(PointercrateError::SubdivisionWithoutNationality),
+                    };
+
+                    self.player.set_subdivision(subdivision, connection).await?
+                },
+                None => self.player.reset_subdivision(connection).await?,
+            }
+        }
+
         if let Some(banned) = patch.banned {
             if banned && !self.player.base.banned {
                 self.player.base.ban(connection).await?;
@@ -90,76 +107,194 @@ impl FullPlayer {
 
     /// Merges the given player into `Self`, deleting `with`.
     ///
-    /// Note that this method **does not** rename `Self`
+    /// The merge is logged into the `player_merges` audit log table, together with everything
+    /// needed to [undo](DatabasePlayer::split) it later. Note that this method **does not** rename
+    /// `Self`
     pub async fn merge(&mut self, with: DatabasePlayer, connection: &mut PgConnection) -> Result<()> {
         info!("Merging player {} with player {}", self, with);
+
+        let merged_row = sqlx::query!(
+            "SELECT nationality::TEXT, subdivision::TEXT, link_banned, hide_videos FROM players WHERE id = $1",
+            with.id
+        )
+        .fetch_one(&mut *connection)
+        .await?;
+
         // First, delete duplicate creator entries
 
-        let deleted = sqlx::query!(
+        let deleted: Vec<i32> = sqlx::query!(
             "DELETE FROM creators AS c1 WHERE c1.creator = $2 AND EXISTS (SELECT 1 FROM creators AS c2 WHERE c2.demon = c1.demon AND \
-             c2.creator = $1)",
+             c2.creator = $1) RETURNING demon",
             self.player.base.id,
             with.id
         )
-        .execute(&mut *connection)
-        .await?;
+        .fetch_all(&mut *connection)
+        .await?
+        .into_iter()
+        .map(|row| row.demon)
+        .collect();
 
         info!(
             "Deleted {} duplicate creator entries while merging {} and {}",
-            deleted.rows_affected(),
+            deleted.len(),
             self,
             with
         );
 
         // Transfer all other creator entries over
-        let updated = sqlx::query!("UPDATE creators SET creator = $1 WHERE creator = $2", self.player.base.id, with.id)
-            .execute(&mut *connection)
-            .await?;
+        let creators_moved: Vec<i32> = sqlx::query!(
+            "UPDATE creators SET creator = $1 WHERE creator = $2 RETURNING demon",
+            self.player.base.id,
+            with.id
+        )
+        .fetch_all(&mut *connection)
+        .await?
+        .into_iter()
+        .map(|row| row.demon)
+        .collect();
 
-        info!("Transferred {} creator entries from {} to {}", updated.rows_affected(), with, self);
+        info!("Transferred {} creator entries from {} to {}", creators_moved.len(), with, self);
 
         // Transfer over verifier and publisher entries
 
-        let updated_verifiers = sqlx::query!("UPDATE demons SET verifier = $1 WHERE verifier = $2", self.player.base.id, with.id)
-            .execute(&mut *connection)
-            .await?;
-        let updated_publishers = sqlx::query!(
-            "UPDATE demons SET publisher = $1 WHERE publisher = $2",
+        let updated_verifiers: Vec<i32> = sqlx::query!(
+            "UPDATE demons SET verifier = $1 WHERE verifier = $2 RETURNING id",
             self.player.base.id,
             with.id
         )
-        .execute(&mut *connection)
-        .await?;
+        .fetch_all(&mut *connection)
+        .await?
+        .into_iter()
+        .map(|row| row.id)
+        .collect();
+        let updated_publishers: Vec<i32> = sqlx::query!(
+            "UPDATE demons SET publisher = $1 WHERE publisher = $2 RETURNING id",
+            self.player.base.id,
+            with.id
+        )
+        .fetch_all(&mut *connection)
+        .await?
+        .into_iter()
+        .map(|row| row.id)
+        .collect();
 
         info!(
             "Transferred over {} verifier and {} publisher entires from {} to {}",
-            updated_verifiers.rows_affected(),
-            updated_publishers.rows_affected(),
+            updated_verifiers.len(),
+            updated_publishers.len(),
             with,
             self
         );
 
-        // Alright so merging records is HARD. We already implemented it over in the record patching, so
-        // while somewhat inefficient maybe, we'll just call that code for each record of the current player
-        for row in sqlx::query!("SELECT id FROM records WHERE player = $1", with.id)
+        let target_records: Vec<i32> = sqlx::query!("SELECT id FROM records WHERE player = $1", self.player.base.id)
             .fetch_all(&mut *connection)
             .await?
-        {
+            .into_iter()
+            .map(|row| row.id)
+            .collect();
+        let records = sqlx::query!("SELECT id FROM records WHERE player = $1", with.id)
+            .fetch_all(&mut *connection)
+            .await?;
+        let mut merged_records = Vec::new();
+
+        // Alright so merging records is HARD. We already implemented it over in the record patching, so
+        // while somewhat inefficient maybe, we'll just call that code for each record of the current player
+        for row in records {
             // FIXME: this is really inefficient and can be made a lot faster by simple moving around some code
             // in the FullRecord impls
             let mut record = FullRecord::by_id(row.id, &mut *connection).await?;
             info!("Moving record {} over to new player {}", record, self.player.base);
-            record.set_player(self.player.base.clone(), &mut *connection).await?
+            record.set_player(self.player.base.clone(), &mut *connection).await?;
+            merged_records.push(row.id);
         }
 
         self.records = approved_records_by(&self.player.base, &mut *connection).await?;
 
         // Transfer all records over, now that they're unique
-        let updated = sqlx::query!("UPDATE records SET player = $1 WHERE player = $2", self.player.base.id, with.id)
-            .execute(&mut *connection)
-            .await?;
+        let updated = sqlx::query!(
+            "UPDATE records SET player = $1 WHERE player = $2 RETURNING id",
+            self.player.base.id,
+            with.id
+        )
+        .fetch_all(&mut *connection)
+        .await?;
+
+        info!("Moved {} records from {} to {}", updated.len(), with, self);
+
+        // Transfer claims over. If both players have a verified claim, the one on the merged player
+        // has to be verified again
+        let (merged_claims, merged_claims_verified): (Vec<i32>, Vec<bool>) =
+            sqlx::query!("SELECT member_id, verified FROM player_claims WHERE player_id = $1", with.id)
+                .fetch_all(&mut *connection)
+                .await?
+                .into_iter()
+                .map(|row| (row.member_id, row.verified))
+                .unzip();
+
+        sqlx::query!(
+            "UPDATE player_claims SET player_id = $1, verified = verified AND NOT EXISTS (SELECT 1 FROM player_claims WHERE player_id = \
+             $1 AND verified) WHERE player_id = $2",
+            self.player.base.id,
+            with.id
+        )
+        .execute(&mut *connection)
+        .await?;
 
-        info!("Moved {} records from {} to {}", updated.rows_affected(), with, self);
+        merged_records.extend(updated.into_iter().map(|row| row.id));
+
+        // Moving a record over might have deleted records of either player (including records moved
+        // over earlier), so check which of them are still around
+        let remaining: Vec<i32> = sqlx::query!(
+            "SELECT id FROM records WHERE id = ANY($1) OR id = ANY($2)",
+            &merged_records,
+            &target_records
+        )
+        .fetch_all(&mut *connection)
+        .await?
+        .into_iter()
+        .map(|row| row.id)
+        .collect();
+
+        let moved_records: Vec<i32> = merged_records.iter().copied().filter(|id| remaining.contains(id)).collect();
+        let deleted_records: Vec<i32> = target_records
+            .into_iter()
+            .chain(merged_records)
+            .filter(|id| !remaining.contains(id))
+            .collect();
+
+        info!("Merging the records of {} and {} deleted records {:?}", self, with, deleted_records);
+
+        sqlx::query(
+            "INSERT INTO player_merges (userid, id, merged_id, merged_name, records_moved, creators_moved, creators_deleted, \
+             verifications_moved, publications_moved, merged_banned, merged_link_banned, merged_hide_videos, merged_nationality, \
+             merged_subdivision, merged_claims, merged_claims_verified, target_name, moved_records, deleted_records, moved_creators, \
+             deleted_creators, verified_demons, published_demons) (SELECT id, $1, $2, $3::text, $4, $5, $6, $7, $8, $9, $10, $11, $12, \
+             $13, $14, $15, $16::text, $17, $18, $19, $20, $21, $22 FROM active_user LIMIT 1)",
+        )
+        .bind(self.player.base.id)
+        .bind(with.id)
+        .bind(with.name.to_string())
+        .bind(moved_records.len() as i32)
+        .bind(creators_moved.len() as i32)
+        .bind(deleted.len() as i32)
+        .bind(updated_verifiers.len() as i32)
+        .bind(updated_publishers.len() as i32)
+        .bind(with.banned)
+        .bind(merged_row.link_banned)
+        .bind(merged_row.hide_videos)
+        .bind(merged_row.nationality)
+        .bind(merged_row.subdivision)
+        .bind(&merged_claims)
+        .bind(&merged_claims_verified)
+        .bind(self.player.base.name.to_string())
+        .bind(&moved_records)
+        .bind(&deleted_records)
+        .bind(&creators_moved)
+        .bind(&deleted)
+        .bind(&updated_verifiers)
+        .bind(&updated_publishers)
+        .execute(&mut *connection)
+        .await?;
 
         // Delete the second player
         sqlx::query!("DELETE FROM players WHERE id = $1", with.id)
@@ -172,18 +307,29 @@ impl FullPlayer {
 
 impl Player {
     pub async fn reset_nationality(&mut self, connection: &mut PgConnection) -> Result<()> {
-        sqlx::query!("UPDATE players SET nationality = NULL WHERE id = $1", self.base.id)
-            .execute(connection)
-            .await?;
+        sqlx::query!(
+            "UPDATE players SET nationality = NULL, subdivision = NULL WHERE id = $1",
+            self.base.id
+        )
+        .execute(connection)
+        .await?;
 
         self.nationality = None;
 
         Ok(())
     }
 
-    pub async fn set_nationality(&mut self, nationality: Nationality, connection: &mut PgConnection) -> Result<()> {
+    /// Sets this player's nationality. Unless it stays the same, this also resets the player's
+    /// subdivision
+    pub async fn set_nationality(&mut self, mut nationality: Nationality, connection: &mut PgConnection) -> Result<()> {
+        if let Some(current) = self.nationality.take() {
+            if current.iso_country_code == nationality.iso_country_code {
+                nationality.subdivision = current.subdivision;
+            }
+        }
+
         sqlx::query!(
-            "UPDATE players SET nationality = $1::text WHERE id = $2",
+            "UPDATE players SET nationality = $1::text, subdivision = CASE WHEN nationality = $1::text THEN subdivision END WHERE id = $2",
             nationality.iso_country_code,
             self.base.id
         )
@@ -194,6 +340,36 @@ impl Player {
 
         Ok(())
     }
+
+    /// Sets the subdivision of this player's nationality they are from. The subdivision needs to
+    /// belong to the player's nationality
+    pub async fn set_subdivision(&mut self, subdivision: Subdivision, connection: &mut PgConnection) -> Result<()> {
+        sqlx::query!(
+            "UPDATE players SET subdivision = $1::text WHERE id = $2",
+            subdivision.iso_code,
+            self.base.id
+        )
+        .execute(connection)
+        .await?;
+
+        if let Some(ref mut nationality) = self.nationality {
+            nationality.subdivision = Some(subdivision);
+        }
+
+        Ok(())
+    }
+
+    pub async fn reset_subdivision(&mut self, connection: &mut PgConnection) -> Result<()> {
+        sqlx::query!("UPDATE players SET subdivision = NULL WHERE id = $1", self.base.id)
+            .execute(connection)
+            .await?;
+
+        if let Some(ref mut nationality) = self.nationality {
+            nationality.subdivision = None;
+        }
+
+        Ok(())
+    }
 }
 
 impl DatabasePlayer {
@@ -249,6 +425,17 @@ mod tests {
             record::{RecordPagination, RecordStatus},
         },
     };
+    use sqlx::PgConnection;
+
+    async fn record_ids(player_id: i32, connection: &mut PgConnection) -> Vec<i32> {
+        sqlx::query!("SELECT id FROM records WHERE player = $1 ORDER BY id", player_id)
+            .fetch_all(connection)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| row.id)
+            .collect()
+    }
 
     #[actix_rt::test]
     async fn test_cosmetic_rename() {
@@ -320,4 +507,191 @@ mod tests {
             assert_eq!(record.status, RecordStatus::Rejected);
         }
     }
+
+    #[actix_rt::test]
+    async fn test_subdivision_changes_are_audited() {
+        let mut connection = crate::test::test_setup().await;
+
+        crate::state::audit_connection(&mut connection, 0).await.unwrap();
+
+        let player_id = DatabasePlayer::by_name(CiStr::from_str("stardust1971"), &mut connection)
+            .await
+            .unwrap()
+            .id;
+
+        for (nationality, subdivision) in &[
+            ("US", Some("CA")),
+            ("US", Some("TX")),
+            ("US", None),
+            ("CA", Some("ON")),
+            ("DE", Some("BY")),
+        ] {
+            sqlx::query!(
+                "UPDATE players SET nationality = $1, subdivision = $2 WHERE id = $3",
+                *nationality,
+                *subdivision,
+                player_id
+            )
+            .execute(&mut connection)
+            .await
+            .unwrap();
+        }
+
+        let logged: Vec<Option<String>> = sqlx::query!(
+            "SELECT nation || '-' || subdivision AS subdivision FROM player_subdivision_modifications WHERE id = $1 ORDER BY audit_id",
+            player_id
+        )
+        .fetch_all(&mut connection)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| row.subdivision)
+        .collect();
+
+        // Entries contain the old subdivision, and stardust1971 initially had none
+        assert_eq!(logged, vec![
+            None,
+            Some("US-CA".to_owned()),
+            Some("US-TX".to_owned()),
+            None,
+            Some("CA-ON".to_owned())
+        ]);
+    }
+
+    #[actix_rt::test]
+    async fn test_merge_and_split() {
+        let mut connection = crate::test::test_setup().await;
+
+        crate::state::audit_connection(&mut connection, 0).await.unwrap();
+
+        let stardust = DatabasePlayer::by_name(CiStr::from_str("stardust1971"), &mut connection)
+            .await
+            .unwrap();
+        let aquatias = DatabasePlayer::by_name(CiStr::from_str("Aquatias"), &mut connection).await.unwrap();
+
+        let stardust_records = record_ids(stardust.id, &mut connection).await;
+        let aquatias_records = record_ids(aquatias.id, &mut connection).await;
+
+        // Aquatias has a rejected record on the demon stardust1971 has a submission and an approved
+        // record on, which get deleted when the rejected record is moved over
+        let deleted_by_merge = sqlx::query!(
+            "SELECT id FROM records WHERE player = $1 AND demon = (SELECT demon FROM records WHERE player = $2) ORDER BY id",
+            stardust.id,
+            aquatias.id
+        )
+        .fetch_all(&mut connection)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| row.id)
+        .collect::<Vec<_>>();
+
+        assert_eq!(deleted_by_merge.len(), 2);
+
+        sqlx::query!(
+            "UPDATE players SET nationality = 'US', subdivision = 'CA' WHERE id = $1",
+            aquatias.id
+        )
+        .execute(&mut connection)
+        .await
+        .unwrap();
+
+        // Both players are claimed, so the claim on Aquatias loses its verification when merging
+        let members =
+            sqlx::query!("INSERT INTO members (name, password_hash) VALUES ('claimer1', ''), ('claimer2', '') RETURNING member_id")
+                .fetch_all(&mut connection)
+                .await
+                .unwrap();
+
+        sqlx::query!(
+            "INSERT INTO player_claims (member_id, player_id, verified) VALUES ($1, $2, TRUE), ($3, $4, TRUE)",
+            members[0].member_id,
+            stardust.id,
+            members[1].member_id,
+            aquatias.id
+        )
+        .execute(&mut connection)
+        .await
+        .unwrap();
+
+        let mut player = Player::by_id(stardust.id, &mut connection)
+            .await
+            .unwrap()
+            .upgrade(&mut connection)
+            .await
+            .unwrap();
+
+        let result = player.merge(aquatias.clone(), &mut connection).await;
+
+        assert!(result.is_ok(), "{:?}", result.unwrap_err());
+        assert!(DatabasePlayer::by_id(aquatias.id, &mut connection).await.is_err());
+
+        let merge = sqlx::query!(
+            "SELECT audit_id, moved_records, deleted_records FROM player_merges WHERE id = $1",
+            stardust.id
+        )
+        .fetch_one(&mut connection)
+        .await
+        .unwrap();
+
+        assert_eq!(merge.moved_records, Some(aquatias_records.clone()));
+
+        let mut logged_deletions = merge.deleted_records.unwrap();
+        logged_deletions.sort();
+
+        assert_eq!(logged_deletions, deleted_by_merge);
+
+        let claim = sqlx::query!(
+            "SELECT player_id, verified FROM player_claims WHERE member_id = $1",
+            members[1].member_id
+        )
+        .fetch_one(&mut connection)
+        .await
+        .unwrap();
+
+        assert_eq!(claim.player_id, stardust.id);
+        assert!(!claim.verified);
+
+        let mut target = DatabasePlayer::by_id(stardust.id, &mut connection).await.unwrap();
+        let result = target.split(merge.audit_id, &mut connection).await;
+
+        assert!(result.is_ok(), "{:?}", result.unwrap_err());
+
+        let (restored, mut still_deleted) = result.unwrap();
+        still_deleted.sort();
+
+        assert_eq!(restored, aquatias);
+        assert_eq!(still_deleted, deleted_by_merge);
+
+        let restored_row = sqlx::query!(
+            "SELECT nationality::TEXT, subdivision::TEXT FROM players WHERE id = $1",
+            aquatias.id
+        )
+        .fetch_one(&mut connection)
+        .await
+        .unwrap();
+
+        assert_eq!(restored_row.nationality.as_deref(), Some("US"));
+        assert_eq!(restored_row.subdivision.as_deref(), Some("CA"));
+
+        let claims = sqlx::query!(
+            "SELECT member_id, player_id, verified FROM player_claims WHERE member_id = $1 OR member_id = $2 ORDER BY member_id",
+            members[0].member_id,
+            members[1].member_id
+        )
+        .fetch_all(&mut connection)
+        .await
+        .unwrap();
+
+        assert_eq!((claims[0].player_id, claims[0].verified), (stardust.id, true));
+        assert_eq!((claims[1].player_id, claims[1].verified), (aquatias.id, true));
+        assert_eq!(record_ids(aquatias.id, &mut connection).await, aquatias_records);
+        assert_eq!(
+            record_ids(stardust.id, &mut connection).await,
+            stardust_records
+                .into_iter()
+                .filter(|id| !deleted_by_merge.contains(id))
+                .collect::<Vec<_>>()
+        );
+    }
 }
@@ -0,0 +1,161 @@
+//! Module containing code for previewing player merges
+//!
+//! Merging two players moves all records of one player over to the other, which, due to the
+//! uniqueness rules for records (see [`FullRecord::set_player`]), might delete some of them or move
+//! their notes to a different record. Since reproducing these rules here would be error prone, a
+//! preview is generated by actually performing the merge and comparing the records of both players
+//! before and after. It is the caller's responsibility to roll back the transaction afterwards.
+
+use crate::{
+    cistring::CiStr,
+    error::PointercrateError,
+    model::demonlist::{
+        player::{DatabasePlayer, FullPlayer, PatchPlayer},
+        record::{FullRecord, MinimalRecordPD},
+    },
+    Result,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::PgConnection;
+
+#[derive(Debug, Deserialize, Default)]
+pub struct DryRun {
+    /// Whether the request should only report what it would do, without making any changes
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// A note that was moved from one record to another
+#[derive(Debug, Serialize)]
+pub struct MovedNote {
+    pub id: i32,
+
+    /// The ID of the record the note was moved from (which was deleted)
+    pub from: i32,
+
+    /// The ID of the record the note was moved to
+    pub to: i32,
+}
+
+#[derive(Debug, Serialize, Default)]
+pub struct MergePreview {
+    /// Records of either player that would be deleted
+    pub deleted: Vec<MinimalRecordPD>,
+
+    /// Records that would be transferred to the player merged into
+    pub transferred: Vec<MinimalRecordPD>,
+    pub moved_notes: Vec<MovedNote>,
+}
+
+/// The state of all records of some players before a merge
+struct RecordSnapshot {
+    records: Vec<FullRecord>,
+}
+
+impl RecordSnapshot {
+    async fn take(players: &[i32], connection: &mut PgConnection) -> Result<RecordSnapshot> {
+        let rows = sqlx::query!("SELECT id FROM records WHERE player = ANY($1)", players)
+            .fetch_all(&mut *connection)
+            .await?;
+
+        let mut records = Vec::new();
+
+        for row in rows {
+            records.push(FullRecord::by_id(row.id, &mut *connection).await?);
+        }
+
+        Ok(RecordSnapshot { records })
+    }
+
+    async fn compare(self, connection: &mut PgConnection) -> Result<MergePreview> {
+        let mut preview = MergePreview::default();
+
+        for record in self.records {
+            let current = sqlx::query!("SELECT player FROM records WHERE id = $1", record.id)
+                .fetch_optional(&mut *connection)
+                .await?;
+
+            for note in &record.notes {
+                let moved_to = sqlx::query!("SELECT record FROM record_notes WHERE id = $1", note.id)
+                    .fetch_optional(&mut *connection)
+                    .await?;
+
+                if let Some(row) = moved_to {
+                    if row.record != record.id {
+                        preview.moved_notes.push(MovedNote {
+                            id: note.id,
+                            from: record.id,
+                            to: row.record,
+                        })
+                    }
+                }
+            }
+
+            let transferred = match current {
+                None => false,
+                Some(ref row) if row.player == record.player.id => continue,
+                Some(_) => true,
+            };
+
+            let record = MinimalRecordPD {
+                id: record.id,
+                progress: record.progress,
+                video: record.video,
+                status: record.status,
+                demon: record.demon,
+                player: record.player,
+            };
+
+            if transferred {
+                preview.transferred.push(record)
+            } else {
+                preview.deleted.push(record)
+            }
+        }
+
+        Ok(preview)
+    }
+}
+
+impl FullPlayer {
+    /// Merges the given player into `self` and reports what happened to the records of both
+    /// players.
+    ///
+    /// The transaction this is called in must be rolled back afterwards
+    pub async fn preview_merge(&mut self, with: DatabasePlayer, connection: &mut PgConnection) -> Result<MergePreview> {
+        if with.id == self.player.base.id {
+            return Err(PointercrateError::BadRequest {
+                message: "A player cannot be merged with itself".to_string(),
+            })
+        }
+
+        let snapshot = RecordSnapshot::take(&[self.player.base.id, with.id], &mut *connection).await?;
+
+        self.merge(with, &mut *connection).await?;
+
+        snapshot.compare(connection).await
+    }
+
+    /// Applies the given patch and reports what happened to the records of this player and of
+    /// any player it would be merged with.
+    ///
+    /// The transaction this is called in must be rolled back afterwards
+    pub async fn preview_patch(self, patch: PatchPlayer, connection: &mut PgConnection) -> Result<(FullPlayer, MergePreview)> {
+        let mut players = vec![self.player.base.id];
+
+        if let Some(ref name) = patch.name {
+            match DatabasePlayer::by_name(CiStr::from_str(name.trim()), &mut *connection).await {
+                Ok(existing) => players.push(existing.id),
+                Err(PointercrateError::ModelNotFound { .. }) => (),
+                Err(err) => return Err(err),
+            }
+        }
+
+        let snapshot = RecordSnapshot::take(&players, &mut *connection).await?;
+
+        let player = self.apply_patch(patch, &mut *connection).await?;
+        let preview = snapshot.compare(connection).await?;
+
+        Ok((player, preview))
+    }
+}
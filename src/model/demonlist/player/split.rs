@@ -0,0 +1,212 @@
+//! Module containing code for undoing player merges
+//!
+//! When merging two players, everything that is moved over to the player merged into (and the
+//! state of the merged player itself) is logged into `player_merges`. This allows undoing a merge
+//! by recreating the merged player with its original ID and moving everything back.
+//!
+//! Note that merging records might have deleted some of them, and that these deletions are not
+//! undone. Instead, the deleted records are reported, so that they can be restored via the record
+//! audit log if needed. Objects that were changed since the merge (e.g. a record that has been
+//! moved to a different player, or a claim that has been withdrawn) are left alone.
+
+use crate::{
+    cistring::CiString,
+    error::PointercrateError,
+    model::demonlist::{player::DatabasePlayer, record::FullRecord},
+    Result,
+};
+use log::info;
+use sqlx::{Done, PgConnection};
+
+impl DatabasePlayer {
+    /// Undoes the merge logged by the `player_merges` entry with the given audit log ID, which
+    /// needs to be a merge into `self`.
+    ///
+    /// Returns the recreated player, together with the IDs of the records deleted during the merge
+    /// that have not been restored since
+    pub async fn split(&mut self, merge_audit_id: i32, connection: &mut PgConnection) -> Result<(DatabasePlayer, Vec<i32>)> {
+        let merge = sqlx::query!(
+            r#"SELECT merged_id, merged_name::TEXT AS "merged_name!", merged_banned, merged_link_banned, merged_hide_videos,
+                      merged_nationality::TEXT, merged_subdivision::TEXT, merged_claims, merged_claims_verified,
+                      target_name::TEXT, moved_records, deleted_records, moved_creators, deleted_creators, verified_demons, published_demons
+                      FROM player_merges WHERE id = $1 AND audit_id = $2"#,
+            self.id,
+            merge_audit_id
+        )
+        .fetch_optional(&mut *connection)
+        .await?
+        .ok_or_else(|| {
+            PointercrateError::ModelNotFound {
+                model: "Player merge",
+                identified_by: merge_audit_id.to_string(),
+            }
+        })?;
+
+        let already_undone = sqlx::query!(
+            r#"SELECT EXISTS (SELECT 1 FROM player_splits WHERE merge = $1) AS "exists!: bool""#,
+            merge_audit_id
+        )
+        .fetch_one(&mut *connection)
+        .await?
+        .exists;
+
+        if already_undone {
+            return Err(PointercrateError::MergeAlreadyUndone)
+        }
+
+        let (banned, target_name, moved_records, deleted_records, moved_creators, deleted_creators, verified_demons, published_demons) =
+            match (
+                merge.merged_banned,
+                merge.target_name,
+                merge.moved_records,
+                merge.deleted_records,
+                merge.moved_creators,
+                merge.deleted_creators,
+                merge.verified_demons,
+                merge.published_demons,
+            ) {
+                (Some(a), Some(b), Some(c), Some(d), Some(e), Some(f), Some(g), Some(h)) => (a, b, c, d, e, f, g, h),
+                _ => return Err(PointercrateError::IrreversibleMerge),
+            };
+
+        let merged_name = CiString(merge.merged_name);
+
+        info!("Undoing merge of player {} into {}", merged_name, self);
+
+        // Merges usually happen by renaming a player to the name of an existing one, meaning the
+        // player merged into now carries the merged player's name
+        if self.name == merged_name {
+            let target_name = CiString(target_name);
+
+            match DatabasePlayer::by_name(target_name.as_ref(), &mut *connection).await {
+                Ok(existing) if existing.id != self.id => return Err(PointercrateError::Conflict),
+                Err(PointercrateError::ModelNotFound { .. }) | Ok(_) => (),
+                Err(err) => return Err(err),
+            }
+
+            sqlx::query!("UPDATE players SET name = $1::text WHERE id = $2", target_name.to_string(), self.id)
+                .execute(&mut *connection)
+                .await?;
+
+            self.name = target_name;
+        }
+
+        // Recreate the player as unbanned, since records can only be moved to banned players if
+        // they are rejected. It is banned again after all its records are moved back.
+        let inserted = sqlx::query!(
+            "INSERT INTO players (id, name, nationality, subdivision, link_banned, hide_videos) VALUES ($1, $2::text, $3::text, $4::text, \
+             $5, $6) ON CONFLICT DO NOTHING",
+            merge.merged_id,
+            merged_name.to_string(),
+            merge.merged_nationality,
+            merge.merged_subdivision,
+            merge.merged_link_banned.unwrap_or(false),
+            merge.merged_hide_videos.unwrap_or(false)
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        if inserted.rows_affected() == 0 {
+            // Another player with that ID or name exists
+            return Err(PointercrateError::Conflict)
+        }
+
+        let mut restored = DatabasePlayer {
+            id: merge.merged_id,
+            name: merged_name,
+            banned: false,
+        };
+
+        let records = sqlx::query!("SELECT id FROM records WHERE player = $1 AND id = ANY($2)", self.id, &moved_records)
+            .fetch_all(&mut *connection)
+            .await?;
+
+        for row in records {
+            let mut record = FullRecord::by_id(row.id, &mut *connection).await?;
+
+            info!("Moving record {} back to player {}", record, restored);
+
+            record.set_player(restored.clone(), &mut *connection).await?;
+        }
+
+        sqlx::query!(
+            "UPDATE creators SET creator = $1 WHERE creator = $2 AND demon = ANY($3)",
+            restored.id,
+            self.id,
+            &moved_creators
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        sqlx::query!(
+            "INSERT INTO creators (demon, creator) SELECT id, $1 FROM demons WHERE id = ANY($2)",
+            restored.id,
+            &deleted_creators
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        sqlx::query!(
+            "UPDATE demons SET verifier = $1 WHERE verifier = $2 AND id = ANY($3)",
+            restored.id,
+            self.id,
+            &verified_demons
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        sqlx::query!(
+            "UPDATE demons SET publisher = $1 WHERE publisher = $2 AND id = ANY($3)",
+            restored.id,
+            self.id,
+            &published_demons
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        // Claims are only moved back if they are still on the player merged into. Since the restored
+        // player has no claims yet, verified claims can simply be restored as such.
+        if let (Some(claims), Some(claims_verified)) = (merge.merged_claims, merge.merged_claims_verified) {
+            sqlx::query!(
+                "UPDATE player_claims SET player_id = $1, verified = merged.verified FROM UNNEST($3::INTEGER[], $4::BOOLEAN[]) AS \
+                 merged(member_id, verified) WHERE player_claims.member_id = merged.member_id AND player_claims.player_id = $2",
+                restored.id,
+                self.id,
+                &claims,
+                &claims_verified
+            )
+            .execute(&mut *connection)
+            .await?;
+        }
+
+        if banned {
+            restored.ban(&mut *connection).await?;
+        }
+
+        let restored_records: Vec<i32> = sqlx::query!("SELECT id FROM records WHERE id = ANY($1)", &deleted_records)
+            .fetch_all(&mut *connection)
+            .await?
+            .into_iter()
+            .map(|row| row.id)
+            .collect();
+        let still_deleted: Vec<i32> = deleted_records.into_iter().filter(|id| !restored_records.contains(id)).collect();
+
+        if !still_deleted.is_empty() {
+            info!(
+                "Records {:?} were deleted when {} was merged into {} and are not restored",
+                still_deleted, restored, self
+            );
+        }
+
+        sqlx::query!(
+            "INSERT INTO player_splits (userid, id, merge, restored_id) (SELECT id, $1, $2, $3 FROM active_user LIMIT 1)",
+            self.id,
+            merge_audit_id,
+            restored.id
+        )
+        .execute(connection)
+        .await?;
+
+        Ok((restored, still_deleted))
+    }
+}
@@ -22,36 +22,44 @@
 //!   the 'under consideration' status makes. A record under consideration IS NOT UNIQUE!
 
 pub use self::{
-    get::{approved_records_by, approved_records_on},
+    assignment::{release_stale_assignments, Assignee},
+    bulk::{BulkOperation, BulkOutcome, BulkPatch, MAX_BULK_OPERATIONS},
+    get::{approved_records_by, approved_records_by_at, approved_records_on, approved_records_on_at, pending_records_by},
+    lookup::SubmissionStatus,
     paginate::RecordPagination,
     patch::PatchRecord,
     post::Submission,
+    verification::{verify_videos, VideoVerificationStatus},
 };
 use crate::{
     model::{
-        demonlist::{demon::MinimalDemon, player::DatabasePlayer, record::note::Note, submitter::Submitter},
+        demonlist::{
+            demon::MinimalDemon, player::DatabasePlayer, record::note::Note, rejection_reason::RejectionReason, submitter::Submitter,
+        },
         nationality::Nationality,
     },
-    state::PointercrateState,
     Result,
 };
 use derive_more::Display;
-use log::{debug, error, warn};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use serde_json::json;
 use sqlx::PgConnection;
 use std::{
     fmt::{Display, Formatter},
     hash::{Hash, Hasher},
 };
 
+mod assignment;
 pub mod audit;
+mod bulk;
 mod delete;
 mod get;
+mod lookup;
 pub mod note;
 mod paginate;
 mod patch;
 mod post;
+mod revert;
+mod verification;
 
 #[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
 pub enum RecordStatus {
@@ -72,7 +80,7 @@ impl RecordStatus {
         .to_owned()
     }
 
-    fn from_sql(sql: &str) -> Self {
+    pub(crate) fn from_sql(sql: &str) -> Self {
         match sql {
             "SUBMITTED" => RecordStatus::Submitted,
             "APPROVED" => RecordStatus::Approved,
@@ -141,6 +149,17 @@ pub struct FullRecord {
     pub demon: MinimalDemon,
     pub submitter: Option<Submitter>,
     pub notes: Vec<Note>,
+
+    /// The state of the verification of this record's video. `None` if the video has been
+    /// verified to exist (or if verification was never needed, e.g. for records added by list
+    /// helpers)
+    pub video_verification: Option<VideoVerificationStatus>,
+
+    /// The list helper currently reviewing this record, if any
+    pub assignee: Option<Assignee>,
+
+    /// The reason this record was rejected for. Only ever set if this record is rejected
+    pub rejection_reason: Option<RejectionReason>,
 }
 
 impl Hash for FullRecord {
@@ -151,6 +170,8 @@ impl Hash for FullRecord {
         self.status.hash(state);
         self.player.id.hash(state);
         self.demon.id.hash(state);
+        self.assignee.hash(state);
+        self.rejection_reason.hash(state);
         // notes have sub-endpoint -> no hash
         // submitter cannot be patched -> no hash
     }
@@ -188,12 +209,6 @@ pub struct MinimalRecordP {
     pub nationality: Option<Nationality>,
 }
 
-#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
-pub enum WebhookType {
-    Add,
-    Edited,
-}
-
 impl FullRecord {
     /// Gets the maximal and minimal submitter id currently in use
     ///
@@ -204,155 +219,4 @@ impl FullRecord {
             .await?; // FIXME: crashes on empty table
         Ok((row.max_id, row.min_id))
     }
-
-    pub async fn validate(self, state: PointercrateState) {
-        let mut connection = match state.connection().await {
-            Ok(connection) => connection,
-            Err(err) => return error!("INTERNAL SERVER ERROR: failed to acquire database connection: {:?}", err),
-        };
-
-        let video = match self.video {
-            Some(ref video) => video,
-            None => return,
-        };
-
-        debug!("Verifying that submission {} with video {} actually is valid", self, video);
-
-        match state.http_client.head(video).send().await {
-            Ok(response) => {
-                let status = response.status().as_u16();
-
-                if status == 401 || status == 403 || status == 405 {
-                    // Some websites (billibilli) respond unfavorably to HEAD requests. Retry with
-                    // GET
-                    match state.http_client.get(video).send().await {
-                        Ok(response) => {
-                            let status = response.status().as_u16();
-
-                            if status >= 200 && status < 400 {
-                                debug!("HEAD request yielded some sort of successful response, executing webhook");
-
-                                self.execute_webhook(&state, WebhookType::Add).await;
-                            }
-                        },
-                        Err(err) => {
-                            error!(
-                                "INTERNAL SERVER ERROR: HEAD request to verify video failed: {:?}. Deleting submission",
-                                err
-                            );
-
-                            match self.delete(&mut connection).await {
-                                Ok(_) => (),
-                                Err(error) => error!("INTERNAL SERVER ERROR: Failure to delete record - {:?}!", error),
-                            }
-                        },
-                    }
-                } else if status >= 200 && status < 400 {
-                    debug!("HEAD request yielded some sort of successful response, executing webhook");
-
-                    self.execute_webhook(&state, WebhookType::Add).await;
-                } else {
-                    warn!("Server response to 'HEAD {}' was {:?}, deleting submission!", video, response);
-
-                    match self.delete(&mut connection).await {
-                        Ok(_) => (),
-                        Err(error) => error!("INTERNAL SERVER ERROR: Failure to delete record - {:?}!", error),
-                    }
-                }
-            },
-            Err(error) => {
-                error!(
-                    "INTERNAL SERVER ERROR: HEAD request to verify video failed: {:?}. Deleting submission",
-                    error
-                );
-
-                match self.delete(&mut connection).await {
-                    Ok(_) => (),
-                    Err(error) => error!("INTERNAL SERVER ERROR: Failure to delete record - {:?}!", error),
-                }
-            },
-        }
-    }
-
-    pub async fn edit_webhook(self, state: PointercrateState) {
-        // not really going to check much lol
-        debug!("Executing edited record webhook");
-        self.execute_webhook(&state, WebhookType::Edited).await;
-    }
-
-    async fn execute_webhook(&self, state: &PointercrateState, webhook_type: WebhookType) {
-        if let Some(ref webhook_url) = state.webhook_url {
-            let body = match webhook_type {
-                WebhookType::Edited => self.webhook_status_embed().to_string(),
-                _ => self.webhook_embed().to_string(),
-            };
-
-            match state
-                .http_client
-                .post(&**webhook_url)
-                .header("Content-Type", "application/json")
-                .body(body)
-                .send()
-                .await
-            {
-                Err(error) => error!("INTERNAL SERVER ERROR: Failure to execute discord webhook: {:?}", error),
-                Ok(_) => debug!("Successfully executed discord webhook"),
-            }
-        } else {
-            warn!("Trying to execute webhook, though no link was configured!");
-        }
-    }
-
-    fn webhook_embed(&self) -> serde_json::Value {
-        let mut payload = json!({
-            "content": format!("**New record submitted! ID: {}**", self.id),
-            "embeds": [
-                {
-                    "type": "rich",
-                    "title": format!("{}% on {}", self.progress, self.demon.name),
-                    "description": format!("{} just got {}% on {}! Go add their record!", self.player.name, self.progress, self.demon.name),
-                    "footer": {
-                        "text": format!("This record has been submitted by submitter #{}", self.submitter.map(|s|s.id).unwrap_or(1))
-                    },
-                    "author": {
-                        "name": format!("{} (ID: {})", self.player.name, self.player.id),
-                        "url": self.video
-                    },
-                    "thumbnail": {
-                        "url": "https://cdn.discordapp.com/emojis/561867333476286464.png?size=1024"
-                    },
-                }
-            ]
-        });
-
-        if let Some(ref video) = self.video {
-            payload["embeds"][0]["fields"] = json! {
-                [{
-                    "name": "Video Proof:",
-                    "value": video
-                }]
-            };
-        }
-
-        payload
-    }
-
-    fn webhook_status_embed(&self) -> serde_json::Value {
-        let payload = json!({
-            "content": format!("**Record edited! ID: {}**", self.id),
-            "embeds": [
-                {
-                    "type": "rich",
-                    "title": format!("{}% on {}", self.progress, self.demon.name),
-                    "description": format!("{}'s record's status has been set to `{}`!", self.player.name, self.status),
-                    "author": {
-                        "name": format!("Owner: {} (ID: {})", self.player.name, self.player.id),
-                        "url": self.video
-                    },
-                }
-            ]
-        });
-
-        payload
-    }
 }
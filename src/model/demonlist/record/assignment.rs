@@ -0,0 +1,123 @@
+//! Module containing code for assigning records to the list helpers reviewing them
+//!
+//! A list helper can claim a record to signal to the rest of the list team that they are already
+//! looking at it. Claims are automatically released once the record is approved or rejected, or
+//! after [`config::record_assignment_timeout`] hours (see [`release_stale_assignments`]).
+
+use crate::{
+    config,
+    error::PointercrateError,
+    model::{demonlist::record::FullRecord, user::User},
+    permissions::Permissions,
+    state::PointercrateState,
+    Result,
+};
+use chrono::NaiveDateTime;
+use log::{error, info};
+use serde::Serialize;
+use sqlx::{Done, PgConnection};
+use std::time::Duration;
+
+/// How often we check for stale assignments
+const POLL_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// The list helper a record is assigned to
+#[derive(Debug, Serialize, Hash, PartialEq, Eq)]
+pub struct Assignee {
+    pub id: i32,
+    pub name: String,
+    pub assigned_at: NaiveDateTime,
+}
+
+impl FullRecord {
+    /// Gets the user the record with the given id is assigned to
+    pub(super) async fn assignee_of(id: i32, connection: &mut PgConnection) -> Result<Option<Assignee>> {
+        let row = sqlx::query!(
+            "SELECT member_id, name, assigned_at FROM record_assignments INNER JOIN members ON member = member_id WHERE record = $1",
+            id
+        )
+        .fetch_optional(connection)
+        .await?;
+
+        Ok(row.map(|row| {
+            Assignee {
+                id: row.member_id,
+                name: row.name,
+                assigned_at: row.assigned_at,
+            }
+        }))
+    }
+
+    /// Assigns this record to the given user, overriding any existing assignment
+    ///
+    /// The user needs to have at least [`Permissions::ListHelper`] permissions
+    pub async fn set_assignee(&mut self, user: &User, connection: &mut PgConnection) -> Result<()> {
+        if !user.has_permission(Permissions::ListHelper) {
+            return Err(PointercrateError::InvalidAssignee)
+        }
+
+        let assigned_at = sqlx::query!(
+            "INSERT INTO record_assignments (record, member) VALUES ($1, $2) ON CONFLICT (record) DO UPDATE SET member = EXCLUDED.member, \
+             assigned_at = EXCLUDED.assigned_at RETURNING assigned_at",
+            self.id,
+            user.id
+        )
+        .fetch_one(connection)
+        .await?
+        .assigned_at;
+
+        self.assignee = Some(Assignee {
+            id: user.id,
+            name: user.name.clone(),
+            assigned_at,
+        });
+
+        Ok(())
+    }
+
+    pub async fn release_assignee(&mut self, connection: &mut PgConnection) -> Result<()> {
+        sqlx::query!("DELETE FROM record_assignments WHERE record = $1", self.id)
+            .execute(connection)
+            .await?;
+
+        self.assignee = None;
+
+        Ok(())
+    }
+}
+
+/// Background task releasing assignments that are older than
+/// [`config::record_assignment_timeout`]
+///
+/// Should be spawned exactly once at startup. Never returns.
+pub async fn release_stale_assignments(state: PointercrateState) {
+    info!("Starting task for releasing stale record assignments");
+
+    let mut interval = actix_rt::time::interval(POLL_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        if let Err(error) = release_stale(&state).await {
+            error!("INTERNAL SERVER ERROR: Failure to release stale record assignments: {:?}", error);
+        }
+    }
+}
+
+async fn release_stale(state: &PointercrateState) -> Result<()> {
+    let mut connection = state.connection().await?;
+
+    let released = sqlx::query!(
+        "DELETE FROM record_assignments WHERE assigned_at < (NOW() AT TIME ZONE 'utc') - make_interval(hours => $1)",
+        config::record_assignment_timeout()
+    )
+    .execute(&mut connection)
+    .await?
+    .rows_affected();
+
+    if released > 0 {
+        info!("Released {} stale record assignments", released);
+    }
+
+    Ok(())
+}
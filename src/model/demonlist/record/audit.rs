@@ -1,13 +1,13 @@
-use crate::{model::demonlist::record::RecordStatus, Result};
+use crate::{error::PointercrateError, model::demonlist::record::RecordStatus, util::non_nullable, Result};
 use chrono::NaiveDateTime;
 use futures::StreamExt;
-use serde::Serialize;
-use sqlx::PgConnection;
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgRow, PgConnection, Row};
 
 #[derive(Serialize)]
 pub struct NamedId {
-    id: i32,
-    name: Option<String>,
+    pub id: i32,
+    pub name: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -19,10 +19,23 @@ pub struct RecordModificationData {
     demon: Option<NamedId>,
 }
 
+#[derive(Serialize)]
+pub struct VideoProbeData {
+    video: String,
+    response: Option<i16>,
+    error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct RejectionReasonChangeData {
+    /// The record's new rejection reason, or `None` if the reason was removed
+    reason: Option<NamedId>,
+}
+
 #[derive(Serialize)]
 pub struct RecordEntry {
     time: NaiveDateTime,
-    audit_id: i32,
+    pub audit_id: i32,
     record_id: i32,
     user: NamedId,
     r#type: RecordEntryType,
@@ -32,6 +45,8 @@ pub struct RecordEntry {
 pub enum RecordEntryType {
     Addition,
     Modification(RecordModificationData),
+    VideoProbe(VideoProbeData),
+    RejectionReasonChange(RejectionReasonChangeData),
     Deletion,
 }
 
@@ -121,6 +136,71 @@ pub async fn entries_for_record(record_id: i32, connection: &mut PgConnection) -
         }
     }
 
+    let probe_rows = sqlx::query!(
+        r#"SELECT time, audit_id, 
+                  userid,
+                  members.name AS "name?",
+                  video,
+                  response,
+                  error
+                  FROM record_video_probes LEFT OUTER JOIN members ON members.member_id = userid WHERE id = $1"#,
+        record_id
+    )
+    .fetch_all(&mut *connection)
+    .await?;
+
+    for probe in probe_rows {
+        entries.push(RecordEntry {
+            time: probe.time,
+            audit_id: probe.audit_id,
+            record_id,
+            user: NamedId {
+                name: probe.name,
+                id: probe.userid,
+            },
+            r#type: RecordEntryType::VideoProbe(VideoProbeData {
+                video: probe.video,
+                response: probe.response,
+                error: probe.error,
+            }),
+        });
+    }
+
+    let reason_rows = sqlx::query!(
+        r#"SELECT time, audit_id, 
+                  userid,
+                  members.name AS "name?",
+                  reason,
+                  rejection_reasons.name AS "reason_name?"
+                  FROM record_rejection_reason_changes 
+                  LEFT OUTER JOIN members ON members.member_id = userid
+                  LEFT OUTER JOIN rejection_reasons ON rejection_reasons.id = reason
+                  WHERE record_rejection_reason_changes.id = $1"#,
+        record_id
+    )
+    .fetch_all(&mut *connection)
+    .await?;
+
+    for change in reason_rows {
+        entries.push(RecordEntry {
+            time: change.time,
+            audit_id: change.audit_id,
+            record_id,
+            user: NamedId {
+                name: change.name,
+                id: change.userid,
+            },
+            r#type: RecordEntryType::RejectionReasonChange(RejectionReasonChangeData {
+                reason: change.reason.map(|id| {
+                    NamedId {
+                        id,
+                        name: change.reason_name,
+                    }
+                }),
+            }),
+        });
+    }
+
     let deletion_row = sqlx::query!(
         r#"SELECT time, audit_id, 
                   userid,
@@ -144,5 +224,124 @@ pub async fn entries_for_record(record_id: i32, connection: &mut PgConnection) -
         });
     }
 
+    // All audit log tables share the audit_id sequence, so this is the order in which the entries
+    // were created
+    entries.sort_by_key(|entry| entry.audit_id);
+
     Ok(entries)
 }
+
+/// Pagination over the audit log entries of all records of some player and/or on some demon
+///
+/// Entries are ordered by their `audit_id`, meaning chronologically.
+#[derive(Deserialize, Debug, Clone, Serialize)]
+pub struct RecordAuditPagination {
+    #[serde(rename = "before", default, deserialize_with = "non_nullable")]
+    pub before_id: Option<i32>,
+
+    #[serde(rename = "after", default, deserialize_with = "non_nullable")]
+    pub after_id: Option<i32>,
+
+    #[serde(default, deserialize_with = "non_nullable")]
+    pub limit: Option<u8>,
+
+    /// The ID of the player whose records' entries should be listed. This includes records that
+    /// have since been deleted or transferred to a different player
+    #[serde(default, deserialize_with = "non_nullable")]
+    pub player: Option<i32>,
+
+    /// The ID of the demon whose records' entries should be listed. This includes records that have
+    /// since been deleted or moved to a different demon
+    #[serde(default, deserialize_with = "non_nullable")]
+    pub demon: Option<i32>,
+}
+
+impl RecordAuditPagination {
+    pub async fn page(&self, connection: &mut PgConnection) -> Result<Vec<RecordEntry>> {
+        if let Some(limit) = self.limit {
+            if limit < 1 || limit > 100 {
+                return Err(PointercrateError::InvalidPaginationLimit)
+            }
+        }
+
+        if let (Some(after), Some(before)) = (self.before_id, self.after_id) {
+            if after < before {
+                return Err(PointercrateError::AfterSmallerBefore)
+            }
+        }
+
+        let order = if self.before_id.is_some() && self.after_id.is_none() {
+            "DESC"
+        } else {
+            "ASC"
+        };
+
+        let query = format!(include_str!("../../../../sql/paginate_record_audit.sql"), order);
+
+        let mut stream = sqlx::query(&query)
+            .bind(self.before_id)
+            .bind(self.after_id)
+            .bind(self.player)
+            .bind(self.demon)
+            .bind(self.limit.unwrap_or(50) as i32 + 1)
+            .fetch(connection);
+
+        let mut entries = Vec::new();
+
+        while let Some(row) = stream.next().await {
+            entries.push(entry_from_row(row?)?)
+        }
+
+        Ok(entries)
+    }
+}
+
+/// The smallest and largest `audit_id` of all audit log entries
+pub async fn extremal_audit_ids(connection: &mut PgConnection) -> Result<(i32, i32)> {
+    let row = sqlx::query!(
+        r#"SELECT COALESCE(MAX(audit_id), 0) AS "max_id!: i32", COALESCE(MIN(audit_id), 0) AS "min_id!: i32" FROM audit_log2"#
+    )
+    .fetch_one(connection)
+    .await?;
+
+    Ok((row.max_id, row.min_id))
+}
+
+fn entry_from_row(row: PgRow) -> Result<RecordEntry> {
+    let named_id = |id: Option<i32>, name: Option<String>| id.map(|id| NamedId { id, name });
+
+    let r#type = match row.try_get::<&str, _>("type_")? {
+        "ADDITION" => RecordEntryType::Addition,
+        "MODIFICATION" =>
+            RecordEntryType::Modification(RecordModificationData {
+                progress: row.try_get("progress")?,
+                video: row.try_get("video")?,
+                status: row.try_get::<Option<&str>, _>("status")?.map(RecordStatus::from_sql),
+                player: named_id(row.try_get("player")?, row.try_get("player_name")?),
+                demon: named_id(row.try_get("demon")?, row.try_get("demon_name")?),
+            }),
+        "VIDEO_PROBE" =>
+            RecordEntryType::VideoProbe(VideoProbeData {
+                video: row.try_get("video")?,
+                response: row.try_get("response")?,
+                error: row.try_get("error")?,
+            }),
+        "REJECTION_REASON_CHANGE" =>
+            RecordEntryType::RejectionReasonChange(RejectionReasonChangeData {
+                reason: named_id(row.try_get("reason")?, row.try_get("reason_name")?),
+            }),
+        "DELETION" => RecordEntryType::Deletion,
+        _ => unreachable!(),
+    };
+
+    Ok(RecordEntry {
+        time: row.try_get("time")?,
+        audit_id: row.try_get("audit_id")?,
+        record_id: row.try_get("record_id")?,
+        user: NamedId {
+            id: row.try_get("userid")?,
+            name: row.try_get("username")?,
+        },
+        r#type,
+    })
+}
@@ -0,0 +1,79 @@
+use crate::{
+    error::PointercrateError,
+    model::demonlist::record::{FullRecord, PatchRecord},
+    util,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+/// The maximal number of operations a single bulk request may contain
+pub const MAX_BULK_OPERATIONS: usize = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct BulkPatch {
+    pub operations: Vec<BulkOperation>,
+
+    /// Whether all operations should be rolled back if a single one fails
+    #[serde(default)]
+    pub atomic: bool,
+}
+
+/// A single modification of a record as part of a [`BulkPatch`]
+#[derive(Debug, Deserialize)]
+pub struct BulkOperation {
+    pub id: i32,
+
+    /// The etag of the record, as it would be sent in the `If-Match` header of a normal `PATCH`
+    /// request
+    pub etag: String,
+    pub patch: PatchRecord,
+}
+
+/// The outcome of a single [`BulkOperation`]
+#[derive(Debug, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum BulkOutcome {
+    /// The operation was successfully applied
+    Applied { id: i32, etag: String, data: FullRecord },
+
+    /// The operation failed and has been rolled back. The other operations are unaffected, unless
+    /// the request was atomic
+    Failed { id: i32, error: serde_json::Value },
+
+    /// The operation would have succeeded, but was rolled back because another operation of the
+    /// same atomic request failed
+    RolledBack { id: i32 },
+
+    /// The operation wasn't attempted because a previous operation of the same atomic request
+    /// failed
+    Skipped { id: i32 },
+}
+
+impl BulkOutcome {
+    pub fn applied(record: FullRecord) -> Self {
+        BulkOutcome::Applied {
+            id: record.id,
+            etag: util::etag(&record).to_string(),
+            data: record,
+        }
+    }
+
+    pub fn failed(id: i32, error: PointercrateError) -> Self {
+        BulkOutcome::Failed {
+            id,
+            error: json!({
+                "code": error.error_code(),
+                "message": error.to_string(),
+                "data": error
+            }),
+        }
+    }
+
+    /// Turns the outcome of an applied operation into [`BulkOutcome::RolledBack`]
+    pub fn roll_back(self) -> Self {
+        match self {
+            BulkOutcome::Applied { id, .. } => BulkOutcome::RolledBack { id },
+            outcome => outcome,
+        }
+    }
+}
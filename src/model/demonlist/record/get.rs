@@ -6,12 +6,14 @@ use crate::{
             demon::MinimalDemon,
             player::DatabasePlayer,
             record::{note::notes_on, FullRecord, MinimalRecordD, MinimalRecordP, RecordStatus},
+            rejection_reason::RejectionReason,
             submitter::Submitter,
         },
         nationality::Nationality,
     },
     Result,
 };
+use chrono::NaiveDateTime;
 use futures::stream::StreamExt;
 use sqlx::{Error, PgConnection};
 
@@ -57,7 +59,10 @@ impl FullRecord {
                         id: row.submitter_id,
                         banned: row.submitter_banned,
                     }),
-                    notes: notes_on(id, connection).await?,
+                    notes: notes_on(id, &mut *connection).await?,
+                    video_verification: FullRecord::video_verification_of(id, &mut *connection).await?,
+                    assignee: FullRecord::assignee_of(id, &mut *connection).await?,
+                    rejection_reason: RejectionReason::of_record(id, connection).await?,
                 }),
 
             Err(Error::RowNotFound) =>
@@ -72,9 +77,9 @@ impl FullRecord {
 
 pub async fn approved_records_by(player: &DatabasePlayer, connection: &mut PgConnection) -> Result<Vec<MinimalRecordD>> {
     let mut stream = sqlx::query!(
-        r#"SELECT records.id, progress, CASE WHEN players.link_banned THEN NULL ELSE records.video::text END, demons.id AS demon_id, 
-         demons.name as "name: String", demons.position FROM records INNER JOIN demons ON records.demon = demons.id INNER JOIN players ON players.id 
-         = $1 WHERE status_ = 'APPROVED' AND records.player = $1"#,
+        r#"SELECT records.id, progress, CASE WHEN players.link_banned OR players.hide_videos THEN NULL ELSE records.video::text END, 
+         demons.id AS demon_id, demons.name as "name: String", demons.position FROM records INNER JOIN demons ON records.demon = demons.id 
+         INNER JOIN players ON players.id = $1 WHERE status_ = 'APPROVED' AND records.player = $1"#,
         player.id
     )
     .fetch(connection);
@@ -100,6 +105,38 @@ pub async fn approved_records_by(player: &DatabasePlayer, connection: &mut PgCon
     Ok(records)
 }
 
+/// Gets the given player's records that are still waiting for review, i.e. those that are either
+/// submitted or under consideration
+pub async fn pending_records_by(player: &DatabasePlayer, connection: &mut PgConnection) -> Result<Vec<MinimalRecordD>> {
+    let mut stream = sqlx::query!(
+        r#"SELECT records.id, progress, records.video::text, status_::text AS "status!: String", demons.id AS demon_id, demons.name as 
+         "name: String", demons.position FROM records INNER JOIN demons ON records.demon = demons.id WHERE (status_ = 'SUBMITTED' OR status_ = 
+         'UNDER_CONSIDERATION') AND records.player = $1 ORDER BY records.id"#,
+        player.id
+    )
+    .fetch(connection);
+
+    let mut records = Vec::new();
+
+    while let Some(row) = stream.next().await {
+        let row = row?;
+
+        records.push(MinimalRecordD {
+            id: row.id,
+            progress: row.progress,
+            video: row.video,
+            status: RecordStatus::from_sql(&row.status),
+            demon: MinimalDemon {
+                id: row.demon_id,
+                position: row.position,
+                name: CiString(row.name),
+            },
+        })
+    }
+
+    Ok(records)
+}
+
 pub async fn approved_records_on(demon: &MinimalDemon, connection: &mut PgConnection) -> Result<Vec<MinimalRecordP>> {
     struct Fetched {
         id: i32,
@@ -114,8 +151,9 @@ pub async fn approved_records_on(demon: &MinimalDemon, connection: &mut PgConnec
 
     let mut stream = sqlx::query_as!(
         Fetched,
-        r#"SELECT records.id, progress, CASE WHEN players.link_banned THEN NULL ELSE video::text END, players.id AS player_id, 
-         players.name AS "name: String", players.banned, nation::TEXT, iso_country_code::TEXT FROM records INNER JOIN players ON records.player = players.id LEFT OUTER JOIN nationalities ON nationality = iso_country_code WHERE status_ = 'APPROVED' AND 
+        r#"SELECT records.id, progress, CASE WHEN players.link_banned OR players.hide_videos THEN NULL ELSE video::text END, players.id AS 
+         player_id, players.name AS "name: String", players.banned, nation::TEXT, iso_country_code::TEXT FROM records INNER JOIN players ON 
+         records.player = players.id LEFT OUTER JOIN nationalities ON nationality = iso_country_code WHERE status_ = 'APPROVED' AND 
          records.demon = $1 ORDER BY progress DESC, id ASC"#,
         demon.id
     )
@@ -141,6 +179,87 @@ pub async fn approved_records_on(demon: &MinimalDemon, connection: &mut PgConnec
                     Some(Nationality {
                         iso_country_code: code,
                         nation: CiString(nation),
+                        subdivision: None,
+                    }),
+                _ => None,
+            },
+        })
+    }
+
+    Ok(records)
+}
+
+/// Like [`approved_records_by`], but returns the player's approved records as they were at the
+/// given point in time (see the `records_at` SQL function). The positions of the demons are those
+/// at that time as well. Demons that weren't on the list yet are skipped.
+pub async fn approved_records_by_at(
+    player: &DatabasePlayer, at: NaiveDateTime, connection: &mut PgConnection,
+) -> Result<Vec<MinimalRecordD>> {
+    let mut stream = sqlx::query!(
+        r#"SELECT records.id AS "id!", progress AS "progress!", CASE WHEN players.link_banned OR players.hide_videos THEN NULL ELSE 
+         records.video::text END, demons.id AS "demon_id!", demons.name AS "name!: String", demons.position_ AS "position!" FROM 
+         records_at($2) AS records INNER JOIN list_at($2) AS demons ON records.demon = demons.id INNER JOIN players ON players.id = $1 WHERE 
+         status_ = 'APPROVED' AND records.player = $1"#,
+        player.id,
+        at
+    )
+    .fetch(connection);
+
+    let mut records = Vec::new();
+
+    while let Some(row) = stream.next().await {
+        let row = row?;
+
+        records.push(MinimalRecordD {
+            id: row.id,
+            progress: row.progress,
+            video: row.video,
+            status: RecordStatus::Approved,
+            demon: MinimalDemon {
+                id: row.demon_id,
+                position: row.position,
+                name: CiString(row.name),
+            },
+        })
+    }
+
+    Ok(records)
+}
+
+/// Like [`approved_records_on`], but returns the approved records on the given demon as they were
+/// at the given point in time (see the `records_at` SQL function)
+pub async fn approved_records_on_at(demon: &MinimalDemon, at: NaiveDateTime, connection: &mut PgConnection) -> Result<Vec<MinimalRecordP>> {
+    let mut stream = sqlx::query!(
+        r#"SELECT records.id AS "id!", progress AS "progress!", CASE WHEN players.link_banned OR players.hide_videos THEN NULL ELSE 
+         video::text END, players.id AS player_id, players.name AS "name: String", players.banned, nation::TEXT, iso_country_code::TEXT FROM 
+         records_at($2) AS records INNER JOIN players ON records.player = players.id LEFT OUTER JOIN nationalities ON nationality = 
+         iso_country_code WHERE status_ = 'APPROVED' AND records.demon = $1 ORDER BY records.progress DESC, records.id ASC"#,
+        demon.id,
+        at
+    )
+    .fetch(connection);
+
+    let mut records = Vec::new();
+
+    while let Some(row) = stream.next().await {
+        let row = row?;
+
+        records.push(MinimalRecordP {
+            id: row.id,
+            progress: row.progress,
+            video: row.video,
+            status: RecordStatus::Approved,
+            player: DatabasePlayer {
+                id: row.player_id,
+                name: CiString(row.name),
+                banned: row.banned,
+            },
+            nationality: match (row.nation, row.iso_country_code) {
+                (Some(nation), Some(code)) =>
+                    Some(Nationality {
+                        iso_country_code: code,
+                        nation: CiString(nation),
+                        subdivision: None,
                     }),
                 _ => None,
             },
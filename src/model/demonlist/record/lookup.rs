@@ -0,0 +1,112 @@
+//! Module containing the public lookup of submissions
+//!
+//! Every record gets a random lookup token upon creation, which is returned exactly once, in the
+//! response to the submission. With it, the submitter can check on the status of their submission
+//! without needing an account. The information returned is limited to what the submitter already
+//! knows plus the outcome of the review, meaning the submitter and any internal notes of the list
+//! team are never exposed.
+
+use crate::{
+    error::PointercrateError,
+    model::demonlist::{
+        demon::MinimalDemon,
+        player::DatabasePlayer,
+        record::{FullRecord, RecordStatus},
+        rejection_reason::RejectionReason,
+    },
+    Result,
+};
+use rand::RngCore;
+use serde::Serialize;
+use sqlx::PgConnection;
+use std::hash::{Hash, Hasher};
+
+#[derive(Debug, Serialize)]
+pub struct SubmissionStatus {
+    pub id: i32,
+    pub progress: i16,
+    pub video: Option<String>,
+    pub status: RecordStatus,
+    pub player: DatabasePlayer,
+    pub demon: MinimalDemon,
+
+    /// The contents of all notes on the record that the list team marked as public
+    pub notes: Vec<String>,
+    pub rejection_reason: Option<RejectionReason>,
+}
+
+impl Hash for SubmissionStatus {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+        self.progress.hash(state);
+        self.video.hash(state);
+        self.status.hash(state);
+        self.player.id.hash(state);
+        self.demon.id.hash(state);
+        self.notes.hash(state);
+        self.rejection_reason.hash(state);
+    }
+}
+
+impl From<FullRecord> for SubmissionStatus {
+    fn from(record: FullRecord) -> Self {
+        SubmissionStatus {
+            id: record.id,
+            progress: record.progress,
+            video: record.video,
+            status: record.status,
+            player: record.player,
+            demon: record.demon,
+            notes: record
+                .notes
+                .into_iter()
+                .filter(|note| note.is_public)
+                .map(|note| note.content)
+                .collect(),
+            rejection_reason: record.rejection_reason,
+        }
+    }
+}
+
+impl SubmissionStatus {
+    /// Gets the status of the submission with the given lookup token
+    pub async fn by_token(token: &str, connection: &mut PgConnection) -> Result<SubmissionStatus> {
+        let not_found = || {
+            PointercrateError::ModelNotFound {
+                model: "Submission",
+                identified_by: token.to_string(),
+            }
+        };
+
+        let row = sqlx::query!("SELECT record FROM record_lookup_tokens WHERE token = $1", token)
+            .fetch_optional(&mut *connection)
+            .await?
+            .ok_or_else(not_found)?;
+
+        // Tokens outlive their record if it gets deleted, see record_lookup_tokens
+        match FullRecord::by_id(row.record, connection).await {
+            Err(PointercrateError::ModelNotFound { .. }) => Err(not_found()),
+            result => Ok(result?.into()),
+        }
+    }
+}
+
+impl FullRecord {
+    /// Generates the lookup token for this record
+    ///
+    /// The token is not stored anywhere on the record object, since it should only ever be handed
+    /// out to the submitter (and in particular not be included in webhook messages)
+    pub async fn create_lookup_token(&self, connection: &mut PgConnection) -> Result<String> {
+        let mut bytes = [0u8; 24];
+
+        rand::thread_rng().fill_bytes(&mut bytes);
+
+        let token = hex::encode(bytes);
+
+        sqlx::query!("INSERT INTO record_lookup_tokens (record, token) VALUES ($1, $2)", self.id, token)
+            .execute(connection)
+            .await?;
+
+        Ok(token)
+    }
+}
@@ -16,6 +16,11 @@ pub struct Note {
 
     pub content: String,
 
+    /// Whether this note is visible to the submitter of the record it was made on (see
+    /// [`SubmissionStatus`](crate::model::demonlist::record::SubmissionStatus)). Notes are internal
+    /// to the list team by default
+    pub is_public: bool,
+
     /// Whether this note was originally made on a different record and later transferred to this
     /// one due to deletion.
     pub transferred: bool,
@@ -33,6 +38,7 @@ pub struct Note {
 
 impl Hash for Note {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.content.hash(state)
+        self.content.hash(state);
+        self.is_public.hash(state);
     }
 }
@@ -6,6 +6,7 @@ struct PartialNote {
     id: i32,
     record: i32,
     content: String,
+    is_public: bool,
     author: Option<String>,
     transferred: bool,
 }
@@ -29,6 +30,7 @@ impl PartialNote {
             id: self.id,
             record: self.record,
             content: self.content,
+            is_public: self.is_public,
             author: self.author,
             transferred: self.transferred,
             editors,
@@ -41,7 +43,7 @@ impl Note {
         // TODO: handling of deleted users
         let row = sqlx::query_as!(
             PartialNote,
-            r#"SELECT id, record, content, members.name AS "author?: String", EXISTS(SELECT 1 FROM record_notes_modifications WHERE record IS NOT NULL 
+            r#"SELECT id, record, content, is_public, members.name AS "author?: String", EXISTS(SELECT 1 FROM record_notes_modifications WHERE record IS NOT NULL 
              AND id = $1) AS "transferred!: bool" FROM record_notes NATURAL JOIN record_notes_additions LEFT OUTER JOIN members on 
              members.member_id = record_notes_additions.userid WHERE id = $1"#,
             note_id
@@ -64,7 +66,7 @@ impl Note {
 pub async fn notes_on(record_id: i32, connection: &mut PgConnection) -> Result<Vec<Note>> {
     let partials = sqlx::query_as!(
         PartialNote,
-        r#"SELECT id, record, content, members.name AS "author?: String", EXISTS(SELECT 1 FROM record_notes_modifications WHERE record IS NOT NULL AND 
+        r#"SELECT id, record, content, is_public, members.name AS "author?: String", EXISTS(SELECT 1 FROM record_notes_modifications WHERE record IS NOT NULL AND 
          id = $1) AS "transferred!: bool"  FROM record_notes NATURAL JOIN record_notes_additions LEFT OUTER JOIN members on members.member_id = 
          record_notes_additions.userid WHERE record = $1"#,
         record_id
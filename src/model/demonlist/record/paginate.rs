@@ -63,6 +63,21 @@ pub struct RecordPagination {
 
     #[serde(default, deserialize_with = "non_nullable")]
     pub submitter: Option<i32>,
+
+    /// Whether to only list records whose video was found to be dead during the last periodic
+    /// video check (or only those whose video wasn't)
+    #[serde(default, deserialize_with = "non_nullable")]
+    pub dead_video: Option<bool>,
+
+    /// The ID of the list helper the records are assigned to
+    #[serde(default, deserialize_with = "non_nullable")]
+    pub reviewer: Option<i32>,
+
+    /// The ID of the
+    /// [`RejectionReason`](crate::model::demonlist::rejection_reason::RejectionReason)
+    /// the records were rejected for
+    #[serde(default, deserialize_with = "non_nullable")]
+    rejection_reason: Option<i32>,
 }
 
 impl RecordPagination {
@@ -113,6 +128,9 @@ impl RecordPagination {
             .bind(self.video == Some(None))
             .bind(self.player)
             .bind(self.submitter)
+            .bind(self.dead_video)
+            .bind(self.reviewer)
+            .bind(self.rejection_reason)
             .bind(limit + 1)
             .fetch(&mut *connection);
 
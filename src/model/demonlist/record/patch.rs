@@ -1,10 +1,15 @@
 use crate::{
     cistring::CiString,
     error::PointercrateError,
-    model::demonlist::{
-        demon::MinimalDemon,
-        player::DatabasePlayer,
-        record::{FullRecord, RecordStatus},
+    model::{
+        demonlist::{
+            demon::MinimalDemon,
+            player::DatabasePlayer,
+            record::{FullRecord, RecordStatus},
+            rejection_reason::RejectionReason,
+            video_key::{self, VideoOwner},
+        },
+        user::User,
     },
     util::{non_nullable, nullable},
     Result,
@@ -13,25 +18,34 @@ use log::{info, warn};
 use serde::Deserialize;
 use sqlx::{Done, PgConnection};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Default)]
 pub struct PatchRecord {
     #[serde(default, deserialize_with = "non_nullable")]
-    progress: Option<i16>,
+    pub(super) progress: Option<i16>,
 
     #[serde(default, deserialize_with = "nullable")]
-    video: Option<Option<String>>,
+    pub(super) video: Option<Option<String>>,
 
     #[serde(default, deserialize_with = "non_nullable")]
-    status: Option<RecordStatus>,
+    pub status: Option<RecordStatus>,
 
     #[serde(default, deserialize_with = "non_nullable")]
-    player: Option<CiString>,
+    pub(super) player: Option<CiString>,
 
     #[serde(default, deserialize_with = "non_nullable")]
     demon: Option<CiString>,
 
     #[serde(default, deserialize_with = "non_nullable")]
-    demon_id: Option<i32>,
+    pub(super) demon_id: Option<i32>,
+
+    /// The ID of the user to assign this record to, or `null` to release the current assignment
+    #[serde(default, deserialize_with = "nullable")]
+    pub assignee: Option<Option<i32>>,
+
+    /// The ID of the [`RejectionReason`] this record was rejected for. Can only be set if the
+    /// record is (or is being) rejected
+    #[serde(default, deserialize_with = "non_nullable")]
+    reason: Option<i32>,
 }
 
 impl FullRecord {
@@ -50,10 +64,25 @@ impl FullRecord {
             }
         }
 
+        // Has to happen before the status is updated, since approving or rejecting a record releases its
+        // assignment
+        if let Some(assignee) = data.assignee {
+            match assignee {
+                None => self.release_assignee(connection).await?,
+                Some(user_id) => self.set_assignee(&User::by_id(user_id, connection).await?, connection).await?,
+            }
+        }
+
         if let Some(status) = data.status {
             self.set_status(status, connection).await?
         }
 
+        if let Some(reason) = data.reason {
+            let reason = RejectionReason::by_id(reason, connection).await?;
+
+            self.set_rejection_reason(reason, connection).await?;
+        }
+
         if let Some(player) = data.player {
             let player = DatabasePlayer::by_name_or_create(player.as_ref(), connection).await?;
 
@@ -201,12 +230,7 @@ impl FullRecord {
             return Ok(())
         }
 
-        if let Some(row) = sqlx::query!(r#"SELECT id FROM records WHERE video = $1"#, video.to_string())
-            .fetch_optional(&mut *connection)
-            .await?
-        {
-            return Err(PointercrateError::DuplicateVideo { id: row.id })
-        }
+        video_key::ensure_unused(&video, Some(VideoOwner::Record(self.id)), &mut *connection).await?;
 
         sqlx::query!("UPDATE records SET video = $1::text WHERE id = $2", video, self.id)
             .execute(connection)
@@ -323,6 +347,21 @@ impl FullRecord {
             _ => (),
         }
 
+        // Once a list helper approved or rejected a record, there is no point in further verifying its
+        // video, and its review is done
+        if status == RecordStatus::Approved || status == RecordStatus::Rejected {
+            self.stop_video_verification(&mut *connection).await?;
+            self.release_assignee(&mut *connection).await?;
+        }
+
+        if status != RecordStatus::Rejected && self.rejection_reason.is_some() {
+            sqlx::query!("DELETE FROM record_rejection_reasons WHERE record = $1", self.id)
+                .execute(&mut *connection)
+                .await?;
+
+            self.rejection_reason = None;
+        }
+
         sqlx::query!(
             "UPDATE records SET status_ = cast($1::text as record_status) WHERE id = $2", /* FIXME(sqlx) ridiculous query
                                                                                            * format to trick sqlx into working
@@ -338,6 +377,28 @@ impl FullRecord {
         Ok(())
     }
 
+    /// Sets the reason this record was rejected for
+    ///
+    /// Fails if this record isn't rejected
+    pub async fn set_rejection_reason(&mut self, reason: RejectionReason, connection: &mut PgConnection) -> Result<()> {
+        if self.status != RecordStatus::Rejected {
+            return Err(PointercrateError::ReasonWithoutRejection)
+        }
+
+        sqlx::query!(
+            "INSERT INTO record_rejection_reasons (record, reason) VALUES ($1, $2) ON CONFLICT (record) DO UPDATE SET reason = \
+             EXCLUDED.reason",
+            self.id,
+            reason.id
+        )
+        .execute(connection)
+        .await?;
+
+        self.rejection_reason = Some(reason);
+
+        Ok(())
+    }
+
     /// Updates this record's progress
     ///
     /// If this record is approved, all submissions with lower progress of the same (player,
@@ -7,6 +7,7 @@ use crate::{
         player::DatabasePlayer,
         record::{note::Note, FullRecord, RecordStatus},
         submitter::Submitter,
+        video_key,
     },
     ratelimit::{PreparedRatelimits, RatelimitScope},
     Result,
@@ -79,19 +80,11 @@ impl FullRecord {
 
         debug!("Submission is valid, checking for duplicates!");
 
-        // Search for existing records. If a video exists, we also check if a record with
-        // exactly that video exists.
+        // Search for existing records. If a video exists, we also check if any record or demon already
+        // uses that video.
 
         if let Some(ref video) = video {
-            if let Some(row) = sqlx::query!(r#"SELECT id, status_::text as "status_!: String" FROM records WHERE video = $1"#, video.to_string())
-                .fetch_optional(&mut *connection) // FIXME(sqlx)
-                .await?
-            {
-                return Err(PointercrateError::SubmissionExists {
-                    existing: row.id,
-                    status: RecordStatus::from_sql(&row.status_),
-                })
-            }
+            video_key::ensure_unused(video, None, &mut *connection).await?;
         }
 
         let existing = sqlx::query!(
@@ -140,18 +133,24 @@ impl FullRecord {
             demon,
             submitter: Some(submitter),
             notes: Vec::new(),
+            video_verification: None,
+            assignee: None,
+            rejection_reason: None,
         };
 
         // Dealing with different status and upholding their invariant is complicated, we should not
         // duplicate that code!
         if submission.status != RecordStatus::Submitted {
             record.set_status(submission.status, &mut *connection).await?;
+        } else if record.video.is_some() {
+            record.start_video_verification(&mut *connection).await?;
         }
 
         if let Some(note) = submission.note {
             if !note.trim().is_empty() {
+                // The submitter wrote this note themselves, so there is no reason to hide it from them
                 let note_id = sqlx::query!(
-                    "INSERT INTO record_notes (record, content) VALUES ($1, $2) RETURNING id",
+                    "INSERT INTO record_notes (record, content, is_public) VALUES ($1, $2, TRUE) RETURNING id",
                     record.id,
                     note
                 )
@@ -163,6 +162,7 @@ impl FullRecord {
                     id: note_id,
                     record: id,
                     content: note,
+                    is_public: true,
                     transferred: false,
                     author: None,
                     editors: Vec::new(),
@@ -0,0 +1,390 @@
+//! Module containing code for undoing changes to records using the audit log
+//!
+//! Every modification of a record logs the _old_ values of all changed fields into
+//! `record_modifications`, so the state of a record directly before some modification can be
+//! reconstructed by starting at its current state and undoing all modifications since then, newest
+//! first. The deletion of a record additionally logs a modification containing its complete state,
+//! meaning deleted records can be restored as well.
+//!
+//! Note that the audit log doesn't notice a record's video being set if it previously had none, so
+//! such changes cannot be undone.
+
+use crate::{
+    error::PointercrateError,
+    model::demonlist::{
+        demon::MinimalDemon,
+        player::DatabasePlayer,
+        record::{FullRecord, PatchRecord, RecordStatus},
+        submitter::Submitter,
+        video_key::{self, VideoOwner},
+    },
+    Result,
+};
+use log::{error, info};
+use sqlx::PgConnection;
+
+/// The kind of audit log entry some change to a record was logged as
+enum LoggedChange {
+    Modification,
+    Deletion {
+        /// The submitter of the deleted record. `None` for records deleted before submitters were
+        /// logged
+        submitter: Option<i32>,
+    },
+}
+
+impl LoggedChange {
+    /// Looks up what kind of change to the given record the audit log entry with the given ID is
+    async fn of(record_id: i32, audit_id: i32, connection: &mut PgConnection) -> Result<LoggedChange> {
+        let is_modification = sqlx::query!(
+            r#"SELECT EXISTS (SELECT 1 FROM record_modifications WHERE id = $1 AND audit_id = $2) AS "exists!: bool""#,
+            record_id,
+            audit_id
+        )
+        .fetch_one(&mut *connection)
+        .await?
+        .exists;
+
+        if is_modification {
+            return Ok(LoggedChange::Modification)
+        }
+
+        let deletion = sqlx::query!(
+            "SELECT submitter FROM record_deletions WHERE id = $1 AND audit_id = $2",
+            record_id,
+            audit_id
+        )
+        .fetch_optional(&mut *connection)
+        .await?;
+
+        if let Some(deletion) = deletion {
+            return Ok(LoggedChange::Deletion {
+                submitter: deletion.submitter,
+            })
+        }
+
+        let is_other_entry = sqlx::query!(
+            r#"SELECT EXISTS (SELECT 1 FROM record_additions WHERE id = $1 AND audit_id = $2 UNION ALL SELECT 1 FROM record_video_probes
+             WHERE id = $1 AND audit_id = $2 UNION ALL SELECT 1 FROM record_rejection_reason_changes WHERE id = $1 AND audit_id = $2) AS
+             "exists!: bool""#,
+            record_id,
+            audit_id
+        )
+        .fetch_one(connection)
+        .await?
+        .exists;
+
+        if is_other_entry {
+            Err(PointercrateError::IrrevertibleEntry)
+        } else {
+            Err(PointercrateError::ModelNotFound {
+                model: "Audit log entry",
+                identified_by: format!("{} of record {}", audit_id, record_id),
+            })
+        }
+    }
+}
+
+impl FullRecord {
+    /// Reverts this record to the state it was in directly before the modification logged by the
+    /// audit log entry with the given ID
+    ///
+    /// The old state is applied via [`FullRecord::apply_patch`], meaning that all invariants
+    /// regarding approved and rejected records are upheld. This might cause other records to be
+    /// deleted. Must be called inside a transaction
+    pub async fn revert(self, audit_id: i32, connection: &mut PgConnection) -> Result<FullRecord> {
+        match LoggedChange::of(self.id, audit_id, &mut *connection).await? {
+            // The record has been restored since
+            LoggedChange::Deletion { .. } => return Err(PointercrateError::Conflict),
+            LoggedChange::Modification => (),
+        }
+
+        let mut progress = self.progress;
+        let mut video = self.video.clone();
+        let mut status = self.status;
+        let mut player = self.player.id;
+        let mut demon = self.demon.id;
+
+        let rows = sqlx::query!(
+            "SELECT progress, video::TEXT, status_::TEXT, player, demon FROM record_modifications WHERE id = $1 AND audit_id >= $2 ORDER \
+             BY audit_id DESC",
+            self.id,
+            audit_id
+        )
+        .fetch_all(&mut *connection)
+        .await?;
+
+        for row in rows {
+            if let Some(old_progress) = row.progress {
+                progress = old_progress;
+            }
+            if let Some(old_video) = row.video {
+                video = Some(old_video);
+            }
+            if let Some(old_status) = row.status_ {
+                status = RecordStatus::from_sql(&old_status);
+            }
+            if let Some(old_player) = row.player {
+                player = old_player;
+            }
+            if let Some(old_demon) = row.demon {
+                demon = old_demon;
+            }
+        }
+
+        let mut patch = PatchRecord::default();
+
+        if progress != self.progress {
+            patch.progress = Some(progress);
+        }
+
+        if video != self.video {
+            patch.video = Some(video);
+        }
+
+        if status != self.status {
+            patch.status = Some(status);
+        }
+
+        if player != self.player.id {
+            patch.player = Some(DatabasePlayer::by_id(player, &mut *connection).await?.name);
+        }
+
+        if demon != self.demon.id {
+            patch.demon_id = Some(demon);
+        }
+
+        info!("Reverting record {} to its state before audit log entry {}", self, audit_id);
+
+        self.apply_patch(patch, connection).await
+    }
+
+    /// Restores the record with the given ID, which was deleted by the audit log entry with the
+    /// given ID
+    ///
+    /// The record is restored with its original ID, but without any of its notes. Its lookup token
+    /// survives the deletion, so the submitter's status page works again. Must be called inside a
+    /// transaction
+    pub async fn restore(id: i32, audit_id: i32, connection: &mut PgConnection) -> Result<FullRecord> {
+        let submitter = match LoggedChange::of(id, audit_id, &mut *connection).await? {
+            LoggedChange::Deletion {
+                submitter: Some(submitter),
+            } => submitter,
+            LoggedChange::Deletion { submitter: None } => return Err(PointercrateError::SubmitterUnknown),
+            // Modifications of deleted records can only be reverted after restoring the record
+            LoggedChange::Modification =>
+                return Err(PointercrateError::ModelNotFound {
+                    model: "Record",
+                    identified_by: id.to_string(),
+                }),
+        };
+
+        let exists = sqlx::query!(r#"SELECT EXISTS (SELECT 1 FROM records WHERE id = $1) AS "exists!: bool""#, id)
+            .fetch_one(&mut *connection)
+            .await?
+            .exists;
+
+        if exists {
+            return Err(PointercrateError::Conflict)
+        }
+
+        // The deletion trigger logs a modification containing the complete state of the record
+        // directly before the deletion
+        let row = sqlx::query!(
+            "SELECT progress, video::TEXT, status_::TEXT, player, demon FROM record_modifications WHERE id = $1 AND audit_id < $2 ORDER \
+             BY audit_id DESC LIMIT 1",
+            id,
+            audit_id
+        )
+        .fetch_one(&mut *connection)
+        .await?;
+
+        let (progress, status, player, demon) = match (row.progress, row.status_, row.player, row.demon) {
+            (Some(progress), Some(status), Some(player), Some(demon)) => (progress, RecordStatus::from_sql(&status), player, demon),
+            _ => {
+                error!("The audit log does not contain the state of record {} before its deletion!", id);
+
+                return Err(PointercrateError::InternalServerError)
+            },
+        };
+
+        let player = DatabasePlayer::by_id(player, &mut *connection).await?;
+        let demon = MinimalDemon::by_id(demon, &mut *connection).await?;
+        let submitter = Submitter::by_id(submitter, &mut *connection).await?;
+
+        if player.banned && status != RecordStatus::Rejected {
+            return Err(PointercrateError::PlayerBanned)
+        }
+
+        if let Some(ref video) = row.video {
+            video_key::ensure_unused(video, Some(VideoOwner::Record(id)), &mut *connection).await?;
+        }
+
+        // Restoring a rejected record deletes all other records of its player on its demon (see
+        // set_status below). Otherwise, the record must not conflict with a record that was
+        // rejected or approved (with at least as much progress) since its deletion, as the
+        // invariants on approved records cannot be upheld then
+        if status != RecordStatus::Rejected {
+            let existing = sqlx::query!(
+                r#"SELECT id, status_::text as "status_!: String" FROM records WHERE demon = $1 AND player = $2 AND (status_ = 'REJECTED' OR 
+                 (status_ = 'APPROVED' AND progress >= $3)) LIMIT 1"#,
+                demon.id,
+                player.id,
+                progress
+            )
+            .fetch_optional(&mut *connection)
+            .await?;
+
+            if let Some(existing) = existing {
+                return Err(PointercrateError::SubmissionExists {
+                    existing: existing.id,
+                    status: RecordStatus::from_sql(&existing.status_),
+                })
+            }
+        }
+
+        info!("Restoring record {} deleted in audit log entry {}", id, audit_id);
+
+        sqlx::query(
+            "INSERT INTO records (id, progress, video, status_, player, submitter, demon) VALUES ($1, $2, $3::TEXT, 'SUBMITTED', $4, $5, \
+             $6)",
+        )
+        .bind(id)
+        .bind(progress)
+        .bind(&row.video)
+        .bind(player.id)
+        .bind(submitter.id)
+        .bind(demon.id)
+        .execute(&mut *connection)
+        .await?;
+
+        let mut record = FullRecord {
+            id,
+            progress,
+            video: row.video,
+            status: RecordStatus::Submitted,
+            player,
+            demon,
+            submitter: Some(submitter),
+            notes: Vec::new(),
+            video_verification: None,
+            assignee: None,
+            rejection_reason: None,
+        };
+
+        // Same as for new submissions, let set_status deal with the invariants of the different states
+        if status != RecordStatus::Submitted {
+            record.set_status(status, connection).await?;
+        }
+
+        Ok(record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        cistring::CiStr,
+        error::PointercrateError,
+        model::demonlist::{
+            player::DatabasePlayer,
+            record::{FullRecord, RecordStatus, SubmissionStatus},
+        },
+    };
+
+    #[actix_rt::test]
+    async fn test_restore_conflicting_with_approved_record() {
+        let mut connection = crate::test::test_setup().await;
+
+        crate::state::audit_connection(&mut connection, 0).await.unwrap();
+
+        let player = DatabasePlayer::by_name(CiStr::from_str("stardust1971"), &mut connection)
+            .await
+            .unwrap();
+
+        // stardust1971 has a 90% approved record and a 100% submission on the first demon of the test set
+        let approved = sqlx::query!(
+            "SELECT id FROM records WHERE player = $1 AND status_ = 'APPROVED' AND progress = 90",
+            player.id
+        )
+        .fetch_one(&mut connection)
+        .await
+        .unwrap()
+        .id;
+        let submitted = sqlx::query!("SELECT id FROM records WHERE player = $1 AND status_ = 'SUBMITTED'", player.id)
+            .fetch_one(&mut connection)
+            .await
+            .unwrap()
+            .id;
+
+        FullRecord::by_id(approved, &mut connection)
+            .await
+            .unwrap()
+            .delete(&mut connection)
+            .await
+            .unwrap();
+
+        let deletion = sqlx::query!("SELECT audit_id FROM record_deletions WHERE id = $1", approved)
+            .fetch_one(&mut connection)
+            .await
+            .unwrap()
+            .audit_id;
+
+        // After the deletion, the 100% submission gets approved
+        let mut record = FullRecord::by_id(submitted, &mut connection).await.unwrap();
+        record.set_status(RecordStatus::Approved, &mut connection).await.unwrap();
+
+        let result = FullRecord::restore(approved, deletion, &mut connection).await;
+
+        match result {
+            Err(PointercrateError::SubmissionExists {
+                existing,
+                status: RecordStatus::Approved,
+            }) => assert_eq!(existing, submitted),
+            _ =>
+                panic!(
+                    "Restoring a record conflicting with an approved record should fail, got {:?}",
+                    result
+                ),
+        }
+
+        assert!(FullRecord::by_id(approved, &mut connection).await.is_err());
+        assert_eq!(FullRecord::by_id(submitted, &mut connection).await.unwrap().progress, 100);
+    }
+
+    #[actix_rt::test]
+    async fn test_restore_keeps_lookup_token() {
+        let mut connection = crate::test::test_setup().await;
+
+        crate::state::audit_connection(&mut connection, 0).await.unwrap();
+
+        let player = DatabasePlayer::by_name(CiStr::from_str("stardust1971"), &mut connection)
+            .await
+            .unwrap();
+        let submitted = sqlx::query!("SELECT id FROM records WHERE player = $1 AND status_ = 'SUBMITTED'", player.id)
+            .fetch_one(&mut connection)
+            .await
+            .unwrap()
+            .id;
+
+        let record = FullRecord::by_id(submitted, &mut connection).await.unwrap();
+        let token = record.create_lookup_token(&mut connection).await.unwrap();
+
+        record.delete(&mut connection).await.unwrap();
+
+        assert!(SubmissionStatus::by_token(&token, &mut connection).await.is_err());
+
+        let deletion = sqlx::query!("SELECT audit_id FROM record_deletions WHERE id = $1", submitted)
+            .fetch_one(&mut connection)
+            .await
+            .unwrap()
+            .audit_id;
+        let result = FullRecord::restore(submitted, deletion, &mut connection).await;
+
+        assert!(result.is_ok(), "{:?}", result.unwrap_err());
+
+        let status = SubmissionStatus::by_token(&token, &mut connection).await;
+
+        assert!(status.is_ok(), "{:?}", status.unwrap_err());
+    }
+}
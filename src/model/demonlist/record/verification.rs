@@ -0,0 +1,315 @@
+//! Module containing the verification of submission videos
+//!
+//! Every submission with a video starts out as 'unverified'. We then probe its video, right after
+//! submission and afterwards periodically in a background task (see [`verify_videos`]), until one
+//! of the following happens:
+//!
+//! * The video host responds successfully. The submission is then considered verified, and the
+//!   webhook messages about it are released
+//! * The video host responds with a definitive client error (e.g. a 404) several times. The
+//!   submission is then deleted
+//! * The verification window (see [`config::video_verification_window`]) runs out without either of
+//!   the above happening (for instance because the video host was unreachable the whole time). The
+//!   submission is then flagged for manual review by list helpers, and the webhook messages about
+//!   it are released. The same happens right away if the video host refuses to answer our requests
+//!   (see [`ProbeResult::Refused`])
+//!
+//! Each probe is logged in the record's audit log. Verification stops once a submission is approved
+//! or rejected by a list helper.
+
+use crate::{
+    config,
+    model::{demonlist::record::FullRecord, webhook},
+    state::PointercrateState,
+    video::{self, ProbeResult},
+    Result,
+};
+use log::{debug, error, info, warn};
+use serde::{Serialize, Serializer};
+use sqlx::PgConnection;
+use std::{
+    fmt::{Display, Formatter},
+    time::Duration,
+};
+
+/// How often we check whether any submissions are due for another verification attempt
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The maximal number of submissions probed per check
+const BATCH_SIZE: i64 = 10;
+
+/// The number of definitive client errors after which a submission is deleted
+const MAX_DEFINITIVE_FAILURES: i32 = 3;
+
+/// The delay (in seconds) before the first retry. Doubled with each further attempt
+const BASE_RETRY_DELAY: f64 = 60.0;
+
+/// The maximal delay (in seconds) between two verification attempts
+const MAX_RETRY_DELAY: f64 = 60.0 * 60.0;
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub enum VideoVerificationStatus {
+    /// The submission's video has not yet been verified to exist, but we are still trying
+    Unverified,
+
+    /// We could not verify the submission's video within the verification window and gave up.
+    /// A list helper should manually check the video
+    Flagged,
+}
+
+impl VideoVerificationStatus {
+    fn to_sql(&self) -> String {
+        match self {
+            VideoVerificationStatus::Unverified => "UNVERIFIED",
+            VideoVerificationStatus::Flagged => "FLAGGED",
+        }
+        .to_owned()
+    }
+
+    fn from_sql(sql: &str) -> Self {
+        match sql {
+            "UNVERIFIED" => VideoVerificationStatus::Unverified,
+            "FLAGGED" => VideoVerificationStatus::Flagged,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl Display for VideoVerificationStatus {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            VideoVerificationStatus::Unverified => write!(f, "unverified"),
+            VideoVerificationStatus::Flagged => write!(f, "flagged"),
+        }
+    }
+}
+
+impl Serialize for VideoVerificationStatus {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl FullRecord {
+    /// Gets the state of the verification of the video of the record with the given id
+    pub(super) async fn video_verification_of(id: i32, connection: &mut PgConnection) -> Result<Option<VideoVerificationStatus>> {
+        let row = sqlx::query!(
+            r#"SELECT status_::text AS "status!: String" FROM record_video_verifications WHERE record = $1"#,
+            id
+        )
+        .fetch_optional(connection)
+        .await?;
+
+        Ok(row.map(|row| VideoVerificationStatus::from_sql(&row.status)))
+    }
+
+    /// Marks this record's video as unverified
+    ///
+    /// The first verification attempt should be started via [`FullRecord::validate`] once the
+    /// transaction this record was created in has been committed.
+    pub(super) async fn start_video_verification(&mut self, connection: &mut PgConnection) -> Result<()> {
+        sqlx::query!("INSERT INTO record_video_verifications (record) VALUES ($1)", self.id)
+            .execute(connection)
+            .await?;
+
+        self.video_verification = Some(VideoVerificationStatus::Unverified);
+
+        Ok(())
+    }
+
+    /// Stops verification of this record's video, for instance because it was approved or rejected
+    /// by a list helper
+    pub(super) async fn stop_video_verification(&mut self, connection: &mut PgConnection) -> Result<()> {
+        sqlx::query!("DELETE FROM record_video_verifications WHERE record = $1", self.id)
+            .execute(connection)
+            .await?;
+
+        self.video_verification = None;
+
+        Ok(())
+    }
+
+    /// Makes the first attempt at verifying this record's video
+    ///
+    /// Meant to be spawned as a background task right after a record has been submitted.
+    pub async fn validate(self, state: PointercrateState) {
+        if let Err(error) = self.verify_video(&state).await {
+            error!("INTERNAL SERVER ERROR: Failure to verify video of submission - {:?}!", error)
+        }
+    }
+
+    /// Probes this record's video once and updates the verification state accordingly
+    ///
+    /// The state is updated in a transaction of its own, which is only started once the probe is
+    /// done.
+    async fn verify_video(self, state: &PointercrateState) -> Result<()> {
+        let video = match self.video {
+            Some(ref video) => video.clone(),
+            None => return Ok(()),
+        };
+
+        // Push back the next attempt before doing anything else, so that if anything below fails, this
+        // submission doesn't stay at the front of the queue
+        sqlx::query!(
+            "UPDATE record_video_verifications SET next_attempt = (NOW() AT TIME ZONE 'utc') + make_interval(secs => $2) WHERE record = $1",
+            self.id,
+            MAX_RETRY_DELAY
+        )
+        .execute(&mut *state.connection().await?)
+        .await?;
+
+        debug!("Verifying that submission {} with video {} actually is valid", self, video);
+
+        let result = video::probe(&state.http_client, &video).await;
+
+        let mut connection = state.transaction().await?;
+
+        self.apply_probe_result(&video, result, &mut connection).await?;
+
+        connection.commit().await?;
+
+        Ok(())
+    }
+
+    /// Logs the result of probing this record's video and updates the verification state
+    /// accordingly
+    async fn apply_probe_result(self, video: &str, result: ProbeResult, connection: &mut PgConnection) -> Result<()> {
+        let (response, error) = match result {
+            ProbeResult::Alive(status) | ProbeResult::Dead(status) | ProbeResult::Refused(status) => (Some(status as i16), None),
+            ProbeResult::Unreachable(ref error) => (None, Some(error.clone())),
+        };
+
+        sqlx::query!(
+            "INSERT INTO record_video_probes (userid, id, video, response, error) (SELECT id, $1, $2, $3, $4 FROM active_user LIMIT 1)",
+            self.id,
+            video,
+            response,
+            error
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        let verification = sqlx::query!(
+            r#"UPDATE record_video_verifications SET attempts = attempts + 1, definitive_failures = definitive_failures + $2 WHERE record = $1 AND status_ = 'UNVERIFIED' RETURNING attempts, definitive_failures, started_at < (NOW() AT TIME ZONE 'utc') - make_interval(hours => $3) AS "expired!: bool""#,
+            self.id,
+            result.is_definitive_failure() as i32,
+            config::video_verification_window()
+        )
+        .fetch_optional(&mut *connection)
+        .await?;
+
+        // The submission was approved or rejected in the meantime
+        let verification = match verification {
+            Some(verification) => verification,
+            None => return Ok(()),
+        };
+
+        match result {
+            ProbeResult::Alive(_) => {
+                debug!("Video probe yielded some sort of successful response, releasing webhook");
+
+                sqlx::query!("DELETE FROM record_video_verifications WHERE record = $1", self.id)
+                    .execute(&mut *connection)
+                    .await?;
+
+                webhook::delivery::release_held(self.id, connection).await
+            },
+            _ if verification.definitive_failures >= MAX_DEFINITIVE_FAILURES => {
+                warn!(
+                    "Video of submission {} ({}) repeatedly could not be found, deleting submission!",
+                    self, video
+                );
+
+                let id = self.id;
+
+                self.delete(&mut *connection).await?;
+
+                webhook::delivery::discard_held(id, connection).await
+            },
+            // A host blocking us will keep doing so, meaning there's no point in retrying. The video might
+            // well exist though, so hand the submission over to list helpers right away
+            _ if verification.expired || matches!(result, ProbeResult::Refused(_)) => {
+                warn!(
+                    "Failed to verify video of submission {} ({}) ({:?}), flagging it for manual review",
+                    self, video, result
+                );
+
+                sqlx::query!(
+                    "UPDATE record_video_verifications SET status_ = 'FLAGGED' WHERE record = $1",
+                    self.id
+                )
+                .execute(&mut *connection)
+                .await?;
+
+                webhook::delivery::release_held(self.id, connection).await
+            },
+            _ => {
+                let delay = (BASE_RETRY_DELAY * 2f64.powi(verification.attempts - 1)).min(MAX_RETRY_DELAY);
+
+                info!(
+                    "Failed to verify video of submission {} ({}): {:?}. Retrying in {} seconds",
+                    self, video, result, delay
+                );
+
+                sqlx::query!(
+                    "UPDATE record_video_verifications SET next_attempt = (NOW() AT TIME ZONE 'utc') + make_interval(secs => $2) WHERE \
+                     record = $1",
+                    self.id,
+                    delay
+                )
+                .execute(connection)
+                .await?;
+
+                Ok(())
+            },
+        }
+    }
+}
+
+/// Background task retrying the verification of submissions whose video could not yet be verified
+///
+/// Should be spawned exactly once at startup. Never returns.
+pub async fn verify_videos(state: PointercrateState) {
+    info!("Starting video verification task");
+
+    let mut interval = actix_rt::time::interval(POLL_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        if let Err(error) = verify_due(&state).await {
+            error!("INTERNAL SERVER ERROR: Failure to verify submission videos: {:?}", error);
+        }
+    }
+}
+
+async fn verify_due(state: &PointercrateState) -> Result<()> {
+    let due = sqlx::query!(
+        "SELECT record FROM record_video_verifications WHERE status_ = 'UNVERIFIED' AND next_attempt <= (NOW() AT TIME ZONE 'utc') ORDER \
+         BY next_attempt LIMIT $1",
+        BATCH_SIZE
+    )
+    .fetch_all(&mut *state.connection().await?)
+    .await?;
+
+    // A failure for one submission must not keep the others from being verified
+    for row in due {
+        if let Err(error) = verify_one(row.record, state).await {
+            error!(
+                "INTERNAL SERVER ERROR: Failure to verify video of submission {}: {:?}",
+                row.record, error
+            );
+        }
+    }
+
+    Ok(())
+}
+
+async fn verify_one(record_id: i32, state: &PointercrateState) -> Result<()> {
+    let record = FullRecord::by_id(record_id, &mut *state.connection().await?).await?;
+
+    record.verify_video(state).await
+}
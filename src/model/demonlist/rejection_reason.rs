@@ -0,0 +1,25 @@
+//! Module containing the catalogue of reasons for rejecting records
+//!
+//! When rejecting a record, list helpers can pick one of the reasons from the catalogue, which is
+//! then stored alongside the record. The catalogue itself is managed by list administrators.
+
+pub use self::{patch::PatchRejectionReason, post::PostRejectionReason};
+use derive_more::Display;
+use serde::Serialize;
+
+mod delete;
+mod get;
+mod patch;
+mod post;
+
+#[derive(Debug, Serialize, Hash, Display, PartialEq, Eq, Clone)]
+#[display(fmt = "{} (ID: {})", name, id)]
+pub struct RejectionReason {
+    pub id: i32,
+
+    /// Short name of the reason, e.g. "No clicks audible"
+    pub name: String,
+
+    /// Optional longer explanation of when this reason applies
+    pub description: Option<String>,
+}
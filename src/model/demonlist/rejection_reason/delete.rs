@@ -0,0 +1,18 @@
+use crate::{model::demonlist::rejection_reason::RejectionReason, Result};
+use log::info;
+use sqlx::PgConnection;
+
+impl RejectionReason {
+    pub async fn delete(self, connection: &mut PgConnection) -> Result<()> {
+        info!("Deleting rejection reason {}", self);
+
+        // Records rejected for this reason lose their reason due to the ON DELETE CASCADE on
+        // record_rejection_reasons.reason
+
+        sqlx::query!("DELETE FROM rejection_reasons WHERE id = $1", self.id)
+            .execute(connection)
+            .await?;
+
+        Ok(())
+    }
+}
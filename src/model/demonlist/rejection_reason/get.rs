@@ -0,0 +1,54 @@
+use crate::{error::PointercrateError, model::demonlist::rejection_reason::RejectionReason, Result};
+use sqlx::{Error, PgConnection};
+
+impl RejectionReason {
+    pub async fn by_id(id: i32, connection: &mut PgConnection) -> Result<RejectionReason> {
+        let result = sqlx::query_as!(
+            RejectionReason,
+            "SELECT id, name, description FROM rejection_reasons WHERE id = $1",
+            id
+        )
+        .fetch_one(connection)
+        .await;
+
+        match result {
+            Ok(reason) => Ok(reason),
+            Err(Error::RowNotFound) =>
+                Err(PointercrateError::ModelNotFound {
+                    model: "RejectionReason",
+                    identified_by: id.to_string(),
+                }),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Checks whether a rejection reason with the given name exists
+    pub async fn name_exists(name: &str, connection: &mut PgConnection) -> Result<bool> {
+        Ok(sqlx::query!(
+            r#"SELECT EXISTS (SELECT 1 FROM rejection_reasons WHERE name = $1) AS "exists!: bool""#,
+            name
+        )
+        .fetch_one(connection)
+        .await?
+        .exists)
+    }
+
+    pub async fn all(connection: &mut PgConnection) -> Result<Vec<RejectionReason>> {
+        Ok(
+            sqlx::query_as!(RejectionReason, "SELECT id, name, description FROM rejection_reasons ORDER BY id")
+                .fetch_all(connection)
+                .await?,
+        )
+    }
+
+    /// Gets the reason the record with the given ID was rejected for, if any
+    pub async fn of_record(record_id: i32, connection: &mut PgConnection) -> Result<Option<RejectionReason>> {
+        Ok(sqlx::query_as!(
+            RejectionReason,
+            "SELECT id, name, description FROM rejection_reasons INNER JOIN record_rejection_reasons ON reason = id WHERE record = $1",
+            record_id
+        )
+        .fetch_optional(connection)
+        .await?)
+    }
+}
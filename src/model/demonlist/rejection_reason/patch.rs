@@ -0,0 +1,58 @@
+use crate::{
+    error::PointercrateError,
+    model::demonlist::rejection_reason::RejectionReason,
+    util::{non_nullable, nullable},
+    Result,
+};
+use log::info;
+use serde::Deserialize;
+use sqlx::PgConnection;
+
+#[derive(Deserialize, Debug)]
+pub struct PatchRejectionReason {
+    #[serde(default, deserialize_with = "non_nullable")]
+    name: Option<String>,
+
+    #[serde(default, deserialize_with = "nullable")]
+    description: Option<Option<String>>,
+}
+
+impl RejectionReason {
+    pub async fn apply_patch(mut self, patch: PatchRejectionReason, connection: &mut PgConnection) -> Result<Self> {
+        info!("Patching rejection reason {} with {:?}", self, patch);
+
+        if let Some(name) = patch.name {
+            self.set_name(name, connection).await?;
+        }
+
+        if let Some(description) = patch.description {
+            self.set_description(description, connection).await?;
+        }
+
+        Ok(self)
+    }
+
+    pub async fn set_name(&mut self, name: String, connection: &mut PgConnection) -> Result<()> {
+        if name != self.name && RejectionReason::name_exists(&name, connection).await? {
+            return Err(PointercrateError::RejectionReasonExists)
+        }
+
+        sqlx::query!("UPDATE rejection_reasons SET name = $1 WHERE id = $2", name, self.id)
+            .execute(connection)
+            .await?;
+
+        self.name = name;
+
+        Ok(())
+    }
+
+    pub async fn set_description(&mut self, description: Option<String>, connection: &mut PgConnection) -> Result<()> {
+        sqlx::query!("UPDATE rejection_reasons SET description = $1 WHERE id = $2", description, self.id)
+            .execute(connection)
+            .await?;
+
+        self.description = description;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,39 @@
+use crate::{error::PointercrateError, model::demonlist::rejection_reason::RejectionReason, Result};
+use derive_more::Display;
+use log::info;
+use serde::Deserialize;
+use sqlx::PgConnection;
+
+#[derive(Deserialize, Debug, Display)]
+#[display(fmt = "{}", name)]
+pub struct PostRejectionReason {
+    pub name: String,
+
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+impl RejectionReason {
+    pub async fn create_from(data: PostRejectionReason, connection: &mut PgConnection) -> Result<RejectionReason> {
+        info!("Creating new rejection reason {}", data);
+
+        if RejectionReason::name_exists(&data.name, connection).await? {
+            return Err(PointercrateError::RejectionReasonExists)
+        }
+
+        let id = sqlx::query!(
+            "INSERT INTO rejection_reasons (name, description) VALUES ($1, $2) RETURNING id",
+            data.name,
+            data.description
+        )
+        .fetch_one(&mut *connection)
+        .await?
+        .id;
+
+        Ok(RejectionReason {
+            id,
+            name: data.name,
+            description: data.description,
+        })
+    }
+}
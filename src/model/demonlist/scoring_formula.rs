@@ -0,0 +1,180 @@
+//! Module containing the versioned scoring formula
+//!
+//! The parameters of the formula determining how many points a record is worth are stored in the
+//! `scoring_formulas` table. A formula cannot be changed after it was created. Instead, list
+//! administrators create a new version, preview the ranking it would produce and then activate it.
+//! Exactly one version is active at any time, and it is used by both [`ScoringFormula::score`] and
+//! the `players_with_score` view (via the `formula_score` SQL function).
+
+pub use self::post::PostScoringFormula;
+use chrono::NaiveDateTime;
+use derive_more::Display;
+use serde::Serialize;
+use std::hash::{Hash, Hasher};
+
+mod activate;
+mod get;
+mod post;
+
+#[derive(Debug, Serialize, Display, Clone)]
+#[display(fmt = "scoring formula {} ({})", id, description)]
+pub struct ScoringFormula {
+    pub id: i32,
+
+    /// Short description of what changed compared to the previous version
+    pub description: String,
+
+    /// The amount of points a 100% record on the demon at position 1 is worth
+    pub max_score: f64,
+
+    /// The fraction of `max_score` a 100% record on the demon at position `curve_length` is worth
+    pub min_score_ratio: f64,
+    pub curve_length: i16,
+
+    /// The lowest position at which records still give points
+    pub scored_positions: i16,
+
+    /// The fraction of a demon's points a record with exactly the demon's requirement is worth
+    pub partial_base: f64,
+
+    /// The additional fraction of a demon's points awarded as progress goes from the demon's
+    /// requirement towards 100%
+    pub partial_weight: f64,
+
+    pub active: bool,
+    pub created_at: NaiveDateTime,
+    pub created_by: Option<i32>,
+    pub activated_at: Option<NaiveDateTime>,
+
+    /// The ID of the user who last activated this formula. `None` for the initial formula
+    pub activated_by: Option<i32>,
+}
+
+impl Hash for ScoringFormula {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // formulas are immutable, only their activation status can change
+        self.id.hash(state);
+        self.active.hash(state);
+    }
+}
+
+impl ScoringFormula {
+    /// Computes the amount of points a record with the given progress on a demon at the given
+    /// position and with the given requirement is worth under this formula
+    ///
+    /// Keep in sync with the `formula_score` SQL function
+    pub fn score(&self, position: i16, requirement: i16, progress: i16) -> f64 {
+        if position > self.scored_positions || (progress != 100 && progress < requirement) {
+            return 0f64
+        }
+
+        let mut score =
+            self.max_score * f64::exp((1f64 - f64::from(position)) * self.min_score_ratio.ln() / (1f64 - f64::from(self.curve_length)));
+
+        if progress != 100 {
+            score *=
+                self.partial_base + self.partial_weight * (f64::from(progress) - f64::from(requirement)) / (100f64 - f64::from(requirement))
+        }
+
+        score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::demonlist::scoring_formula::{PostScoringFormula, ScoringFormula};
+
+    #[actix_rt::test]
+    async fn test_initial_formula_matches_record_score() {
+        let mut connection = crate::test::test_setup().await;
+
+        let initial = sqlx::query!(r#"SELECT MIN(id) AS "id!" FROM scoring_formulas"#)
+            .fetch_one(&mut connection)
+            .await
+            .unwrap()
+            .id;
+        let formula = ScoringFormula::by_id(initial, &mut connection).await.unwrap();
+
+        // record_score is what the ranking was computed with before scoring formulas were introduced
+        // (with a list size of 100)
+        let rows = sqlx::query!(
+            r#"SELECT position AS "position!", progress AS "progress!", requirement AS "requirement!",
+                      record_score(progress::FLOAT, position::FLOAT, 100::FLOAT, requirement::FLOAT) AS "old_score!",
+                      formula_score(progress::FLOAT, position::FLOAT, requirement::FLOAT, scoring_formulas) AS "new_score!"
+               FROM generate_series(1, 100) AS position, generate_series(0, 100) AS progress, 
+                    (VALUES (1), (35), (50), (99)) AS requirements(requirement), scoring_formulas
+               WHERE scoring_formulas.id = $1"#,
+            initial
+        )
+        .fetch_all(&mut connection)
+        .await
+        .unwrap();
+
+        assert_eq!(rows.len(), 100 * 101 * 4);
+
+        for row in rows {
+            let rust_score = formula.score(row.position as i16, row.requirement as i16, row.progress as i16);
+
+            assert!(
+                (row.new_score - row.old_score).abs() < 1e-9,
+                "formula_score differs from record_score for {}% at position {} (requirement {}): {} vs {}",
+                row.progress,
+                row.position,
+                row.requirement,
+                row.new_score,
+                row.old_score
+            );
+            assert!(
+                (rust_score - row.old_score).abs() < 1e-9,
+                "ScoringFormula::score differs from record_score for {}% at position {} (requirement {}): {} vs {}",
+                row.progress,
+                row.position,
+                row.requirement,
+                rust_score,
+                row.old_score
+            );
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_past_rankings_use_formula_active_back_then() {
+        let mut connection = crate::test::test_setup().await;
+
+        let initial = sqlx::query!(r#"SELECT MIN(id) AS "id!" FROM scoring_formulas"#)
+            .fetch_one(&mut connection)
+            .await
+            .unwrap()
+            .id;
+        let data = PostScoringFormula {
+            description: "Flat".to_owned(),
+            max_score: 1.0,
+            min_score_ratio: 1.0,
+            curve_length: 2,
+            scored_positions: 100,
+            partial_base: 0.0,
+            partial_weight: 0.0,
+        };
+        let member = sqlx::query!("SELECT member_id FROM members WHERE name = 'stadust_existing'")
+            .fetch_one(&mut connection)
+            .await
+            .unwrap()
+            .member_id;
+        let formula = ScoringFormula::create_from(data, member, &mut connection).await.unwrap();
+        let formula = formula.activate(member, &mut connection).await.unwrap();
+
+        // Before the first logged activation, the initial formula was active
+        let past = sqlx::query!(r#"SELECT scoring_formula_at('2000-01-01T00:00:00'::TIMESTAMP) AS "id!""#)
+            .fetch_one(&mut connection)
+            .await
+            .unwrap()
+            .id;
+        let now = sqlx::query!(r#"SELECT scoring_formula_at(NOW() AT TIME ZONE 'utc') AS "id!""#)
+            .fetch_one(&mut connection)
+            .await
+            .unwrap()
+            .id;
+
+        assert_eq!(past, initial);
+        assert_eq!(now, formula.id);
+    }
+}
@@ -0,0 +1,31 @@
+use crate::{model::demonlist::scoring_formula::ScoringFormula, Result};
+use log::info;
+use sqlx::PgConnection;
+
+impl ScoringFormula {
+    /// Makes this formula the one used to compute the player ranking, deactivating the previously
+    /// active one
+    pub async fn activate(self, activated_by: i32, connection: &mut PgConnection) -> Result<ScoringFormula> {
+        if self.active {
+            return Ok(self)
+        }
+
+        info!("Activating {} (requested by user {})", self, activated_by);
+
+        sqlx::query!("UPDATE scoring_formulas SET active = FALSE WHERE active")
+            .execute(&mut *connection)
+            .await?;
+        sqlx::query!(
+            "UPDATE scoring_formulas SET active = TRUE, activated_at = (NOW() AT TIME ZONE 'utc'), activated_by = $2 WHERE id = $1",
+            self.id,
+            activated_by
+        )
+        .execute(&mut *connection)
+        .await?;
+        sqlx::query!("INSERT INTO scoring_formula_activations (formula) VALUES ($1)", self.id)
+            .execute(&mut *connection)
+            .await?;
+
+        ScoringFormula::by_id(self.id, connection).await
+    }
+}
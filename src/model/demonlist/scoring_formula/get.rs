@@ -0,0 +1,47 @@
+use crate::{error::PointercrateError, model::demonlist::scoring_formula::ScoringFormula, Result};
+use sqlx::{Error, PgConnection};
+
+impl ScoringFormula {
+    pub async fn by_id(id: i32, connection: &mut PgConnection) -> Result<ScoringFormula> {
+        let result = sqlx::query_as!(
+            ScoringFormula,
+            "SELECT id, description, max_score, min_score_ratio, curve_length, scored_positions, partial_base, partial_weight, active, \
+             created_at, created_by, activated_at, activated_by FROM scoring_formulas WHERE id = $1",
+            id
+        )
+        .fetch_one(connection)
+        .await;
+
+        match result {
+            Ok(formula) => Ok(formula),
+            Err(Error::RowNotFound) =>
+                Err(PointercrateError::ModelNotFound {
+                    model: "ScoringFormula",
+                    identified_by: id.to_string(),
+                }),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Gets the currently active scoring formula
+    pub async fn active(connection: &mut PgConnection) -> Result<ScoringFormula> {
+        Ok(sqlx::query_as!(
+            ScoringFormula,
+            "SELECT id, description, max_score, min_score_ratio, curve_length, scored_positions, partial_base, partial_weight, active, \
+             created_at, created_by, activated_at, activated_by FROM scoring_formulas WHERE active"
+        )
+        .fetch_one(connection)
+        .await?)
+    }
+
+    /// Gets all versions of the scoring formula, newest first
+    pub async fn all(connection: &mut PgConnection) -> Result<Vec<ScoringFormula>> {
+        Ok(sqlx::query_as!(
+            ScoringFormula,
+            "SELECT id, description, max_score, min_score_ratio, curve_length, scored_positions, partial_base, partial_weight, active, \
+             created_at, created_by, activated_at, activated_by FROM scoring_formulas ORDER BY id DESC"
+        )
+        .fetch_all(connection)
+        .await?)
+    }
+}
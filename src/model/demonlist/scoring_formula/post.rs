@@ -0,0 +1,64 @@
+use crate::{error::PointercrateError, model::demonlist::scoring_formula::ScoringFormula, Result};
+use derive_more::Display;
+use log::info;
+use serde::Deserialize;
+use sqlx::PgConnection;
+
+#[derive(Deserialize, Debug, Display)]
+#[display(fmt = "{}", description)]
+pub struct PostScoringFormula {
+    pub description: String,
+    pub max_score: f64,
+    pub min_score_ratio: f64,
+    pub curve_length: i16,
+    pub scored_positions: i16,
+    pub partial_base: f64,
+    pub partial_weight: f64,
+}
+
+impl PostScoringFormula {
+    fn validate(&self) -> Result<()> {
+        let reason = if self.max_score <= 0f64 {
+            "max_score needs to be positive"
+        } else if self.min_score_ratio <= 0f64 || self.min_score_ratio > 1f64 {
+            "min_score_ratio needs to be greater than 0 and at most 1"
+        } else if self.curve_length < 2 {
+            "curve_length needs to be at least 2"
+        } else if self.scored_positions < 1 {
+            "scored_positions needs to be at least 1"
+        } else if self.partial_base < 0f64 || self.partial_weight < 0f64 {
+            "partial_base and partial_weight cannot be negative"
+        } else {
+            return Ok(())
+        };
+
+        Err(PointercrateError::InvalidScoringFormula { reason })
+    }
+}
+
+impl ScoringFormula {
+    /// Creates a new, inactive version of the scoring formula
+    pub async fn create_from(data: PostScoringFormula, created_by: i32, connection: &mut PgConnection) -> Result<ScoringFormula> {
+        info!("Creating new scoring formula {}", data);
+
+        data.validate()?;
+
+        let id = sqlx::query!(
+            "INSERT INTO scoring_formulas (description, max_score, min_score_ratio, curve_length, scored_positions, partial_base, \
+             partial_weight, created_by) VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING id",
+            data.description,
+            data.max_score,
+            data.min_score_ratio,
+            data.curve_length,
+            data.scored_positions,
+            data.partial_base,
+            data.partial_weight,
+            created_by
+        )
+        .fetch_one(&mut *connection)
+        .await?
+        .id;
+
+        ScoringFormula::by_id(id, connection).await
+    }
+}
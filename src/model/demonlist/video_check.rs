@@ -0,0 +1,150 @@
+//! Module containing the periodic liveness check of videos on the list
+//!
+//! Videos of approved records and verification videos of demons tend to get deleted or privated
+//! over time. A background task (see [`recheck_videos`]) periodically re-probes all of them and
+//! stores the result in the `video_checks` table, so that list moderators can find records whose
+//! video has gone dead.
+
+use crate::{
+    config,
+    state::PointercrateState,
+    video::{self, ProbeResult},
+    Result,
+};
+use log::{debug, error, info, warn};
+use sqlx::PgConnection;
+use std::{
+    fmt::{Display, Formatter},
+    time::Duration,
+};
+
+/// How often we check whether videos are due for a re-check
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The maximal number of videos probed per tick. Kept small so we do not hammer video hosts
+const BATCH_SIZE: i64 = 10;
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub enum VideoStatus {
+    /// The video host responded successfully the last time we checked
+    Alive,
+
+    /// The video host responded with an error status code the last time we checked, meaning the
+    /// video has most likely been deleted or privated
+    Dead,
+
+    /// We could not reach the video host the last time we checked, or it refused to tell us
+    /// anything about the video
+    Unreachable,
+}
+
+impl VideoStatus {
+    fn to_sql(&self) -> String {
+        match self {
+            VideoStatus::Alive => "ALIVE",
+            VideoStatus::Dead => "DEAD",
+            VideoStatus::Unreachable => "UNREACHABLE",
+        }
+        .to_owned()
+    }
+}
+
+impl Display for VideoStatus {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            VideoStatus::Alive => write!(f, "alive"),
+            VideoStatus::Dead => write!(f, "dead"),
+            VideoStatus::Unreachable => write!(f, "unreachable"),
+        }
+    }
+}
+
+impl From<&ProbeResult> for VideoStatus {
+    fn from(result: &ProbeResult) -> Self {
+        match result {
+            ProbeResult::Alive(_) => VideoStatus::Alive,
+            ProbeResult::Dead(_) => VideoStatus::Dead,
+            // Hosts blocking us don't tell us anything about the video itself
+            ProbeResult::Refused(_) | ProbeResult::Unreachable(_) => VideoStatus::Unreachable,
+        }
+    }
+}
+
+/// Stores the result of probing the given video, overriding any previous result
+///
+/// `dead_since` is set the first time a video is found to be dead, and cleared once it is found to
+/// be alive again. Unreachable video hosts do not affect it.
+async fn store_check(video: &str, result: &ProbeResult, connection: &mut PgConnection) -> Result<()> {
+    let (response, error) = match result {
+        ProbeResult::Alive(status) | ProbeResult::Dead(status) | ProbeResult::Refused(status) => (Some(*status as i16), None),
+        ProbeResult::Unreachable(error) => (None, Some(error.as_str())),
+    };
+
+    sqlx::query!(
+        "INSERT INTO video_checks (video, status_, last_response, last_error, dead_since) VALUES ($1, cast($2::text as VIDEO_STATUS), $3, \
+         $4, CASE WHEN $2 = 'DEAD' THEN NOW() AT TIME ZONE 'utc' END) ON CONFLICT (video) DO UPDATE SET status_ = EXCLUDED.status_, \
+         last_response = EXCLUDED.last_response, last_error = EXCLUDED.last_error, checked_at = EXCLUDED.checked_at, dead_since = CASE \
+         WHEN EXCLUDED.status_ = 'ALIVE' THEN NULL WHEN EXCLUDED.status_ = 'DEAD' THEN COALESCE(video_checks.dead_since, \
+         EXCLUDED.dead_since) ELSE video_checks.dead_since END",
+        video,
+        VideoStatus::from(result).to_sql(),
+        response,
+        error
+    )
+    .execute(connection)
+    .await?;
+
+    Ok(())
+}
+
+/// Gets the videos of approved records and demons that were either never checked, or whose last
+/// check is older than [`config::video_recheck_interval`]
+///
+/// Videos that were never checked come first, then the ones whose check is the oldest.
+async fn due_videos(limit: i64, connection: &mut PgConnection) -> Result<Vec<String>> {
+    let rows = sqlx::query!(
+        r#"SELECT videos.video AS "video!: String" FROM (SELECT video FROM records WHERE status_ = 'APPROVED' AND video IS NOT NULL UNION SELECT video FROM demons WHERE video IS NOT NULL) AS videos LEFT OUTER JOIN video_checks ON videos.video = video_checks.video WHERE video_checks.checked_at IS NULL OR video_checks.checked_at < (NOW() AT TIME ZONE 'utc') - make_interval(days => $1) ORDER BY video_checks.checked_at NULLS FIRST LIMIT $2"#,
+        config::video_recheck_interval(),
+        limit
+    )
+    .fetch_all(connection)
+    .await?;
+
+    Ok(rows.into_iter().map(|row| row.video).collect())
+}
+
+/// Background task periodically re-checking all videos on the list
+///
+/// Should be spawned exactly once at startup. Never returns.
+pub async fn recheck_videos(state: PointercrateState) {
+    info!("Starting video recheck task");
+
+    let mut interval = actix_rt::time::interval(POLL_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        if let Err(error) = recheck_due(&state).await {
+            error!("INTERNAL SERVER ERROR: Failure to recheck videos: {:?}", error);
+        }
+    }
+}
+
+async fn recheck_due(state: &PointercrateState) -> Result<()> {
+    let mut connection = state.connection().await?;
+
+    for video in due_videos(BATCH_SIZE, &mut connection).await? {
+        let result = video::probe(&state.http_client, &video).await;
+
+        match result {
+            ProbeResult::Alive(status) => debug!("Video {} is alive ({})", video, status),
+            ProbeResult::Dead(status) => warn!("Video {} appears to be dead ({})", video, status),
+            ProbeResult::Refused(status) => warn!("Video host of {} refused our requests ({})", video, status),
+            ProbeResult::Unreachable(ref error) => warn!("Failed to reach video {}: {}", video, error),
+        }
+
+        store_check(&video, &result, &mut connection).await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,144 @@
+//! Module containing the detection of videos that are used more than once on the list
+//!
+//! Videos are not compared by their URL, but by their canonical key (see [`video::key`]), so that
+//! for instance a YouTube video linked once with and once without a timestamp is recognized as the
+//! same video. The same video can neither be used by two records, nor by both a record and a demon
+//! (as its verification video), nor by two demons.
+
+use crate::{
+    error::PointercrateError,
+    model::demonlist::{demon::MinimalDemon, record::RecordStatus},
+    state::PointercrateState,
+    video, Result,
+};
+use log::{error, info};
+use serde::Serialize;
+use sqlx::PgConnection;
+use std::fmt::{Display, Formatter};
+
+/// An object already using some video
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum VideoConflict {
+    Record { id: i32, status: RecordStatus },
+    Demon(MinimalDemon),
+}
+
+impl Display for VideoConflict {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            VideoConflict::Record { id, status } => write!(f, "{} record #{}", status, id),
+            VideoConflict::Demon(demon) => write!(f, "the verification of {}", demon),
+        }
+    }
+}
+
+/// The object whose video is being set, which is excluded from duplicate detection
+#[derive(Debug, Clone, Copy)]
+pub enum VideoOwner {
+    Record(i32),
+    Demon(i32),
+}
+
+/// Ensures that the given video isn't already used by any record or demon (other than `owner`)
+///
+/// Also stores the video's key, so that it is taken into account by subsequent checks. The given
+/// video must already have been validated.
+pub async fn ensure_unused(video: &str, owner: Option<VideoOwner>, connection: &mut PgConnection) -> Result<()> {
+    let key = store_key(video, &mut *connection).await?;
+
+    let (record, demon) = match owner {
+        Some(VideoOwner::Record(id)) => (Some(id), None),
+        Some(VideoOwner::Demon(id)) => (None, Some(id)),
+        None => (None, None),
+    };
+
+    let existing_record = sqlx::query!(
+        r#"SELECT records.id, records.status_::text AS "status!: String" FROM records INNER JOIN video_keys ON records.video = video_keys.video 
+         WHERE key = $1 AND ($2::INTEGER IS NULL OR records.id <> $2) LIMIT 1"#,
+        key,
+        record
+    )
+    .fetch_optional(&mut *connection)
+    .await?;
+
+    if let Some(row) = existing_record {
+        return Err(PointercrateError::DuplicateVideo {
+            conflict: VideoConflict::Record {
+                id: row.id,
+                status: RecordStatus::from_sql(&row.status),
+            },
+        })
+    }
+
+    let existing_demon = sqlx::query!(
+        r#"SELECT demons.id, demons.name::text AS "name!: String", demons.position FROM demons INNER JOIN video_keys ON demons.video = 
+         video_keys.video WHERE key = $1 AND ($2::INTEGER IS NULL OR demons.id <> $2) LIMIT 1"#,
+        key,
+        demon
+    )
+    .fetch_optional(connection)
+    .await?;
+
+    match existing_demon {
+        Some(row) =>
+            Err(PointercrateError::DuplicateVideo {
+                conflict: VideoConflict::Demon(MinimalDemon {
+                    id: row.id,
+                    position: row.position,
+                    name: row.name.into(),
+                }),
+            }),
+        None => Ok(()),
+    }
+}
+
+/// Computes and stores the key of the given video, returning the key
+///
+/// Keys of videos that stop being used are removed by a trigger on the `records` and `demons`
+/// tables
+async fn store_key(video: &str, connection: &mut PgConnection) -> Result<String> {
+    let key = video::key(video);
+
+    sqlx::query!(
+        "INSERT INTO video_keys (video, key) VALUES ($1, $2) ON CONFLICT (video) DO UPDATE SET key = EXCLUDED.key",
+        video,
+        key
+    )
+    .execute(connection)
+    .await?;
+
+    Ok(key)
+}
+
+/// Background task computing the keys of all videos that predate the `video_keys` table
+///
+/// Should be spawned exactly once at startup. Returns once all keys have been computed.
+pub async fn backfill_video_keys(state: PointercrateState) {
+    if let Err(error) = backfill(&state).await {
+        error!("INTERNAL SERVER ERROR: Failure to compute video keys: {:?}", error);
+    }
+}
+
+async fn backfill(state: &PointercrateState) -> Result<()> {
+    let mut connection = state.connection().await?;
+
+    let videos = sqlx::query!(
+        r#"SELECT video::text AS "video!: String" FROM records WHERE video IS NOT NULL AND video NOT IN (SELECT video FROM video_keys) UNION 
+         SELECT video::text FROM demons WHERE video IS NOT NULL AND video NOT IN (SELECT video FROM video_keys)"#
+    )
+    .fetch_all(&mut connection)
+    .await?;
+
+    if videos.is_empty() {
+        return Ok(())
+    }
+
+    info!("Computing keys of {} videos", videos.len());
+
+    for row in videos {
+        store_key(&row.video, &mut connection).await?;
+    }
+
+    Ok(())
+}
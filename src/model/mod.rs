@@ -16,6 +16,8 @@
 //! Only the `Database` representation always exists. The others are occasionally not necessary
 //! distinct from each other
 
+pub mod audit;
 pub mod demonlist;
 pub mod nationality;
 pub mod user;
+pub mod webhook;
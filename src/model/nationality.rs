@@ -1,12 +1,50 @@
+pub use self::{
+    heatmap::{HeatmapOptions, NationAggregate},
+    ranking::{FullRankedNation, NationRankingPagination, RankedNation},
+    statistics::{FullNationality, NationalityStatistics},
+};
 use crate::cistring::CiString;
 use derive_more::Constructor;
 use serde::Serialize;
 
 mod get;
+mod heatmap;
+mod ranking;
+mod statistics;
 
 #[derive(Debug, PartialEq, Eq, Serialize, Hash, Constructor)]
 pub struct Nationality {
     #[serde(rename = "country_code")]
     pub iso_country_code: String,
     pub nation: CiString,
+
+    /// The subdivision of this nation a player is from, if this nationality is a player's and
+    /// they have set one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subdivision: Option<Subdivision>,
+}
+
+/// A first-level ISO 3166-2 subdivision (state, province, ...) of some nation
+#[derive(Debug, PartialEq, Eq, Serialize, Hash, Clone)]
+pub struct Subdivision {
+    /// The part of the subdivision's ISO 3166-2 code after the hyphen, e.g. `CA` for California
+    /// (`US-CA`)
+    #[serde(rename = "code")]
+    pub iso_code: String,
+    pub name: CiString,
+}
+
+impl Subdivision {
+    /// Constructs the subdivision a player is from from the columns of a query joining the
+    /// `subdivisions` table
+    pub fn from_columns(iso_code: Option<String>, name: Option<String>) -> Option<Subdivision> {
+        match (iso_code, name) {
+            (Some(iso_code), Some(name)) =>
+                Some(Subdivision {
+                    iso_code,
+                    name: CiString(name),
+                }),
+            _ => None,
+        }
+    }
 }
@@ -1,7 +1,7 @@
 use crate::{
     cistring::{CiStr, CiString},
     error::PointercrateError,
-    model::nationality::Nationality,
+    model::nationality::{Nationality, Subdivision},
     Result,
 };
 use futures::stream::StreamExt;
@@ -19,6 +19,7 @@ impl Nationality {
             Nationality {
                 nation: CiString::from(row.nation),
                 iso_country_code: row.iso_country_code,
+                subdivision: None,
             }
         })
         .map_err(|sqlx_error| {
@@ -45,9 +46,67 @@ impl Nationality {
             nationalities.push(Nationality {
                 nation: CiString::from(row.nation),
                 iso_country_code: row.iso_country_code,
+                subdivision: None,
             })
         }
 
         Ok(nationalities)
     }
+
+    /// Gets the subdivision of this nation with the given code or name
+    ///
+    /// The code can optionally be prefixed with the nation's country code, i.e. both `CA` and
+    /// `US-CA` identify California
+    pub async fn subdivision_by_code_or_name(&self, code: &CiStr, connection: &mut PgConnection) -> Result<Subdivision> {
+        let code = code.to_string(); /* FIXME(sqlx 0.3) */
+        let prefix = format!("{}-", self.iso_country_code);
+        let iso_code = match code.get(..prefix.len()) {
+            Some(start) if start.eq_ignore_ascii_case(&prefix) => &code[prefix.len()..],
+            _ => &code[..],
+        };
+
+        sqlx::query!(
+            r#"SELECT iso_code::TEXT AS "iso_code!", name::TEXT AS "name!" FROM subdivisions WHERE nation = $1 AND (iso_code = UPPER($2) OR 
+             name = $3::CITEXT)"#,
+            self.iso_country_code,
+            iso_code,
+            code
+        )
+        .fetch_one(connection)
+        .await
+        .map(|row| {
+            Subdivision {
+                iso_code: row.iso_code,
+                name: CiString(row.name),
+            }
+        })
+        .map_err(|sqlx_error| {
+            match sqlx_error {
+                Error::RowNotFound =>
+                    PointercrateError::ModelNotFound {
+                        model: "Subdivision",
+                        identified_by: format!("{}-{}", self.iso_country_code, iso_code),
+                    },
+                _ => sqlx_error.into(),
+            }
+        })
+    }
+
+    /// Gets all subdivisions of this nation, ordered by name
+    pub async fn subdivisions(&self, connection: &mut PgConnection) -> Result<Vec<Subdivision>> {
+        Ok(sqlx::query!(
+            r#"SELECT iso_code::TEXT AS "iso_code!", name::TEXT AS "name!" FROM subdivisions WHERE nation = $1 ORDER BY name"#,
+            self.iso_country_code
+        )
+        .fetch_all(connection)
+        .await?
+        .into_iter()
+        .map(|row| {
+            Subdivision {
+                iso_code: row.iso_code,
+                name: CiString(row.name),
+            }
+        })
+        .collect())
+    }
 }
@@ -0,0 +1,252 @@
+//! Module containing the per-nation aggregates the stats viewer's world map is coloured by
+//!
+//! Every nation with at least one player with a non-zero score is assigned a "level" between a
+//! given lower and upper bound, based on one of its aggregates. Since these values are very
+//! unevenly distributed (a handful of nations have orders of magnitude more players than most
+//! others), they are not simply scaled linearly. Instead, the sorted values are split into
+//! subdivisions at the largest jumps between consecutive values, and every subdivision gets an
+//! equal share of the level range.
+
+use crate::{
+    cistring::CiString,
+    error::PointercrateError,
+    model::{demonlist::demon::MinimalDemon, nationality::Nationality},
+    util::non_nullable,
+    Result,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::PgConnection;
+use std::collections::HashMap;
+
+/// The largest level that can be requested for the heatmap
+const MAX_LEVEL: i64 = 1000;
+
+/// The aggregate the heatmap levels are computed from
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HeatmapMetric {
+    Players,
+    TotalScore,
+    AverageScore,
+}
+
+impl Default for HeatmapMetric {
+    fn default() -> Self {
+        HeatmapMetric::Players
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HeatmapOptions {
+    #[serde(default, deserialize_with = "non_nullable")]
+    pub low_level: Option<i64>,
+
+    #[serde(default, deserialize_with = "non_nullable")]
+    pub high_level: Option<i64>,
+
+    #[serde(default)]
+    pub metric: HeatmapMetric,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NationAggregate {
+    #[serde(flatten)]
+    pub nationality: Nationality,
+
+    /// The number of players of this nation with a non-zero score
+    pub players: i64,
+    pub total_score: f64,
+    pub average_score: f64,
+
+    /// The highest demon any (non-banned) player of this nation has a 100% record on or verified
+    pub hardest: Option<MinimalDemon>,
+
+    /// This nation's level on the heatmap, see the module documentation
+    pub level: i64,
+}
+
+impl NationAggregate {
+    /// Computes the aggregates of all nations with at least one player with a non-zero score, and
+    /// assigns them heatmap levels according to the given options
+    pub async fn all(options: &HeatmapOptions, connection: &mut PgConnection) -> Result<Vec<NationAggregate>> {
+        let low_level = options.low_level.unwrap_or(0);
+        let high_level = options.high_level.unwrap_or(100);
+
+        if low_level < 0 || low_level > high_level || high_level > MAX_LEVEL {
+            return Err(PointercrateError::InvalidHeatmapLevels)
+        }
+
+        let rows = sqlx::query!(
+            r#"SELECT nationalities.nation::TEXT AS "nation!", nationalities.iso_country_code::TEXT AS "iso_country_code!", scores.players 
+             AS "players!", scores.total_score AS "total_score!", scores.average_score AS "average_score!", hardest.id AS "hardest_id?", 
+             hardest.name::TEXT AS "hardest_name?", hardest.position AS "hardest_position?" FROM (SELECT iso_country_code, COUNT(*) AS 
+             players, SUM(score) AS total_score, AVG(score) AS average_score FROM players_with_score WHERE iso_country_code IS NOT NULL AND 
+             score != 0 GROUP BY iso_country_code) scores INNER JOIN nationalities ON nationalities.iso_country_code = scores.iso_country_code 
+             LEFT OUTER JOIN (SELECT DISTINCT ON (players.nationality) players.nationality, demons.id, demons.name, demons.position FROM 
+             demons INNER JOIN (SELECT demon, player FROM records WHERE status_ = 'APPROVED' AND progress = 100 UNION SELECT id, verifier 
+             FROM demons) beaten ON beaten.demon = demons.id INNER JOIN players ON players.id = beaten.player WHERE players.nationality IS 
+             NOT NULL AND NOT players.banned ORDER BY players.nationality, demons.position) hardest ON hardest.nationality = 
+             nationalities.iso_country_code ORDER BY nationalities.nation"#
+        )
+        .fetch_all(connection)
+        .await?;
+
+        let mut aggregates: Vec<NationAggregate> = rows
+            .into_iter()
+            .map(|row| {
+                let hardest = match (row.hardest_id, row.hardest_name, row.hardest_position) {
+                    (Some(id), Some(name), Some(position)) =>
+                        Some(MinimalDemon {
+                            id,
+                            position,
+                            name: CiString(name),
+                        }),
+                    _ => None,
+                };
+
+                NationAggregate {
+                    nationality: Nationality {
+                        iso_country_code: row.iso_country_code,
+                        nation: CiString(row.nation),
+                        subdivision: None,
+                    },
+                    players: row.players,
+                    total_score: row.total_score,
+                    average_score: row.average_score,
+                    hardest,
+                    level: low_level,
+                }
+            })
+            .collect();
+
+        let values: HashMap<String, i64> = aggregates
+            .iter()
+            .map(|aggregate| {
+                let value = match options.metric {
+                    HeatmapMetric::Players => aggregate.players,
+                    HeatmapMetric::TotalScore => aggregate.total_score.round() as i64,
+                    HeatmapMetric::AverageScore => aggregate.average_score.round() as i64,
+                };
+
+                (aggregate.nationality.iso_country_code.clone(), value)
+            })
+            .collect();
+
+        let levels = compute_levels(&values, low_level, high_level);
+
+        for aggregate in &mut aggregates {
+            if let Some(&level) = levels.get(&aggregate.nationality.iso_country_code) {
+                aggregate.level = level
+            }
+        }
+
+        Ok(aggregates)
+    }
+}
+
+fn compute_levels(map: &HashMap<String, i64>, low_level: i64, high_level: i64) -> HashMap<&String, i64> {
+    if map.is_empty() {
+        return HashMap::new()
+    }
+
+    let sorted_values: Vec<i64> = {
+        let mut values: Vec<i64> = map.values().copied().collect();
+        values.sort();
+        values
+    };
+
+    let mut differences: Vec<(usize, i64)> = sorted_values.windows(2).map(|w| w[1] - w[0]).enumerate().collect();
+
+    differences.sort();
+
+    // search for local maxima in the data stream
+    let mut division_points: Vec<usize> = differences
+        .windows(3)
+        .filter_map(|w| {
+            if w[1].1 > w[0].1 && w[2].1 < w[1].1 {
+                Some(w[1].0 + 1)
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    if differences.len() > 1 && differences[0] > differences[1] {
+        division_points.insert(0, 1);
+    }
+
+    let subdivisions = division_points.len() as i64 + 1;
+
+    division_points.insert(0, 0);
+    division_points.push(sorted_values.len());
+
+    division_points.sort();
+
+    let max_per_division: Vec<i64> = division_points.iter().skip(1).map(|&idx| sorted_values[idx - 1]).collect();
+    let levels_per_subdivision = (high_level - low_level) / subdivisions;
+
+    let mut level_map = HashMap::new();
+
+    for (key, value) in map {
+        let rank = sorted_values.iter().position(|v| *v == *value).unwrap();
+        let division = division_points.iter().rposition(|&idx| rank >= idx).unwrap();
+
+        let base_level = low_level + ((high_level - low_level) / subdivisions) * (division as i64);
+
+        // values of 0 can happen if the metric is a rounded score
+        let level = match max_per_division[division] {
+            0 => base_level,
+            max => base_level + *value * levels_per_subdivision / max,
+        };
+
+        level_map.insert(key, level);
+    }
+
+    level_map
+}
+
+#[cfg(test)]
+mod tests {
+    use super::compute_levels;
+    use std::collections::HashMap;
+
+    fn levels(values: &[(&str, i64)], low_level: i64, high_level: i64) -> Vec<(String, i64)> {
+        let map: HashMap<String, i64> = values.iter().map(|(code, value)| (code.to_string(), *value)).collect();
+        let mut levels: Vec<(String, i64)> = compute_levels(&map, low_level, high_level)
+            .into_iter()
+            .map(|(code, level)| (code.clone(), level))
+            .collect();
+
+        levels.sort();
+        levels
+    }
+
+    #[test]
+    fn test_no_nations() {
+        assert!(levels(&[], 0, 100).is_empty());
+    }
+
+    #[test]
+    fn test_single_nation() {
+        assert_eq!(levels(&[("DE", 5)], 10, 60), vec![("DE".to_owned(), 60)]);
+        // A rounded score of 0 gets the lowest level instead of dividing by zero
+        assert_eq!(levels(&[("DE", 0)], 10, 60), vec![("DE".to_owned(), 10)]);
+    }
+
+    #[test]
+    fn test_two_nations() {
+        assert_eq!(levels(&[("DE", 5), ("US", 10)], 0, 100), vec![
+            ("DE".to_owned(), 50),
+            ("US".to_owned(), 100)
+        ]);
+    }
+
+    #[test]
+    fn test_empty_level_range() {
+        assert_eq!(levels(&[("DE", 5), ("GB", 7), ("US", 10)], 20, 20), vec![
+            ("DE".to_owned(), 20),
+            ("GB".to_owned(), 20),
+            ("US".to_owned(), 20)
+        ]);
+    }
+}
@@ -0,0 +1,196 @@
+//! Module containing the ranking of nations
+//!
+//! A nation's score is computed from the scores of its players by the `nations_with_score` view.
+//! To keep nations with lots of players from dominating the ranking, every additional player
+//! contributes less than the previous one (the n-th best player contributes 0.9^(n-1) of their
+//! score).
+
+use crate::{
+    cistring::{CiStr, CiString},
+    error::PointercrateError,
+    model::{
+        demonlist::{demon::MinimalDemon, player::RankedPlayer},
+        nationality::{Nationality, Subdivision},
+    },
+    util::non_nullable,
+    Result,
+};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use sqlx::{Error, PgConnection, Row};
+
+/// The number of players listed in the detailed view of a nation
+const TOP_PLAYERS: i64 = 10;
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct RankedNation {
+    pub rank: i64,
+    pub score: f64,
+
+    #[serde(flatten)]
+    pub nationality: Nationality,
+
+    /// The number of players of this nation with a non-zero score
+    pub players: i64,
+
+    #[serde(skip)]
+    pub index: i64,
+}
+
+/// A nation's position in the ranking, together with its best players and the demons beaten by
+/// its players
+#[derive(Debug, Serialize)]
+pub struct FullRankedNation {
+    #[serde(flatten)]
+    pub nation: RankedNation,
+    pub top_players: Vec<RankedPlayer>,
+
+    /// See [`Nationality::beaten_demons`]
+    pub beaten: Vec<MinimalDemon>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NationRankingPagination {
+    #[serde(default, deserialize_with = "non_nullable")]
+    #[serde(rename = "before")]
+    pub before_index: Option<i64>,
+
+    #[serde(default, deserialize_with = "non_nullable")]
+    #[serde(rename = "after")]
+    pub after_index: Option<i64>,
+
+    #[serde(default, deserialize_with = "non_nullable")]
+    pub limit: Option<u8>,
+
+    #[serde(default, deserialize_with = "non_nullable")]
+    name_contains: Option<CiString>,
+}
+
+impl NationRankingPagination {
+    pub async fn page(&self, connection: &mut PgConnection) -> Result<Vec<RankedNation>> {
+        if let Some(limit) = self.limit {
+            if limit < 1 || limit > 100 {
+                return Err(PointercrateError::InvalidPaginationLimit)
+            }
+        }
+
+        let order = if self.before_index.is_some() && self.after_index.is_none() {
+            "DESC"
+        } else {
+            "ASC"
+        };
+
+        let query = format!(include_str!("../../../sql/paginate_nation_ranking.sql"), order);
+
+        let mut stream = sqlx::query(&query)
+            .bind(self.before_index)
+            .bind(self.after_index)
+            .bind(self.name_contains.as_ref().map(|s| s.as_str()))
+            .bind(self.limit.unwrap_or(50) as i32 + 1)
+            .fetch(connection);
+
+        let mut nations = Vec::new();
+
+        while let Some(row) = stream.next().await {
+            let row = row?;
+
+            nations.push(RankedNation {
+                rank: row.get("rank"),
+                score: row.get("score"),
+                nationality: Nationality {
+                    iso_country_code: row.get("iso_country_code"),
+                    nation: CiString(row.get("nation")),
+                    subdivision: None,
+                },
+                players: row.get("players"),
+                index: row.get("index"),
+            })
+        }
+
+        Ok(nations)
+    }
+}
+
+impl RankedNation {
+    /// Gets the highest index value generated by the `nations_with_score` view
+    pub async fn max_index(connection: &mut PgConnection) -> Result<i64> {
+        Ok(
+            sqlx::query!(r#"SELECT COALESCE(MAX(index), 0) AS "max_index!: i64" FROM nations_with_score"#)
+                .fetch_one(connection)
+                .await?
+                .max_index,
+        )
+    }
+
+    /// Gets the ranking entry of the nation with the given country code or name
+    ///
+    /// Nations without any player with a non-zero score are not part of the ranking
+    pub async fn by_country_code_or_name(code: &CiStr, connection: &mut PgConnection) -> Result<RankedNation> {
+        let result = sqlx::query!(
+            r#"SELECT rank AS "rank!", score AS "score!", index AS "index!", nation AS "nation!: String", iso_country_code AS 
+             "iso_country_code!: String", players AS "players!" FROM nations_with_score WHERE iso_country_code = $1 OR nation = $1"#,
+            code.to_string() /* FIXME(sqlx 0.3) */
+        )
+        .fetch_one(connection)
+        .await;
+
+        match result {
+            Ok(row) =>
+                Ok(RankedNation {
+                    rank: row.rank,
+                    score: row.score,
+                    nationality: Nationality {
+                        iso_country_code: row.iso_country_code,
+                        nation: CiString(row.nation),
+                        subdivision: None,
+                    },
+                    players: row.players,
+                    index: row.index,
+                }),
+            Err(Error::RowNotFound) =>
+                Err(PointercrateError::ModelNotFound {
+                    model: "RankedNation",
+                    identified_by: code.to_string(),
+                }),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    pub async fn upgrade(self, connection: &mut PgConnection) -> Result<FullRankedNation> {
+        let top_players = sqlx::query!(
+            r#"SELECT players_with_score.id AS "id!", players_with_score.name AS "name!: String", rank AS "rank!", score AS "score!", index AS 
+             "index!", subdivisions.iso_code::TEXT AS subdivision_code, subdivisions.name::TEXT AS subdivision_name FROM players_with_score 
+             INNER JOIN players ON players.id = players_with_score.id LEFT OUTER JOIN subdivisions ON subdivisions.nation = 
+             players.nationality AND subdivisions.iso_code = players.subdivision WHERE players_with_score.iso_country_code = $1 ORDER BY 
+             rank LIMIT $2"#,
+            self.nationality.iso_country_code,
+            TOP_PLAYERS
+        )
+        .fetch_all(&mut *connection)
+        .await?
+        .into_iter()
+        .map(|row| {
+            RankedPlayer {
+                id: row.id,
+                name: CiString(row.name),
+                rank: row.rank,
+                score: row.score,
+                nationality: Some(Nationality {
+                    iso_country_code: self.nationality.iso_country_code.clone(),
+                    nation: self.nationality.nation.clone(),
+                    subdivision: Subdivision::from_columns(row.subdivision_code, row.subdivision_name),
+                }),
+                index: row.index,
+            }
+        })
+        .collect();
+
+        let beaten = self.nationality.beaten_demons(connection).await?;
+
+        Ok(FullRankedNation {
+            nation: self,
+            top_players,
+            beaten,
+        })
+    }
+}
@@ -0,0 +1,119 @@
+//! Module containing aggregated information about the players of each nation
+
+use crate::{
+    cistring::{CiStr, CiString},
+    model::{
+        demonlist::demon::MinimalDemon,
+        nationality::{Nationality, Subdivision},
+    },
+    Result,
+};
+use serde::Serialize;
+use sqlx::PgConnection;
+
+/// A nation together with some statistics about its players
+#[derive(Debug, Serialize)]
+pub struct NationalityStatistics {
+    #[serde(flatten)]
+    pub nationality: Nationality,
+
+    /// The number of (non-banned) players of this nation
+    pub players: i64,
+
+    /// The sum of the scores of all players of this nation. Unlike the score in the nation
+    /// ranking, this has no diminishing returns
+    pub score: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FullNationality {
+    #[serde(flatten)]
+    pub statistics: NationalityStatistics,
+    pub subdivisions: Vec<Subdivision>,
+
+    /// See [`Nationality::beaten_demons`]
+    pub beaten: Vec<MinimalDemon>,
+}
+
+impl NationalityStatistics {
+    /// Gets the statistics of all nations, ordered by name
+    pub async fn all(connection: &mut PgConnection) -> Result<Vec<NationalityStatistics>> {
+        Ok(sqlx::query!(
+            r#"SELECT nation::TEXT AS "nation!", nationalities.iso_country_code::TEXT AS "iso_country_code!", COALESCE(counts.players, 0) AS 
+             "players!", COALESCE(scores.score, 0.0) AS "score!" FROM nationalities LEFT OUTER JOIN (SELECT nationality, COUNT(*) AS players 
+             FROM players WHERE NOT banned GROUP BY nationality) counts ON counts.nationality = nationalities.iso_country_code LEFT OUTER JOIN 
+             (SELECT iso_country_code, SUM(score) AS score FROM players_with_score GROUP BY iso_country_code) scores ON 
+             scores.iso_country_code = nationalities.iso_country_code ORDER BY nation"#
+        )
+        .fetch_all(connection)
+        .await?
+        .into_iter()
+        .map(|row| {
+            NationalityStatistics {
+                nationality: Nationality {
+                    iso_country_code: row.iso_country_code,
+                    nation: CiString(row.nation),
+                    subdivision: None,
+                },
+                players: row.players,
+                score: row.score,
+            }
+        })
+        .collect())
+    }
+
+    /// Gets the statistics of the nation with the given country code or name
+    pub async fn by_country_code_or_name(code: &CiStr, connection: &mut PgConnection) -> Result<NationalityStatistics> {
+        let nationality = Nationality::by_country_code_or_name(code, &mut *connection).await?;
+
+        let row = sqlx::query!(
+            r#"SELECT (SELECT COUNT(*) FROM players WHERE nationality = $1 AND NOT banned) AS "players!", (SELECT COALESCE(SUM(score), 0.0) 
+             FROM players_with_score WHERE iso_country_code = $1) AS "score!""#,
+            nationality.iso_country_code
+        )
+        .fetch_one(connection)
+        .await?;
+
+        Ok(NationalityStatistics {
+            nationality,
+            players: row.players,
+            score: row.score,
+        })
+    }
+
+    pub async fn upgrade(self, connection: &mut PgConnection) -> Result<FullNationality> {
+        let subdivisions = self.nationality.subdivisions(&mut *connection).await?;
+        let beaten = self.nationality.beaten_demons(connection).await?;
+
+        Ok(FullNationality {
+            statistics: self,
+            subdivisions,
+            beaten,
+        })
+    }
+}
+
+impl Nationality {
+    /// Gets all demons any (non-banned) player of this nation has a 100% record on or verified,
+    /// ordered by position
+    pub async fn beaten_demons(&self, connection: &mut PgConnection) -> Result<Vec<MinimalDemon>> {
+        Ok(sqlx::query!(
+            r#"SELECT id, name AS "name: String", position FROM demons WHERE EXISTS (SELECT 1 FROM records INNER JOIN players ON 
+             players.id = records.player WHERE records.demon = demons.id AND records.status_ = 'APPROVED' AND records.progress = 100 AND 
+             players.nationality = $1 AND NOT players.banned) OR EXISTS (SELECT 1 FROM players WHERE players.id = demons.verifier AND 
+             players.nationality = $1 AND NOT players.banned) ORDER BY position"#,
+            self.iso_country_code
+        )
+        .fetch_all(connection)
+        .await?
+        .into_iter()
+        .map(|row| {
+            MinimalDemon {
+                id: row.id,
+                position: row.position,
+                name: CiString(row.name),
+            }
+        })
+        .collect())
+    }
+}
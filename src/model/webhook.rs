@@ -0,0 +1,197 @@
+//! Module containing all code relating to webhooks
+//!
+//! A webhook is a URL that pointercrate notifies about changes to the demonlist. Each webhook
+//! subscribes to a set of [`WebhookEvent`]s, and only gets notified about those. Actual delivery of
+//! the messages happens asynchronously, see the [`delivery`] module.
+
+pub use self::{patch::PatchWebhook, post::PostWebhook};
+use derive_more::Display;
+use hmac::{Hmac, Mac, NewMac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::fmt::{Display, Formatter};
+
+mod delete;
+pub mod delivery;
+mod discord;
+mod get;
+mod patch;
+mod post;
+
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    /// A record was submitted. Messages about this event are only delivered once the submission's
+    /// video has been verified
+    RecordSubmitted,
+    RecordApproved,
+    RecordRejected,
+
+    /// A record was modified in any way that isn't covered by the other record events
+    RecordModified,
+    RecordDeleted,
+
+    DemonAdded,
+    DemonMoved,
+
+    /// A demon was modified in any way other than being moved
+    DemonModified,
+
+    PlayerBanned,
+    PlayerUnbanned,
+
+    /// A player was modified in any way other than being (un)banned, e.g. renamed, merged, or had
+    /// their nationality changed
+    PlayerModified,
+
+    SubmitterBanned,
+    SubmitterUnbanned,
+}
+
+impl WebhookEvent {
+    fn to_sql(&self) -> String {
+        match self {
+            WebhookEvent::RecordSubmitted => "RECORD_SUBMITTED",
+            WebhookEvent::RecordApproved => "RECORD_APPROVED",
+            WebhookEvent::RecordRejected => "RECORD_REJECTED",
+            WebhookEvent::RecordModified => "RECORD_MODIFIED",
+            WebhookEvent::RecordDeleted => "RECORD_DELETED",
+            WebhookEvent::DemonAdded => "DEMON_ADDED",
+            WebhookEvent::DemonMoved => "DEMON_MOVED",
+            WebhookEvent::DemonModified => "DEMON_MODIFIED",
+            WebhookEvent::PlayerBanned => "PLAYER_BANNED",
+            WebhookEvent::PlayerUnbanned => "PLAYER_UNBANNED",
+            WebhookEvent::PlayerModified => "PLAYER_MODIFIED",
+            WebhookEvent::SubmitterBanned => "SUBMITTER_BANNED",
+            WebhookEvent::SubmitterUnbanned => "SUBMITTER_UNBANNED",
+        }
+        .to_owned()
+    }
+
+    fn from_sql(sql: &str) -> Self {
+        match sql {
+            "RECORD_SUBMITTED" => WebhookEvent::RecordSubmitted,
+            "RECORD_APPROVED" => WebhookEvent::RecordApproved,
+            "RECORD_REJECTED" => WebhookEvent::RecordRejected,
+            "RECORD_MODIFIED" => WebhookEvent::RecordModified,
+            "RECORD_DELETED" => WebhookEvent::RecordDeleted,
+            "DEMON_ADDED" => WebhookEvent::DemonAdded,
+            "DEMON_MOVED" => WebhookEvent::DemonMoved,
+            "DEMON_MODIFIED" => WebhookEvent::DemonModified,
+            "PLAYER_BANNED" => WebhookEvent::PlayerBanned,
+            "PLAYER_UNBANNED" => WebhookEvent::PlayerUnbanned,
+            "PLAYER_MODIFIED" => WebhookEvent::PlayerModified,
+            "SUBMITTER_BANNED" => WebhookEvent::SubmitterBanned,
+            "SUBMITTER_UNBANNED" => WebhookEvent::SubmitterUnbanned,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl Display for WebhookEvent {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.to_sql().to_lowercase())
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookFormat {
+    /// Messages are formatted as discord embeds
+    Discord,
+
+    /// Messages are a signed JSON envelope containing the event, the time at which it happened,
+    /// the ID of the user that caused it and the serialized object it is about
+    Json,
+}
+
+impl WebhookFormat {
+    fn to_sql(&self) -> String {
+        match self {
+            WebhookFormat::Discord => "DISCORD",
+            WebhookFormat::Json => "JSON",
+        }
+        .to_owned()
+    }
+
+    fn from_sql(sql: &str) -> Self {
+        match sql {
+            "DISCORD" => WebhookFormat::Discord,
+            "JSON" => WebhookFormat::Json,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl Default for WebhookFormat {
+    fn default() -> Self {
+        WebhookFormat::Discord
+    }
+}
+
+#[derive(Debug, Serialize, Hash, Display)]
+#[display(fmt = "{} (ID: {})", name, id)]
+pub struct Webhook {
+    pub id: i32,
+    pub name: String,
+    pub url: String,
+    pub format: WebhookFormat,
+    pub events: Vec<WebhookEvent>,
+
+    /// The key with which messages in the [`WebhookFormat::Json`] format are signed. Only set via
+    /// [`Webhook::with_signing_key`], since it needs to be derived from the application secret
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signing_key: Option<String>,
+}
+
+impl Webhook {
+    /// Derives the key with which messages to this webhook are signed
+    ///
+    /// The key is derived from the application secret and the webhook's ID, meaning every webhook
+    /// has a different key, and all keys change if the application secret changes.
+    pub fn derive_signing_key(&self, secret: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_varkey(secret).expect("HMAC can take keys of any size");
+
+        mac.update(format!("pointercrate-webhook-{}", self.id).as_bytes());
+
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    /// Sets [`Webhook::signing_key`] if this webhook receives signed messages
+    pub fn with_signing_key(mut self, secret: &[u8]) -> Self {
+        if self.format == WebhookFormat::Json {
+            self.signing_key = Some(self.derive_signing_key(secret))
+        }
+
+        self
+    }
+
+    /// Computes the signature of the given payload, as sent in the `X-Pointercrate-Signature`
+    /// header
+    ///
+    /// The signature is the hex encoded HMAC-SHA256 of the payload, using the hex encoded signing
+    /// key as key.
+    pub fn sign(&self, secret: &[u8], payload: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_varkey(self.derive_signing_key(secret).as_bytes()).expect("HMAC can take keys of any size");
+
+        mac.update(payload.as_bytes());
+
+        hex::encode(mac.finalize().into_bytes())
+    }
+}
+
+/// Trait implemented by all objects webhook messages can be about
+///
+/// The [`Serialize`] implementation is used to generate the `data` field of messages in the
+/// [`WebhookFormat::Json`] format
+pub trait WebhookSubject: Serialize {
+    /// The ID of the record this message is about, if it is about a record
+    ///
+    /// Used to hold back messages about submissions until their video has been verified
+    fn record_id(&self) -> Option<i32> {
+        None
+    }
+
+    /// Generates the body of a discord webhook message notifying about the given event
+    fn discord_message(&self, event: WebhookEvent) -> serde_json::Value;
+}
@@ -0,0 +1,18 @@
+use crate::{model::webhook::Webhook, Result};
+use log::info;
+use sqlx::PgConnection;
+
+impl Webhook {
+    pub async fn delete(self, connection: &mut PgConnection) -> Result<()> {
+        info!("Deleting webhook {}", self);
+
+        // Subscriptions and queued deliveries get deleted due to the ON DELETE CASCADE on their webhook
+        // column
+
+        sqlx::query!("DELETE FROM webhooks WHERE id = $1", self.id)
+            .execute(connection)
+            .await?;
+
+        Ok(())
+    }
+}
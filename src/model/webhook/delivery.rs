@@ -0,0 +1,153 @@
+//! Module containing the webhook delivery queue
+//!
+//! Webhook messages are never executed directly. Instead, they are written into the
+//! `webhook_deliveries` table in the same transaction as the change they are about, and a
+//! background task (see [`deliver_webhooks`]) takes care of delivering them. Failed deliveries are
+//! retried with exponential backoff, which means that a temporary outage on discord's side does
+//! not cause notifications to be silently lost.
+
+pub use self::{
+    deliver::deliver_webhooks,
+    paginate::DeliveryPagination,
+    post::{discard_held, dispatch, release_held},
+};
+use crate::{model::webhook::WebhookEvent, Result};
+use chrono::NaiveDateTime;
+use derive_more::Display;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sqlx::PgConnection;
+use std::fmt::{Display, Formatter};
+
+mod deliver;
+mod get;
+mod paginate;
+mod patch;
+mod post;
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub enum DeliveryStatus {
+    /// The message is not yet eligible for delivery, for instance because it is about a submission
+    /// whose video still needs to be verified
+    Held,
+
+    /// The message will be delivered as soon as its next attempt is due
+    Pending,
+
+    /// The message was successfully delivered
+    Delivered,
+
+    /// Delivery was given up on, either because the maximal number of attempts was exhausted, or
+    /// because the webhook endpoint rejected the message
+    Failed,
+}
+
+impl DeliveryStatus {
+    fn to_sql(&self) -> String {
+        match self {
+            DeliveryStatus::Held => "HELD",
+            DeliveryStatus::Pending => "PENDING",
+            DeliveryStatus::Delivered => "DELIVERED",
+            DeliveryStatus::Failed => "FAILED",
+        }
+        .to_owned()
+    }
+
+    fn from_sql(sql: &str) -> Self {
+        match sql {
+            "HELD" => DeliveryStatus::Held,
+            "PENDING" => DeliveryStatus::Pending,
+            "DELIVERED" => DeliveryStatus::Delivered,
+            "FAILED" => DeliveryStatus::Failed,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl Display for DeliveryStatus {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            DeliveryStatus::Held => write!(f, "held"),
+            DeliveryStatus::Pending => write!(f, "pending"),
+            DeliveryStatus::Delivered => write!(f, "delivered"),
+            DeliveryStatus::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+impl Serialize for DeliveryStatus {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for DeliveryStatus {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let string = String::deserialize(deserializer)?.to_lowercase();
+
+        match &string[..] {
+            "held" => Ok(DeliveryStatus::Held),
+            "pending" => Ok(DeliveryStatus::Pending),
+            "delivered" => Ok(DeliveryStatus::Delivered),
+            "failed" => Ok(DeliveryStatus::Failed),
+            _ =>
+                Err(serde::de::Error::invalid_value(
+                    serde::de::Unexpected::Str(&string),
+                    &"'held', 'pending', 'delivered' or 'failed'",
+                )),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Hash, Display)]
+#[display(
+    fmt = "webhook delivery {} of event {} to webhook {:?} (status: {}, attempts: {})",
+    id,
+    event,
+    webhook,
+    status,
+    attempts
+)]
+pub struct WebhookDelivery {
+    pub id: i32,
+
+    /// The ID of the [`Webhook`] this message is addressed to. `None` for messages queued before
+    /// webhooks could be configured via the API, which haven't been reattached to a webhook yet
+    /// (see [`Webhook::adopt_legacy_webhook`])
+    pub webhook: Option<i32>,
+    pub event: WebhookEvent,
+
+    /// The ID of the record this message is about, if any
+    pub record: Option<i32>,
+
+    /// The JSON body that will be sent to the webhook
+    pub payload: String,
+    pub status: DeliveryStatus,
+    pub attempts: i32,
+    pub created_at: NaiveDateTime,
+    pub next_attempt: NaiveDateTime,
+    pub last_attempt: Option<NaiveDateTime>,
+
+    /// The HTTP status code the webhook endpoint responded with on the last attempt
+    pub last_response: Option<i16>,
+    pub last_error: Option<String>,
+}
+
+impl WebhookDelivery {
+    /// Gets the maximal and minimal delivery id currently in use
+    ///
+    /// The returned tuple is of the form (max, min)
+    pub async fn extremal_delivery_ids(connection: &mut PgConnection) -> Result<(i32, i32)> {
+        let row = sqlx::query!(
+            r#"SELECT COALESCE(MAX(id), 0) AS "max_id!: i32", COALESCE(MIN(id), 0) AS "min_id!: i32" FROM webhook_deliveries"#
+        )
+        .fetch_one(connection)
+        .await?;
+        Ok((row.max_id, row.min_id))
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{
+    model::webhook::{delivery::WebhookDelivery, Webhook, WebhookFormat},
+    state::PointercrateState,
+    Result,
+};
+use log::{debug, error, info};
+use reqwest::{header::RETRY_AFTER, Client, StatusCode};
+use sqlx::PgConnection;
+use std::{collections::HashMap, time::Duration};
+
+/// How often the delivery queue is checked for due messages
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The maximal number of messages delivered per check of the queue
+const BATCH_SIZE: i64 = 25;
+
+/// Background task delivering queued webhook messages
+///
+/// Should be spawned exactly once at startup. Never returns.
+pub async fn deliver_webhooks(state: PointercrateState) {
+    info!("Starting webhook delivery task");
+
+    let mut interval = actix_rt::time::interval(POLL_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        if let Err(error) = deliver_due(&state).await {
+            error!("INTERNAL SERVER ERROR: Failure to process webhook queue: {:?}", error);
+        }
+    }
+}
+
+async fn deliver_due(state: &PointercrateState) -> Result<()> {
+    let mut connection = state.connection().await?;
+
+    let due = WebhookDelivery::due(BATCH_SIZE, &mut connection).await?;
+
+    if due.is_empty() {
+        return Ok(())
+    }
+
+    let webhooks = Webhook::all(&mut connection)
+        .await?
+        .into_iter()
+        .map(|webhook| (webhook.id, webhook))
+        .collect::<HashMap<_, _>>();
+
+    for mut delivery in due {
+        // `due` skips legacy deliveries without a webhook, and deliveries are deleted together with
+        // their webhook. The webhook can still have been deleted since we loaded the batch though, in
+        // which case the delivery is gone as well
+        match delivery.webhook.and_then(|id| webhooks.get(&id)) {
+            Some(webhook) =>
+                delivery
+                    .attempt(&state.http_client, webhook, &state.secret, &mut connection)
+                    .await?,
+            None => debug!("Skipping delivery {} whose webhook no longer exists", delivery),
+        }
+    }
+
+    Ok(())
+}
+
+impl WebhookDelivery {
+    async fn attempt(&mut self, http_client: &Client, webhook: &Webhook, secret: &[u8], connection: &mut PgConnection) -> Result<()> {
+        debug!("Attempting to deliver {} to {}", self, webhook);
+
+        let mut request = http_client
+            .post(&webhook.url)
+            .header("Content-Type", "application/json")
+            .body(self.payload.clone());
+
+        if webhook.format == WebhookFormat::Json {
+            request = request.header("X-Pointercrate-Event", self.event.to_string()).header(
+                "X-Pointercrate-Signature",
+                format!("sha256={}", webhook.sign(secret, &self.payload)),
+            );
+        }
+
+        let response = match request.send().await {
+            Ok(response) => response,
+            Err(error) => return self.retry_later(None, error.to_string(), connection).await,
+        };
+
+        let status = response.status();
+
+        if status.is_success() {
+            self.mark_delivered(status.as_u16() as i16, connection).await
+        } else if status == StatusCode::TOO_MANY_REQUESTS {
+            // Discord tells us how long to wait both via the Retry-After header and the 'retry_after'
+            // field in the response body.
+            let header_delay = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse::<f64>().ok());
+
+            let delay = match header_delay {
+                Some(delay) => delay,
+                None =>
+                    response
+                        .text()
+                        .await
+                        .ok()
+                        .and_then(|body| serde_json::from_str::<serde_json::Value>(&body).ok())
+                        .and_then(|body| body["retry_after"].as_f64())
+                        .unwrap_or(60.0),
+            };
+
+            self.retry_after(delay, connection).await
+        } else if status.is_client_error() {
+            // The webhook endpoint does not like our message. Trying again won't change that
+            let body = response.text().await.unwrap_or_default();
+
+            self.mark_failed(Some(status.as_u16() as i16), body, connection).await
+        } else {
+            self.retry_later(
+                Some(status.as_u16() as i16),
+                format!("Server responded with {}", status),
+                connection,
+            )
+            .await
+        }
+    }
+}
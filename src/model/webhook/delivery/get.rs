@@ -0,0 +1,80 @@
+use crate::{
+    error::PointercrateError,
+    model::webhook::{
+        delivery::{DeliveryStatus, WebhookDelivery},
+        WebhookEvent,
+    },
+    Result,
+};
+use futures::StreamExt;
+use sqlx::{Error, PgConnection};
+
+impl WebhookDelivery {
+    pub async fn by_id(id: i32, connection: &mut PgConnection) -> Result<WebhookDelivery> {
+        let result = sqlx::query!(
+            r#"SELECT webhook, event::text AS "event!: String", record, payload, status_::text AS "status_!: String", attempts, created_at,
+             next_attempt, last_attempt, last_response, last_error FROM webhook_deliveries WHERE id = $1"#,
+            id
+        )
+        .fetch_one(connection)
+        .await;
+
+        match result {
+            Ok(row) =>
+                Ok(WebhookDelivery {
+                    id,
+                    webhook: row.webhook,
+                    event: WebhookEvent::from_sql(&row.event),
+                    record: row.record,
+                    payload: row.payload,
+                    status: DeliveryStatus::from_sql(&row.status_),
+                    attempts: row.attempts,
+                    created_at: row.created_at,
+                    next_attempt: row.next_attempt,
+                    last_attempt: row.last_attempt,
+                    last_response: row.last_response,
+                    last_error: row.last_error,
+                }),
+            Err(Error::RowNotFound) =>
+                Err(PointercrateError::ModelNotFound {
+                    model: "WebhookDelivery",
+                    identified_by: id.to_string(),
+                }),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Retrieves up to `limit` pending deliveries whose next attempt is due, oldest first
+    pub async fn due(limit: i64, connection: &mut PgConnection) -> Result<Vec<WebhookDelivery>> {
+        let mut stream = sqlx::query!(
+            r#"SELECT id, webhook, event::text AS "event!: String", record, payload, status_::text AS "status_!: String", attempts,
+             created_at, next_attempt, last_attempt, last_response, last_error FROM webhook_deliveries WHERE status_ = 'PENDING' AND
+             next_attempt <= (NOW() AT TIME ZONE 'utc') AND webhook IS NOT NULL ORDER BY next_attempt LIMIT $1"#,
+            limit
+        )
+        .fetch(connection);
+
+        let mut deliveries = Vec::new();
+
+        while let Some(row) = stream.next().await {
+            let row = row?;
+
+            deliveries.push(WebhookDelivery {
+                id: row.id,
+                webhook: row.webhook,
+                event: WebhookEvent::from_sql(&row.event),
+                record: row.record,
+                payload: row.payload,
+                status: DeliveryStatus::from_sql(&row.status_),
+                attempts: row.attempts,
+                created_at: row.created_at,
+                next_attempt: row.next_attempt,
+                last_attempt: row.last_attempt,
+                last_response: row.last_response,
+                last_error: row.last_error,
+            })
+        }
+
+        Ok(deliveries)
+    }
+}
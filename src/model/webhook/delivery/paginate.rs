@@ -0,0 +1,99 @@
+use crate::{
+    error::PointercrateError,
+    model::webhook::{
+        delivery::{DeliveryStatus, WebhookDelivery},
+        WebhookEvent,
+    },
+    util::non_nullable,
+    Result,
+};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgConnection, Row};
+
+#[derive(Deserialize, Debug, Clone, Serialize)]
+pub struct DeliveryPagination {
+    #[serde(rename = "before", default, deserialize_with = "non_nullable")]
+    pub before_id: Option<i32>,
+
+    #[serde(rename = "after", default, deserialize_with = "non_nullable")]
+    pub after_id: Option<i32>,
+
+    #[serde(default, deserialize_with = "non_nullable")]
+    pub limit: Option<u8>,
+
+    #[serde(default, deserialize_with = "non_nullable")]
+    status: Option<DeliveryStatus>,
+
+    #[serde(default, deserialize_with = "non_nullable")]
+    record: Option<i32>,
+
+    #[serde(default, deserialize_with = "non_nullable")]
+    webhook: Option<i32>,
+
+    #[serde(default, deserialize_with = "non_nullable")]
+    event: Option<WebhookEvent>,
+}
+
+impl DeliveryPagination {
+    pub async fn page(&self, connection: &mut PgConnection) -> Result<Vec<WebhookDelivery>> {
+        if let Some(limit) = self.limit {
+            if limit < 1 || limit > 100 {
+                return Err(PointercrateError::InvalidPaginationLimit)
+            }
+        }
+
+        if let (Some(after), Some(before)) = (self.before_id, self.after_id) {
+            if after < before {
+                return Err(PointercrateError::AfterSmallerBefore)
+            }
+        }
+
+        let order = if self.before_id.is_some() && self.after_id.is_none() {
+            "DESC"
+        } else {
+            "ASC"
+        };
+
+        let query = format!(
+            "SELECT id, webhook, event::text AS event, record, payload, status_::text AS status, attempts, created_at, next_attempt, \
+             last_attempt, last_response, last_error FROM webhook_deliveries WHERE (id < $1 OR $1 IS NULL) AND (id > $2 OR $2 IS NULL) \
+             AND (status_ = cast($3::text as webhook_delivery_status) OR $3 IS NULL) AND (record = $4 OR $4 IS NULL) AND (webhook = $5 OR \
+             $5 IS NULL) AND (event = cast($6::text as webhook_event) OR $6 IS NULL) ORDER BY id {} LIMIT $7",
+            order
+        );
+
+        let mut stream = sqlx::query(&query)
+            .bind(self.before_id)
+            .bind(self.after_id)
+            .bind(self.status.map(|s| s.to_sql()))
+            .bind(self.record)
+            .bind(self.webhook)
+            .bind(self.event.map(|e| e.to_sql()))
+            .bind(self.limit.unwrap_or(50) as i32 + 1)
+            .fetch(connection);
+
+        let mut deliveries = Vec::new();
+
+        while let Some(row) = stream.next().await {
+            let row = row?;
+
+            deliveries.push(WebhookDelivery {
+                id: row.get("id"),
+                webhook: row.get("webhook"),
+                event: WebhookEvent::from_sql(&row.get::<String, _>("event")),
+                record: row.get("record"),
+                payload: row.get("payload"),
+                status: DeliveryStatus::from_sql(&row.get::<String, _>("status")),
+                attempts: row.get("attempts"),
+                created_at: row.get("created_at"),
+                next_attempt: row.get("next_attempt"),
+                last_attempt: row.get("last_attempt"),
+                last_response: row.get("last_response"),
+                last_error: row.get("last_error"),
+            })
+        }
+
+        Ok(deliveries)
+    }
+}
@@ -0,0 +1,92 @@
+use crate::{
+    model::webhook::delivery::{DeliveryStatus, WebhookDelivery},
+    Result,
+};
+use log::{info, warn};
+use sqlx::PgConnection;
+
+/// The maximal number of delivery attempts before a message is marked as failed
+const MAX_ATTEMPTS: i32 = 10;
+
+/// The delay (in seconds) before the first retry. Doubled with each further failed attempt
+const BASE_RETRY_DELAY: f64 = 30.0;
+
+/// The maximal delay (in seconds) between two delivery attempts
+const MAX_RETRY_DELAY: f64 = 6.0 * 60.0 * 60.0;
+
+impl WebhookDelivery {
+    pub async fn mark_delivered(&mut self, response: i16, connection: &mut PgConnection) -> Result<()> {
+        info!("Successfully delivered {}", self);
+
+        self.attempts += 1;
+        self.status = DeliveryStatus::Delivered;
+        self.last_response = Some(response);
+        self.last_error = None;
+
+        self.record_attempt(0.0, connection).await
+    }
+
+    /// Gives up on delivering this message
+    pub async fn mark_failed(&mut self, response: Option<i16>, error: String, connection: &mut PgConnection) -> Result<()> {
+        warn!("Giving up on {}: {}", self, error);
+
+        self.attempts += 1;
+        self.status = DeliveryStatus::Failed;
+        self.last_response = response;
+        self.last_error = Some(error);
+
+        self.record_attempt(0.0, connection).await
+    }
+
+    /// Schedules another attempt after a failed one, backing off exponentially
+    ///
+    /// Marks the message as failed if the maximal number of attempts has been exhausted
+    pub async fn retry_later(&mut self, response: Option<i16>, error: String, connection: &mut PgConnection) -> Result<()> {
+        if self.attempts + 1 >= MAX_ATTEMPTS {
+            return self.mark_failed(response, error, connection).await
+        }
+
+        let delay = (BASE_RETRY_DELAY * 2f64.powi(self.attempts)).min(MAX_RETRY_DELAY);
+
+        warn!("Delivery attempt for {} failed: {}. Retrying in {} seconds", self, error, delay);
+
+        self.attempts += 1;
+        self.last_response = response;
+        self.last_error = Some(error);
+
+        self.record_attempt(delay, connection).await
+    }
+
+    /// Schedules another attempt after we got ratelimited by the webhook endpoint
+    ///
+    /// Does not count towards the number of attempts, since the message itself is fine.
+    pub async fn retry_after(&mut self, delay: f64, connection: &mut PgConnection) -> Result<()> {
+        info!("Got ratelimited while delivering {}, retrying in {} seconds", self, delay);
+
+        self.last_response = Some(429);
+        self.last_error = Some("ratelimited".to_owned());
+
+        self.record_attempt(delay, connection).await
+    }
+
+    async fn record_attempt(&mut self, delay: f64, connection: &mut PgConnection) -> Result<()> {
+        let row = sqlx::query!(
+            "UPDATE webhook_deliveries SET status_ = cast($2::text as webhook_delivery_status), attempts = $3, last_attempt = (NOW() AT \
+             TIME ZONE 'utc'), next_attempt = (NOW() AT TIME ZONE 'utc') + make_interval(secs => $4), last_response = $5, last_error = $6 \
+             WHERE id = $1 RETURNING last_attempt AS \"last_attempt!\", next_attempt",
+            self.id,
+            self.status.to_sql(),
+            self.attempts,
+            delay,
+            self.last_response,
+            self.last_error
+        )
+        .fetch_one(connection)
+        .await?;
+
+        self.last_attempt = Some(row.last_attempt);
+        self.next_attempt = row.next_attempt;
+
+        Ok(())
+    }
+}
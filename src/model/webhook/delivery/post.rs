@@ -0,0 +1,102 @@
+use crate::{
+    model::webhook::{delivery::DeliveryStatus, Webhook, WebhookEvent, WebhookFormat, WebhookSubject},
+    Result,
+};
+use chrono::{DateTime, Utc};
+use log::{debug, info};
+use serde::Serialize;
+use serde_json::json;
+use sqlx::PgConnection;
+
+/// The body of messages in the [`WebhookFormat::Json`] format
+#[derive(Serialize)]
+struct Envelope<'a, S> {
+    event: WebhookEvent,
+    timestamp: DateTime<Utc>,
+
+    /// The ID of the user that caused the event, if it wasn't caused by an anonymous request
+    actor: Option<i32>,
+    data: &'a S,
+}
+
+/// Queues messages about the given event for delivery to all webhooks subscribed to it
+///
+/// This should be called on the same connection (and thus in the same transaction) that is used
+/// to make the change the message is about, so that either both are persisted, or neither is.
+///
+/// If `held` is `true`, the messages are not eligible for delivery until they are explicitly
+/// released via [`release_held`]. Only messages about records can be held.
+pub async fn dispatch<S: WebhookSubject>(event: WebhookEvent, subject: &S, held: bool, connection: &mut PgConnection) -> Result<()> {
+    let record = subject.record_id();
+    let status = if held && record.is_some() {
+        DeliveryStatus::Held
+    } else {
+        DeliveryStatus::Pending
+    };
+
+    // The user to which all changes made via this connection are attributed in the audit logs
+    let actor = sqlx::query!("SELECT id FROM active_user LIMIT 1")
+        .fetch_optional(&mut *connection)
+        .await?
+        .map(|row| row.id)
+        .filter(|&id| id != 0);
+
+    let envelope = Envelope {
+        event,
+        timestamp: Utc::now(),
+        actor,
+        data: subject,
+    };
+
+    for webhook in Webhook::subscribed_to(event, &mut *connection).await? {
+        debug!(
+            "Queueing message about event {} for webhook {} (status: {})",
+            event, webhook, status
+        );
+
+        let payload = match webhook.format {
+            WebhookFormat::Discord => subject.discord_message(event),
+            WebhookFormat::Json => json!(envelope),
+        };
+
+        sqlx::query!(
+            "INSERT INTO webhook_deliveries (webhook, event, record, payload, status_) VALUES ($1, cast($2::text as webhook_event), $3, \
+             $4, cast($5::text as webhook_delivery_status))",
+            webhook.id,
+            event.to_sql(),
+            record,
+            payload.to_string(),
+            status.to_sql()
+        )
+        .execute(&mut *connection)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Makes all held messages about the given record eligible for delivery
+pub async fn release_held(record: i32, connection: &mut PgConnection) -> Result<()> {
+    info!("Releasing held webhook messages about record {}", record);
+
+    sqlx::query!(
+        "UPDATE webhook_deliveries SET status_ = 'PENDING', next_attempt = (NOW() AT TIME ZONE 'utc') WHERE record = $1 AND status_ = \
+         'HELD'",
+        record
+    )
+    .execute(connection)
+    .await?;
+
+    Ok(())
+}
+
+/// Drops all held messages about the given record without ever delivering them
+pub async fn discard_held(record: i32, connection: &mut PgConnection) -> Result<()> {
+    info!("Discarding held webhook messages about record {}", record);
+
+    sqlx::query!("DELETE FROM webhook_deliveries WHERE record = $1 AND status_ = 'HELD'", record)
+        .execute(connection)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,176 @@
+//! Module containing the discord message formats for all [`WebhookSubject`]s
+
+use crate::model::{
+    demonlist::{demon::FullDemon, player::FullPlayer, record::FullRecord, submitter::Submitter},
+    webhook::{WebhookEvent, WebhookSubject},
+};
+use serde_json::json;
+
+impl WebhookSubject for FullRecord {
+    fn record_id(&self) -> Option<i32> {
+        Some(self.id)
+    }
+
+    fn discord_message(&self, event: WebhookEvent) -> serde_json::Value {
+        if event != WebhookEvent::RecordSubmitted {
+            let content = match event {
+                WebhookEvent::RecordApproved => format!("**Record approved! ID: {}**", self.id),
+                WebhookEvent::RecordRejected => format!("**Record rejected! ID: {}**", self.id),
+                WebhookEvent::RecordDeleted => format!("**Record deleted! ID: {}**", self.id),
+                _ => format!("**Record edited! ID: {}**", self.id),
+            };
+
+            let mut payload = json!({
+                "content": content,
+                "embeds": [
+                    {
+                        "type": "rich",
+                        "title": format!("{}% on {}", self.progress, self.demon.name),
+                        "description": format!("{}'s record's status has been set to `{}`!", self.player.name, self.status),
+                        "author": {
+                            "name": format!("Owner: {} (ID: {})", self.player.name, self.player.id),
+                            "url": self.video
+                        },
+                    }
+                ]
+            });
+
+            if let (WebhookEvent::RecordRejected, Some(reason)) = (event, &self.rejection_reason) {
+                payload["embeds"][0]["fields"] = json! {
+                    [{
+                        "name": "Reason:",
+                        "value": reason.name
+                    }]
+                };
+            }
+
+            return payload
+        }
+
+        let mut payload = json!({
+            "content": format!("**New record submitted! ID: {}**", self.id),
+            "embeds": [
+                {
+                    "type": "rich",
+                    "title": format!("{}% on {}", self.progress, self.demon.name),
+                    "description": format!("{} just got {}% on {}! Go add their record!", self.player.name, self.progress, self.demon.name),
+                    "footer": {
+                        "text": format!("This record has been submitted by submitter #{}", self.submitter.map(|s|s.id).unwrap_or(1))
+                    },
+                    "author": {
+                        "name": format!("{} (ID: {})", self.player.name, self.player.id),
+                        "url": self.video
+                    },
+                    "thumbnail": {
+                        "url": "https://cdn.discordapp.com/emojis/561867333476286464.png?size=1024"
+                    },
+                }
+            ]
+        });
+
+        if let Some(ref video) = self.video {
+            payload["embeds"][0]["fields"] = json! {
+                [{
+                    "name": "Video Proof:",
+                    "value": video
+                }]
+            };
+        }
+
+        payload
+    }
+}
+
+impl WebhookSubject for FullDemon {
+    fn discord_message(&self, event: WebhookEvent) -> serde_json::Value {
+        let content = match event {
+            WebhookEvent::DemonAdded => format!("**Demon added! ID: {}**", self.demon.base.id),
+            WebhookEvent::DemonMoved => format!("**Demon moved! ID: {}**", self.demon.base.id),
+            _ => format!("**Demon edited! ID: {}**", self.demon.base.id),
+        };
+
+        json!({
+            "content": content,
+            "embeds": [
+                {
+                    "type": "rich",
+                    "title": format!("{} (#{})", self.demon.base.name, self.demon.base.position),
+                    "description": format!(
+                        "{} is now at position {}, requiring {}% to be put on the list!",
+                        self.demon.base.name, self.demon.base.position, self.demon.requirement
+                    ),
+                    "url": self.demon.video,
+                    "fields": [
+                        {
+                            "name": "Verifier:",
+                            "value": self.demon.verifier.name.to_string(),
+                            "inline": true
+                        },
+                        {
+                            "name": "Publisher:",
+                            "value": self.demon.publisher.name.to_string(),
+                            "inline": true
+                        }
+                    ]
+                }
+            ]
+        })
+    }
+}
+
+impl WebhookSubject for FullPlayer {
+    fn discord_message(&self, event: WebhookEvent) -> serde_json::Value {
+        let player = &self.player.base;
+
+        let (content, description) = match event {
+            WebhookEvent::PlayerBanned =>
+                (
+                    format!("**Player banned! ID: {}**", player.id),
+                    format!("{} has been banned from the list!", player.name),
+                ),
+            WebhookEvent::PlayerUnbanned =>
+                (
+                    format!("**Player unbanned! ID: {}**", player.id),
+                    format!("{} has been unbanned from the list!", player.name),
+                ),
+            _ =>
+                (
+                    format!("**Player edited! ID: {}**", player.id),
+                    format!("{}'s player data has been edited!", player.name),
+                ),
+        };
+
+        let nationality = match self.player.nationality {
+            Some(ref nationality) => nationality.nation.to_string(),
+            None => "None".to_owned(),
+        };
+
+        json!({
+            "content": content,
+            "embeds": [
+                {
+                    "type": "rich",
+                    "title": format!("{} (ID: {})", player.name, player.id),
+                    "description": description,
+                    "fields": [
+                        {
+                            "name": "Nationality:",
+                            "value": nationality,
+                        }
+                    ]
+                }
+            ]
+        })
+    }
+}
+
+impl WebhookSubject for Submitter {
+    fn discord_message(&self, event: WebhookEvent) -> serde_json::Value {
+        let content = match event {
+            WebhookEvent::SubmitterBanned => format!("**Submitter #{} has been banned from submitting records!**", self.id),
+            _ => format!("**Submitter #{} has been unbanned!**", self.id),
+        };
+
+        json!({ "content": content })
+    }
+}
@@ -0,0 +1,113 @@
+use crate::{
+    error::PointercrateError,
+    model::webhook::{Webhook, WebhookEvent, WebhookFormat},
+    Result,
+};
+use futures::StreamExt;
+use sqlx::{Error, PgConnection};
+
+impl Webhook {
+    pub async fn by_id(id: i32, connection: &mut PgConnection) -> Result<Webhook> {
+        let result = sqlx::query!(
+            r#"SELECT name, url, format::text AS "format!: String" FROM webhooks WHERE id = $1"#,
+            id
+        )
+        .fetch_one(&mut *connection)
+        .await;
+
+        match result {
+            Ok(row) =>
+                Ok(Webhook {
+                    id,
+                    name: row.name,
+                    url: row.url,
+                    format: WebhookFormat::from_sql(&row.format),
+                    events: events_of(id, connection).await?,
+                    signing_key: None,
+                }),
+            Err(Error::RowNotFound) =>
+                Err(PointercrateError::ModelNotFound {
+                    model: "Webhook",
+                    identified_by: id.to_string(),
+                }),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    pub async fn all(connection: &mut PgConnection) -> Result<Vec<Webhook>> {
+        let mut webhooks = Vec::new();
+
+        {
+            let mut stream = sqlx::query!(r#"SELECT id, name, url, format::text AS "format!: String" FROM webhooks ORDER BY id"#)
+                .fetch(&mut *connection);
+
+            while let Some(row) = stream.next().await {
+                let row = row?;
+
+                webhooks.push(Webhook {
+                    id: row.id,
+                    name: row.name,
+                    url: row.url,
+                    format: WebhookFormat::from_sql(&row.format),
+                    events: Vec::new(),
+                    signing_key: None,
+                })
+            }
+        }
+
+        for webhook in &mut webhooks {
+            webhook.events = events_of(webhook.id, &mut *connection).await?;
+        }
+
+        Ok(webhooks)
+    }
+
+    /// Gets all webhooks that are subscribed to the given event
+    pub async fn subscribed_to(event: WebhookEvent, connection: &mut PgConnection) -> Result<Vec<Webhook>> {
+        let mut webhooks = Vec::new();
+
+        {
+            let mut stream = sqlx::query!(
+                r#"SELECT id, name, url, format::text AS "format!: String" FROM webhooks INNER JOIN webhook_subscriptions ON webhooks.id =
+                 webhook_subscriptions.webhook WHERE event = cast($1::text as webhook_event) ORDER BY id"#,
+                event.to_sql()
+            )
+            .fetch(&mut *connection);
+
+            while let Some(row) = stream.next().await {
+                let row = row?;
+
+                webhooks.push(Webhook {
+                    id: row.id,
+                    name: row.name,
+                    url: row.url,
+                    format: WebhookFormat::from_sql(&row.format),
+                    events: Vec::new(),
+                    signing_key: None,
+                })
+            }
+        }
+
+        for webhook in &mut webhooks {
+            webhook.events = events_of(webhook.id, &mut *connection).await?;
+        }
+
+        Ok(webhooks)
+    }
+}
+
+async fn events_of(webhook: i32, connection: &mut PgConnection) -> Result<Vec<WebhookEvent>> {
+    let mut stream = sqlx::query!(
+        r#"SELECT event::text AS "event!: String" FROM webhook_subscriptions WHERE webhook = $1 ORDER BY event"#,
+        webhook
+    )
+    .fetch(connection);
+
+    let mut events = Vec::new();
+
+    while let Some(row) = stream.next().await {
+        events.push(WebhookEvent::from_sql(&row?.event))
+    }
+
+    Ok(events)
+}
@@ -0,0 +1,108 @@
+use crate::{
+    model::webhook::{post::validate_url, Webhook, WebhookEvent, WebhookFormat},
+    util::non_nullable,
+    Result,
+};
+use log::info;
+use serde::Deserialize;
+use sqlx::PgConnection;
+
+#[derive(Deserialize, Debug)]
+pub struct PatchWebhook {
+    #[serde(default, deserialize_with = "non_nullable")]
+    name: Option<String>,
+
+    #[serde(default, deserialize_with = "non_nullable")]
+    url: Option<String>,
+
+    #[serde(default, deserialize_with = "non_nullable")]
+    format: Option<WebhookFormat>,
+
+    #[serde(default, deserialize_with = "non_nullable")]
+    events: Option<Vec<WebhookEvent>>,
+}
+
+impl Webhook {
+    /// Must run inside a transaction!
+    pub async fn apply_patch(mut self, patch: PatchWebhook, connection: &mut PgConnection) -> Result<Self> {
+        info!("Patching webhook {} with {:?}", self, patch);
+
+        if let Some(name) = patch.name {
+            self.set_name(name, connection).await?;
+        }
+
+        if let Some(url) = patch.url {
+            self.set_url(url, connection).await?;
+        }
+
+        if let Some(format) = patch.format {
+            self.set_format(format, connection).await?;
+        }
+
+        if let Some(events) = patch.events {
+            self.set_events(events, connection).await?;
+        }
+
+        Ok(self)
+    }
+
+    pub async fn set_name(&mut self, name: String, connection: &mut PgConnection) -> Result<()> {
+        sqlx::query!("UPDATE webhooks SET name = $1 WHERE id = $2", name, self.id)
+            .execute(connection)
+            .await?;
+
+        self.name = name;
+
+        Ok(())
+    }
+
+    pub async fn set_url(&mut self, url: String, connection: &mut PgConnection) -> Result<()> {
+        let url = validate_url(&url)?;
+
+        sqlx::query!("UPDATE webhooks SET url = $1 WHERE id = $2", url, self.id)
+            .execute(connection)
+            .await?;
+
+        self.url = url;
+
+        Ok(())
+    }
+
+    pub async fn set_format(&mut self, format: WebhookFormat, connection: &mut PgConnection) -> Result<()> {
+        sqlx::query!(
+            "UPDATE webhooks SET format = cast($1::text as webhook_format) WHERE id = $2",
+            format.to_sql(),
+            self.id
+        )
+        .execute(connection)
+        .await?;
+
+        self.format = format;
+
+        Ok(())
+    }
+
+    /// Replaces the set of events this webhook is subscribed to
+    pub async fn set_events(&mut self, mut events: Vec<WebhookEvent>, connection: &mut PgConnection) -> Result<()> {
+        sqlx::query!("DELETE FROM webhook_subscriptions WHERE webhook = $1", self.id)
+            .execute(&mut *connection)
+            .await?;
+
+        events.sort();
+        events.dedup();
+
+        for event in &events {
+            sqlx::query!(
+                "INSERT INTO webhook_subscriptions (webhook, event) VALUES ($1, cast($2::text as webhook_event))",
+                self.id,
+                event.to_sql()
+            )
+            .execute(&mut *connection)
+            .await?;
+        }
+
+        self.events = events;
+
+        Ok(())
+    }
+}
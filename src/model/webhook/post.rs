@@ -0,0 +1,113 @@
+use crate::{
+    error::PointercrateError,
+    model::webhook::{Webhook, WebhookEvent, WebhookFormat},
+    Result,
+};
+use derive_more::Display;
+use log::info;
+use serde::Deserialize;
+use sqlx::{Done, PgConnection};
+use url::Url;
+
+#[derive(Deserialize, Debug, Display)]
+#[display(fmt = "{} (format: {:?}, events: {:?})", name, format, events)]
+pub struct PostWebhook {
+    pub name: String,
+    pub url: String,
+
+    #[serde(default)]
+    pub format: WebhookFormat,
+
+    #[serde(default)]
+    pub events: Vec<WebhookEvent>,
+}
+
+impl Webhook {
+    pub async fn create_from(data: PostWebhook, connection: &mut PgConnection) -> Result<Webhook> {
+        info!("Creating new webhook from {}", data);
+
+        let url = validate_url(&data.url)?;
+
+        let id = sqlx::query!(
+            "INSERT INTO webhooks (name, url, format) VALUES ($1, $2, cast($3::text as webhook_format)) RETURNING id",
+            data.name,
+            url,
+            data.format.to_sql()
+        )
+        .fetch_one(&mut *connection)
+        .await?
+        .id;
+
+        let mut webhook = Webhook {
+            id,
+            name: data.name,
+            url,
+            format: data.format,
+            events: Vec::new(),
+            signing_key: None,
+        };
+
+        webhook.set_events(data.events, connection).await?;
+
+        Ok(webhook)
+    }
+
+    /// Recreates the webhook that used to be configured via the `DISCORD_WEBHOOK` environment
+    /// variable, if no webhooks have been set up via the API yet
+    ///
+    /// The new webhook is subscribed to the events the old one was notified about (new submissions
+    /// and edits of records), and all messages that were queued for the old webhook are reattached
+    /// to it. Returns `None` if webhooks already exist.
+    pub async fn adopt_legacy_webhook(url: &str, connection: &mut PgConnection) -> Result<Option<Webhook>> {
+        let count = sqlx::query!(r#"SELECT COUNT(*) AS "count!" FROM webhooks"#)
+            .fetch_one(&mut *connection)
+            .await?
+            .count;
+
+        if count != 0 {
+            return Ok(None)
+        }
+
+        let webhook = Webhook::create_from(
+            PostWebhook {
+                name: "Discord".to_owned(),
+                url: url.to_owned(),
+                format: WebhookFormat::Discord,
+                events: vec![
+                    WebhookEvent::RecordSubmitted,
+                    WebhookEvent::RecordApproved,
+                    WebhookEvent::RecordRejected,
+                    WebhookEvent::RecordModified,
+                ],
+            },
+            &mut *connection,
+        )
+        .await?;
+
+        let reattached = sqlx::query!("UPDATE webhook_deliveries SET webhook = $1 WHERE webhook IS NULL", webhook.id)
+            .execute(connection)
+            .await?;
+
+        info!("Reattached {} queued messages to {}", reattached.rows_affected(), webhook);
+
+        Ok(Some(webhook))
+    }
+}
+
+pub(super) fn validate_url(url: &str) -> Result<String> {
+    let url = Url::parse(url).map_err(|_| {
+        PointercrateError::BadRequest {
+            message: "Malformed webhook URL".to_owned(),
+        }
+    })?;
+
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err(PointercrateError::InvalidUrlScheme)
+    }
+
+    if !url.username().is_empty() || url.password().is_some() {
+        return Err(PointercrateError::UrlAuthenticated)
+    }
+
+    Ok(url.to_string())
+}
@@ -1,6 +1,12 @@
-use crate::{config, documentation, gd::PgCache, model::user::AuthenticatedUser, ratelimit::Ratelimits, Result};
+use crate::{
+    config, documentation,
+    gd::PgCache,
+    model::{user::AuthenticatedUser, webhook::Webhook},
+    ratelimit::Ratelimits,
+    Result,
+};
 use chrono::Duration;
-use log::{info, trace};
+use log::{error, info, trace, warn};
 use reqwest::Client;
 use sqlx::{pool::PoolConnection, postgres::PgPoolOptions, PgConnection, Pool, Postgres, Transaction};
 use std::{collections::HashMap, sync::Arc};
@@ -18,7 +24,6 @@ pub struct PointercrateState {
     pub ratelimits: Ratelimits,
 
     pub http_client: Client,
-    pub webhook_url: Option<Arc<String>>,
     pub gd_integration: PgCache,
 }
 
@@ -44,6 +49,10 @@ impl PointercrateState {
             .await
             .expect("Failed to connect to pointercrate database");
 
+        if let Ok(url) = std::env::var("DISCORD_WEBHOOK") {
+            adopt_legacy_webhook(&url, &connection_pool).await;
+        }
+
         PointercrateState {
             gd_integration: PgCache::new(connection_pool.clone(), Duration::minutes(30)),
             documentation_toc,
@@ -54,7 +63,6 @@ impl PointercrateState {
             secret: Arc::new(config::secret()),
             ratelimits: Ratelimits::initialize(),
             http_client: Client::builder().build().expect("Failed to create reqwest client"),
-            webhook_url: std::env::var("DISCORD_WEBHOOK").ok().map(Arc::new),
         }
     }
 
@@ -96,6 +104,38 @@ impl PointercrateState {
     }
 }
 
+/// Turns the webhook configured via the `DISCORD_WEBHOOK` environment variable into a regular
+/// webhook (see [`Webhook::adopt_legacy_webhook`])
+async fn adopt_legacy_webhook(url: &str, connection_pool: &Pool<Postgres>) {
+    let result: Result<Option<Webhook>> = async {
+        let mut connection = connection_pool.begin().await?;
+
+        audit_connection(&mut *connection, 0).await?;
+
+        let webhook = Webhook::adopt_legacy_webhook(url, &mut connection).await?;
+
+        connection.commit().await?;
+
+        Ok(webhook)
+    }
+    .await;
+
+    match result {
+        Ok(Some(webhook)) =>
+            info!(
+                "Created {} from the DISCORD_WEBHOOK environment variable. Manage it via /api/v1/webhooks/ from now on",
+                webhook
+            ),
+        Ok(None) =>
+            warn!("Ignoring the DISCORD_WEBHOOK environment variable, since webhooks have already been configured via /api/v1/webhooks/"),
+        Err(err) =>
+            error!(
+                "INTERNAL SERVER ERROR: Failed to create webhook from the DISCORD_WEBHOOK environment variable: {:?}",
+                err
+            ),
+    }
+}
+
 pub async fn audit_connection(connection: &mut PgConnection, user_id: i32) -> Result<()> {
     trace!(
         "Creating connection of which usage will be attributed to user {} in audit logs",
@@ -208,6 +208,13 @@ where
     }
 }
 
+/// Computes the etag of the given object, as sent in the `ETag` header
+pub fn etag<H: Hash>(obj: &H) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    obj.hash(&mut hasher);
+    hasher.finish()
+}
+
 pub trait HttpResponseBuilderExt {
     fn etag<H: Hash>(&mut self, obj: &H) -> &mut Self;
     fn json_with_etag<H: Serialize + Hash>(&mut self, obj: &H) -> HttpResponse;
@@ -215,9 +222,7 @@ pub trait HttpResponseBuilderExt {
 
 impl HttpResponseBuilderExt for HttpResponseBuilder {
     fn etag<H: Hash>(&mut self, obj: &H) -> &mut Self {
-        let mut hasher = DefaultHasher::new();
-        obj.hash(&mut hasher);
-        self.header("ETag", hasher.finish().to_string())
+        self.header("ETag", etag(obj).to_string())
     }
 
     fn json_with_etag<H: Serialize + Hash>(&mut self, obj: &H) -> HttpResponse {
@@ -1,4 +1,6 @@
 use crate::{error::PointercrateError, Result};
+use log::debug;
+use reqwest::Client;
 use url::Url;
 
 const SCHEMES: [&str; 2] = ["http", "https"];
@@ -9,7 +11,8 @@ const YOUTUBE_FORMAT: &str = "https://www.youtube.com/watch?v={video_id}' or \
 const TWITCH_FORMAT: &str = "https://www.twitch.tv/videos/{video_id}' or \
                              'https://twitch.tv/videos/{video_id}' or\
                              'https://www.twitch.tv/{channel_name}/v/{video_id}' or\
-                             'https://twitch.tv/{channel_name}/v/{video_id}";
+                             'https://twitch.tv/{channel_name}/v/{video_id}' or\
+                             'https://www.twitch.tv/{channel_name}/video/{video_id}";
 const EVERYPLAY_FORMAT: &str = "https://everyplay.com/videos/{video_id}' or'https://www.everyplay.com/videos/{video_id}";
 const VIMEO_FORMAT: &str = "https://vimeo.com/{video_id}' or'https://www.vimeo.com/{video_id}";
 const BILIBILI_FORMAT: &str = "'https://www.bilibili.com/video/{video_id}' or'https://bilibili.com/video/{video_id}";
@@ -100,11 +103,16 @@ pub fn validate(url: &str) -> Result<String> {
                 } else {
                     Err(PointercrateError::InvalidUrlFormat { expected: YOUTUBE_FORMAT })
                 },
-            "www.twitch.tv" | "twitch.tv" =>
+            "www.twitch.tv" | "twitch.tv" | "m.twitch.tv" =>
                 if let Some(path_segments) = url.path_segments() {
-                    match &path_segments.collect::<Vec<_>>()[..] {
+                    // Twitch links are often shared with a trailing slash
+                    match &path_segments.filter(|segment| !segment.is_empty()).collect::<Vec<_>>()[..] {
                         ["videos", video_id] => Ok(format!("https://www.twitch.tv/videos/{}", video_id)),
                         [_, "v", video_id] => Ok(format!("https://www.twitch.tv/videos/{}", video_id)),
+                        // Old style links to past broadcasts. Twitch video IDs are numeric, which keeps other channel
+                        // pages from matching here
+                        [_, "video", video_id] if video_id.chars().all(|c| c.is_ascii_digit()) =>
+                            Ok(format!("https://www.twitch.tv/videos/{}", video_id)),
                         _ => Err(PointercrateError::InvalidUrlFormat { expected: TWITCH_FORMAT }),
                     }
                 } else {
@@ -149,6 +157,36 @@ pub fn validate(url: &str) -> Result<String> {
     }
 }
 
+/// Computes the canonical key of the given video, which identifies it independently of the exact
+/// URL used to link to it
+///
+/// Keys are of the form `{host}:{video id}`, e.g. `youtube:dQw4w9WgXcQ`. Video URLs that are not
+/// (or no longer) accepted by [`validate`] are their own key.
+pub fn key(video: &str) -> String {
+    let canonical = match validate(video) {
+        Ok(canonical) => canonical,
+        Err(_) => return video.to_owned(),
+    };
+
+    // Canonical URLs are always well formed
+    let url = Url::parse(&canonical).unwrap();
+
+    let video_id = match url.domain() {
+        Some("www.youtube.com") =>
+            url.query_pairs()
+                .find_map(|(key, value)| if key == "v" { Some(value.into_owned()) } else { None }),
+        _ =>
+            url.path_segments()
+                .and_then(|mut segments| segments.next_back())
+                .map(ToOwned::to_owned),
+    };
+
+    match video_id {
+        Some(video_id) => format!("{}:{}", host(&canonical).to_lowercase(), video_id),
+        None => canonical,
+    }
+}
+
 pub fn embed(video: &str) -> Option<String> {
     // Video URLs need to be wellformed once we get here!
     let url = Url::parse(video).unwrap();
@@ -194,3 +232,99 @@ pub fn host(video: &str) -> &str {
         host => panic!("{}", host),
     }
 }
+
+/// The outcome of checking whether a video is (still) reachable
+#[derive(Debug)]
+pub enum ProbeResult {
+    /// The video host responded with a successful (2xx or 3xx) status code
+    Alive(u16),
+
+    /// The video host responded with an unsuccessful status code
+    Dead(u16),
+
+    /// The video host refused both our `HEAD` request and the `GET` request we retried with,
+    /// responding with the contained status code. Some hosts refuse requests that do not come from
+    /// a browser, so this does not mean that the video does not exist
+    Refused(u16),
+
+    /// No response could be obtained from the video host
+    Unreachable(String),
+}
+
+impl ProbeResult {
+    /// Whether this result definitively indicates that the video does not exist
+    ///
+    /// This is the case for client errors, except for those that are caused by us (timeouts and
+    /// ratelimits). Server errors and network failures might be temporary.
+    pub fn is_definitive_failure(&self) -> bool {
+        match self {
+            ProbeResult::Dead(status) => *status >= 400 && *status < 500 && *status != 408 && *status != 429,
+            _ => false,
+        }
+    }
+}
+
+/// Checks whether the given video is reachable
+///
+/// Tries a `HEAD` request first. Since some websites (billibilli) respond unfavorably to those, it
+/// falls back to a `GET` request in case of a 401, 403 or 405 response.
+pub async fn probe(http_client: &Client, video: &str) -> ProbeResult {
+    let mut response = http_client.head(video).send().await;
+    let mut retried = false;
+
+    if let Ok(ref head_response) = response {
+        let status = head_response.status().as_u16();
+
+        if status == 401 || status == 403 || status == 405 {
+            debug!("HEAD request to {} yielded {}, retrying with GET", video, status);
+
+            response = http_client.get(video).send().await;
+            retried = true;
+        }
+    }
+
+    match response {
+        Ok(response) => {
+            let status = response.status().as_u16();
+
+            if status >= 200 && status < 400 {
+                ProbeResult::Alive(status)
+            } else if retried {
+                ProbeResult::Refused(status)
+            } else {
+                ProbeResult::Dead(status)
+            }
+        },
+        Err(error) => ProbeResult::Unreachable(error.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{key, validate};
+
+    #[test]
+    fn test_key_ignores_url_variants() {
+        assert_eq!(key("https://www.youtube.com/watch?v=dQw4w9WgXcQ"), "youtube:dQw4w9WgXcQ");
+        assert_eq!(key("https://youtu.be/dQw4w9WgXcQ?t=42"), "youtube:dQw4w9WgXcQ");
+        assert_eq!(key("https://m.youtube.com/watch?t=42&v=dQw4w9WgXcQ"), "youtube:dQw4w9WgXcQ");
+
+        assert_eq!(key("https://www.twitch.tv/videos/123456789"), "twitch:123456789");
+        assert_eq!(key("https://twitch.tv/stadust/v/123456789?t=1h2m3s"), "twitch:123456789");
+        assert_eq!(key("https://m.twitch.tv/videos/123456789/"), "twitch:123456789");
+        assert_eq!(key("https://www.twitch.tv/stadust/video/123456789"), "twitch:123456789");
+    }
+
+    #[test]
+    fn test_twitch_video_path_requires_numeric_id() {
+        assert!(validate("https://www.twitch.tv/stadust/video/123456789").is_ok());
+        assert!(validate("https://www.twitch.tv/stadust/video/highlights").is_err());
+        assert!(validate("https://www.twitch.tv/stadust/video/").is_err());
+        assert!(validate("https://www.twitch.tv/stadust/clip/123456789").is_err());
+    }
+
+    #[test]
+    fn test_key_of_unsupported_video() {
+        assert_eq!(key("https://example.com/video.mp4"), "https://example.com/video.mp4");
+    }
+}
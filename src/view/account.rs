@@ -15,6 +15,7 @@ use std::{
     hash::{Hash, Hasher},
 };
 
+mod audit;
 mod demons;
 mod players;
 mod profile;
@@ -87,6 +88,7 @@ impl Page for AccountPage {
             "js/account/demon.js",
             "js/account/player.js",
             "js/account/submitter.js",
+            "js/account/audit.js",
             "js/staff.js",
         ]
     }
@@ -148,6 +150,15 @@ impl Page for AccountPage {
                             i class = "fa fa-eye fa-2x" aria-hidden="true" {}
                         }
                     }
+                    @if self.user.has_permission(Permissions::Administrator) || self.user.has_permission(Permissions::ListAdministrator) {
+                        div.tab.button.dark-grey.hover.no-shadow data-tab-id="7" {
+                            b {
+                                "Audit Log"
+                            }
+                            (PreEscaped("&nbsp;&nbsp;"))
+                            i class = "fa fa-history fa-2x" aria-hidden="true" {}
+                        }
+                    }
                 }
 
                 (profile::page(&self.user))
@@ -155,13 +166,16 @@ impl Page for AccountPage {
                     (users::page(self.user.has_permission(Permissions::Administrator)))
                 }
                 @if self.user.has_permission(Permissions::ListHelper) {
-                    (records::page(&self.demons))
+                    (records::page(&self.demons, &self.user))
                 }
                 @if self.user.has_permission(Permissions::ListModerator) {
-                    (players::page(&self.nations))
+                    (players::page(&self.nations, self.user.has_permission(Permissions::LeaderboardModerator)))
                     (demons::page())
                     (submitters::page())
                 }
+                @if self.user.has_permission(Permissions::Administrator) || self.user.has_permission(Permissions::ListAdministrator) {
+                    (audit::page())
+                }
             }
         }
     }
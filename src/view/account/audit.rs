@@ -0,0 +1,131 @@
+use crate::view::paginator;
+use maud::{html, Markup};
+
+pub(super) fn page() -> Markup {
+    html! {
+        div.m-center.flex.tab-content.container data-tab-id = "7" {
+            div.left {
+                div.panel.fade {
+                    h2.underlined.pad {
+                        "Audit Log"
+                    }
+                    div.flex.viewer {
+                        (paginator("audit-pagination", "/api/v1/audit/"))
+                        p.viewer-welcome {
+                            "Click on an entry on the left to see its details!"
+                        }
+                        div.viewer-content {
+                            div.flex.col {
+                                h3 style = "font-size:1.1em; margin: 10px 0" {
+                                    "Audit log entry #"
+                                    i#audit-entry-id {}
+                                }
+                                p {
+                                    "Every change made to pointercrate's database is logged here. For modifications, the values shown are the values "
+                                    b { "before" }
+                                    " the change. Fields that were not changed are shown as 'null'."
+                                }
+                                div.stats-container.flex.space {
+                                    span {
+                                        b {
+                                            "Time:"
+                                        }
+                                        br;
+                                        span#audit-entry-time {}
+                                    }
+                                    span {
+                                        b {
+                                            "Performed by:"
+                                        }
+                                        br;
+                                        span#audit-entry-user {}
+                                    }
+                                }
+                                div.stats-container.flex.space {
+                                    span {
+                                        b {
+                                            "Object:"
+                                        }
+                                        br;
+                                        span#audit-entry-object {}
+                                    }
+                                    span {
+                                        b {
+                                            "Action:"
+                                        }
+                                        br;
+                                        span#audit-entry-action {}
+                                    }
+                                }
+                                div.stats-container.flex.space {
+                                    span {
+                                        b {
+                                            "Data:"
+                                        }
+                                        br;
+                                        pre#audit-entry-data style = "white-space: pre-wrap; word-break: break-all" {}
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                div style="height: 50px" {} // to make sure that the footer doesnt float. if it floats, the user page is the only one without a scrollbar at the right, which causes jumpyness when switching tabs.
+            }
+            div.right {
+                (audit_filter())
+            }
+        }
+    }
+}
+
+fn audit_filter() -> Markup {
+    html! {
+        div.panel.fade {
+            h2.underlined.pad {
+                "Filter"
+            }
+            p {
+                "Only show entries matching all of the given criteria. Leave a field empty to not filter by it."
+            }
+            form.flex.col#audit-filter-form novalidate = "" {
+                p.info-red.output {}
+                span.form-input#audit-filter-user {
+                    label for = "user" {"Performed by (user ID):"}
+                    input type = "number" name = "user" min = "0" style="width:93%";
+                    p.error {}
+                }
+                span.form-input#audit-filter-object {
+                    label for = "object" {"Object type:"}
+                    select name = "object" style="width:100%" {
+                        option value = "" {"Any"}
+                        option value = "record" {"Record"}
+                        option value = "record_note" {"Record note"}
+                        option value = "demon" {"Demon"}
+                        option value = "creator" {"Creator (by demon ID)"}
+                        option value = "player" {"Player"}
+                        option value = "submitter" {"Submitter"}
+                        option value = "user" {"User"}
+                    }
+                    p.error {}
+                }
+                span.form-input#audit-filter-object-id {
+                    label for = "object_id" {"Object ID:"}
+                    input type = "number" name = "object_id" min = "0" style="width:93%";
+                    p.error {}
+                }
+                span.form-input#audit-filter-since {
+                    label for = "since" {"Since (UTC):"}
+                    input type = "datetime-local" name = "since" style="width:93%";
+                    p.error {}
+                }
+                span.form-input#audit-filter-until {
+                    label for = "until" {"Until (UTC):"}
+                    input type = "datetime-local" name = "until" style="width:93%";
+                    p.error {}
+                }
+                input.button.dark-grey.hover type = "submit" style = "margin: 15px auto 0px;" value="Apply filter";
+            }
+        }
+    }
+}
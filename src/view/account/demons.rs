@@ -79,6 +79,16 @@ pub(super) fn page() -> Markup {
 
                                     }
                                 }
+                                span.button.dark-grey.hover#demon-show-history style = "margin: 15px auto 0px" {"Show history"};
+                                div#demon-history style = "display: none; margin-top: 15px" {
+                                    h3 style = "font-size:1.1em; margin: 10px 0" {
+                                        "History"
+                                    }
+                                    p {
+                                        "All changes made to this demon, oldest first. Changes to the positions of other demons that caused this demon to move are included."
+                                    }
+                                    ul#demon-history-entries style = "text-align: left" {}
+                                }
                             }
                         }
                     }
@@ -1,7 +1,7 @@
 use crate::{model::nationality::Nationality, view::filtered_paginator};
 use maud::{html, Markup, PreEscaped};
 
-pub(super) fn page(nationalities: &[Nationality]) -> Markup {
+pub(super) fn page(nationalities: &[Nationality], show_claims: bool) -> Markup {
     html! {
         div.m-center.flex.tab-content.container data-tab-id = "4"{
             div.left {
@@ -68,6 +68,17 @@ pub(super) fn page(nationalities: &[Nationality]) -> Markup {
                                     }
                                 }
                                 span.button.dark-grey.hover#player-list-records style = "margin: 15px auto 0px" {"Show records in record manager"};
+                                @if show_claims {
+                                    div#player-claims style = "margin-top: 15px" {
+                                        h3 {
+                                            "Claims:"
+                                        }
+                                        p {
+                                            "Users who requested to be linked to this player. Once a claim is verified, the user can change the player's nationality and hide the videos of their records themselves. Only one claim per player can be verified."
+                                        }
+                                        div.flex.col#player-claims-list {}
+                                    }
+                                }
                             }
                         }
                     }
@@ -1,10 +1,14 @@
-use crate::view::{
-    demonlist::{self, OverviewDemon},
-    dropdown, paginator,
+use crate::{
+    config,
+    model::user::User,
+    view::{
+        demonlist::{self, OverviewDemon},
+        dropdown, paginator,
+    },
 };
 use maud::{html, Markup};
 
-fn record_manager(demons: &[OverviewDemon]) -> Markup {
+fn record_manager(demons: &[OverviewDemon], user: &User) -> Markup {
     html! {
         div.panel.fade#record-manager {
             h2.underlined.pad {
@@ -83,6 +87,16 @@ fn record_manager(demons: &[OverviewDemon]) -> Markup {
                                 span#record-submitter {}
                             }
                         }
+                        div.stats-container.flex.space {
+                            span {
+                                b {
+                                    "Reviewed by:"
+                                }
+                                br;
+                                span#record-assignee {}
+                            }
+                            span.button.dark-grey.hover#record-claim data-user-id = (user.id) style = "margin: auto 0px" {"Claim"}
+                        }
                         span.button.red.hover#record-delete style = "margin: 15px auto 0px" {"Delete Record"};
                     }
                 }
@@ -93,6 +107,11 @@ fn record_manager(demons: &[OverviewDemon]) -> Markup {
 }
 
 fn manager_help() -> Markup {
+    let timeout = match config::record_assignment_timeout() {
+        1 => "an hour".to_owned(),
+        hours => format!("{} hours", hours),
+    };
+
     html! {
         div.panel.fade {
             h1.underlined.pad {
@@ -122,6 +141,10 @@ fn manager_help() -> Markup {
                     }
                 }
             }
+            p {
+                b { "Note: " }
+                "To avoid multiple people reviewing the same record, claim records you are looking at via the 'Claim' button. Putting a record under consideration claims it automatically. Claims are released once the record is approved or rejected, or automatically after " (timeout) ". Records claimed by someone else can only be taken over by list moderators."
+            }
             p {
                 b { "Note: " }
                 "If a player is banned, they cannot have accepted/submitted records on the list. All records marked as 'submitted' are deleted, all others are changed to 'rejected'"
@@ -162,6 +185,14 @@ fn status_selector() -> Markup {
             (dropdown("All", html! {
                 li.dark-grey.hover.underlined data-value = "All" {"All"}
             }, dropdown_items.into_iter()))
+            p {
+                "Filter by reviewer"
+            }
+            (dropdown("Anyone", html! {
+                li.dark-grey.hover.underlined data-value = "Anyone" {"Anyone"}
+            }, std::iter::once(html! {
+                li.dark-grey.hover data-value = "Me" {"Claimed by me"}
+            })))
         }
     }
 }
@@ -228,16 +259,21 @@ fn note_adder() -> Markup {
             }
             p.info-red.output {}
             textarea style = "width: 100%" placeholder = "Add note here. Click 'Add' above when done!"{}
+            label.cb-container.form-input#add-record-note-public for = "is_public" {
+                i {"Show this note to the submitter"}
+                input type = "checkbox" name = "is_public";
+                span.checkmark {}
+            }
         }
     }
 }
 
-pub(super) fn page(demons: &[OverviewDemon]) -> Markup {
+pub(super) fn page(demons: &[OverviewDemon], user: &User) -> Markup {
     html! {
         div.m-center.flex.tab-content.container data-tab-id = "3" {
             div.left {
                 (crate::view::demonlist::submission_panel(demons, false))
-                (record_manager(demons))
+                (record_manager(demons, user))
                 (note_adder())
                 div.panel.fade#record-notes-container style = "display:none" {
                     div.hover.clickable#add-record-note-open {
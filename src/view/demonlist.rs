@@ -2,16 +2,19 @@ pub use self::{
     demon_page::{demon_permalink, page},
     overview::{index, overview_demons, OverviewDemon},
     statsviewer::stats_viewer as stats_viewer2,
+    submission::submission_status,
 };
 use crate::{
     config,
     model::{demonlist::demon::Demon, nationality::Nationality},
 };
+use chrono::{DateTime, FixedOffset};
 use maud::{html, Markup, PreEscaped, Render};
 
 mod demon_page;
 mod overview;
 mod statsviewer;
+mod submission;
 
 struct ListSection {
     name: &'static str,
@@ -256,9 +259,14 @@ pub(super) fn submission_panel(demons: &[OverviewDemon], visible: bool) -> Marku
     }
 }
 
-fn stats_viewer(nations: &[Nationality], visible: bool) -> Markup {
+fn stats_viewer(nations: &[Nationality], visible: bool, when: Option<DateTime<FixedOffset>>) -> Markup {
+    // The stats viewer passes this on to the API when set, see the `at` parameter of the ranking
+    let at = when
+        .map(|when| when.naive_utc().format("%Y-%m-%dT%H:%M:%S").to_string())
+        .unwrap_or_default();
+
     html! {
-        section.panel.fade.closable#statsviewer  style=(if !visible {"display:none"} else {""}) {
+        section.panel.fade.closable#statsviewer  style=(if !visible {"display:none"} else {""}) data-at = (at) {
             span.plus.cross.hover {}
             h2.underlined.pad {
                 "Stats Viewer - "
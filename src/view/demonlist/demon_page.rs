@@ -1,18 +1,21 @@
 use crate::{
     config,
     gd::GDIntegrationResult,
-    model::demonlist::demon::{FullDemon, MinimalDemon},
+    model::demonlist::{
+        demon::{FullDemon, MinimalDemon},
+        scoring_formula::ScoringFormula,
+    },
     state::PointercrateState,
     video,
     view::{
-        demonlist::overview::{DemonlistOverview, OverviewQueryData},
+        demonlist::overview::{time_machine_date, DemonlistOverview, OverviewQueryData},
         Page,
     },
     ViewResult,
 };
-use actix_web::{web::Path, HttpResponse};
+use actix_web::{web::Path, HttpRequest, HttpResponse};
 use actix_web_codegen::get;
-use chrono::NaiveDateTime;
+use chrono::{DateTime, FixedOffset, NaiveDateTime};
 use dash_rs::Thunk;
 use log::error;
 use maud::{html, Markup, PreEscaped, Render};
@@ -30,6 +33,8 @@ pub struct Demonlist {
     movements: Vec<DemonMovement>,
     link_banned: bool,
     integration: GDIntegrationResult,
+    when: Option<DateTime<FixedOffset>>,
+    scoring: ScoringFormula,
 }
 
 #[get("/demonlist/permalink/{id}/")]
@@ -43,10 +48,15 @@ pub async fn demon_permalink(state: PointercrateState, id: Path<i32>) -> ViewRes
 }
 
 #[get("/demonlist/{position}/")]
-pub async fn page(state: PointercrateState, position: Path<i16>) -> ViewResult<HttpResponse> {
+pub async fn page(request: HttpRequest, state: PointercrateState, position: Path<i16>) -> ViewResult<HttpResponse> {
     let mut connection = state.connection().await?;
     let overview = DemonlistOverview::load(&mut connection, None, OverviewQueryData::default()).await?;
-    let demon = FullDemon::by_position(position.into_inner(), &mut connection).await?;
+    let mut demon = FullDemon::by_position(position.into_inner(), &mut connection).await?;
+    let when = time_machine_date(&request);
+
+    if let Some(when) = when {
+        demon.load_records_at(when.naive_utc(), &mut connection).await?;
+    }
     let link_banned = sqlx::query!(
         r#"SELECT link_banned AS "link_banned!: bool" FROM players WHERE id = $1"#,
         demon.demon.verifier.id
@@ -81,6 +91,8 @@ pub async fn page(state: PointercrateState, position: Path<i16>) -> ViewResult<H
         None => error!("No addition logged for demon {}!", demon),
     }
 
+    let scoring = ScoringFormula::active(&mut connection).await?;
+
     let integration = state.gd_integration.data_for_demon(state.http_client.clone(), &demon.demon).await?;
 
     Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(
@@ -90,6 +102,8 @@ pub async fn page(state: PointercrateState, position: Path<i16>) -> ViewResult<H
             movements,
             link_banned,
             integration,
+            when,
+            scoring,
         }
         .render()
         .0,
@@ -101,8 +115,8 @@ impl Demonlist {
         let position = self.data.demon.base.position;
         let name = &self.data.demon.base.name;
 
-        let score100 = self.data.demon.score(100);
-        let score_requirement = self.data.demon.score(self.data.demon.requirement);
+        let score100 = self.data.demon.score(100, &self.scoring);
+        let score_requirement = self.data.demon.score(self.data.demon.requirement, &self.scoring);
 
         html! {
             section.panel.fade.js-scroll-anim data-anim = "fade" {
@@ -163,7 +177,7 @@ impl Demonlist {
                     }
                 }
                 div.underlined.pad.flex.wrap#level-info {
-                    @if position <= config::extended_list_size() {
+                    @if position <= self.scoring.scored_positions {
                         span {
                             b {
                                 "Demonlist score (100%): "
@@ -277,6 +291,12 @@ impl Demonlist {
                         h2 {
                             "Records"
                         }
+                        @if let Some(when) = self.when {
+                            p {
+                                "Showing records as of " (when.format("%B %e, %Y at %l:%M%P GMT%Z")) ". "
+                                a href = (format!("/demonlist/{}/", position)) onclick=r#"document.cookie = "when=""# { "Go to present" }
+                            }
+                        }
                         @if position <= config::list_size() {
                             h3 {
                                 (self.data.demon.requirement) "% or better required to qualify"
@@ -421,7 +441,7 @@ impl Page for Demonlist {
             div.flex.m-center.container {
                 main.left {
                     (super::submission_panel(&self.overview.demon_overview, false))
-                    (super::stats_viewer(&self.overview.nations, false))
+                    (super::stats_viewer(&self.overview.nations, false, self.when))
                     (self.demon_panel())
                     (self.level_info_panel())
                     div.panel.fade.js-scroll-anim.js-collapse data-anim = "fade" {
@@ -40,7 +40,7 @@ pub async fn overview_demons(connection: &mut PgConnection, at: Option<DateTime<
     match at {
         None => Ok(sqlx::query_as!(
                 OverviewDemon,
-                r#"SELECT demons.id, position, demons.name as "name: String", CASE WHEN verifiers.link_banned THEN NULL ELSE video::TEXT END, 
+                r#"SELECT demons.id, position, demons.name as "name: String", CASE WHEN verifiers.link_banned OR verifiers.hide_videos THEN NULL ELSE video::TEXT END, 
                  players.name as "publisher: String", null::smallint as current_position FROM demons INNER JOIN players ON demons.publisher = players.id INNER JOIN players AS verifiers 
                  ON demons.verifier = verifiers.id WHERE position IS NOT NULL ORDER BY position"#
             )
@@ -48,7 +48,7 @@ pub async fn overview_demons(connection: &mut PgConnection, at: Option<DateTime<
             .await?),
         Some(time) => Ok(sqlx::query_as!(
                 OverviewDemon,
-                r#"SELECT demons.id as "id!", position_ as "position!", demons.name as "name!: String", CASE WHEN verifiers.link_banned THEN NULL ELSE video::TEXT END, 
+                r#"SELECT demons.id as "id!", position_ as "position!", demons.name as "name!: String", CASE WHEN verifiers.link_banned OR verifiers.hide_videos THEN NULL ELSE video::TEXT END, 
                  players.name as "publisher: String", current_position FROM list_at($1) AS demons INNER JOIN players ON demons.publisher = players.id INNER JOIN players AS verifiers 
                  ON demons.verifier = verifiers.id ORDER BY position_"#, time.naive_utc()
             )
@@ -144,18 +144,16 @@ pub struct OverviewQueryData {
     record_submitter_shown: bool,
 }
 
-#[get("/demonlist/")]
-pub async fn index(request: HttpRequest, state: PointercrateState, query_data: Query<OverviewQueryData>) -> ViewResult<HttpResponse> {
+/// Gets the point in time selected in the time machine (stored in the `when` cookie), if any
+pub(super) fn time_machine_date(request: &HttpRequest) -> Option<DateTime<FixedOffset>> {
     /* static */
     let EARLIEST_DATE: DateTime<FixedOffset> = FixedOffset::east(0).from_utc_datetime(&NaiveDate::from_ymd(2019, 4, 19).and_hms(0, 0, 0));
 
-    let mut connection = state.connection().await?;
-
     let specified_when = request
         .cookie("when")
         .map(|cookie| DateTime::<FixedOffset>::parse_from_rfc3339(cookie.value()));
 
-    let when = if let Some(when) = specified_when {
+    if let Some(when) = specified_when {
         match when {
             Ok(when) if when < EARLIEST_DATE => Some(EARLIEST_DATE),
             Ok(when) if when >= Utc::now() => None,
@@ -164,7 +162,14 @@ pub async fn index(request: HttpRequest, state: PointercrateState, query_data: Q
         }
     } else {
         None
-    };
+    }
+}
+
+#[get("/demonlist/")]
+pub async fn index(request: HttpRequest, state: PointercrateState, query_data: Query<OverviewQueryData>) -> ViewResult<HttpResponse> {
+    let mut connection = state.connection().await?;
+
+    let when = time_machine_date(&request);
 
     Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(
         DemonlistOverview::load(&mut connection, when, query_data.into_inner())
@@ -201,7 +206,7 @@ impl Page for DemonlistOverview {
                 main.left {
                     (time_machine(self.query_data.time_machine_shown))
                     (super::submission_panel(&self.demon_overview, self.query_data.record_submitter_shown))
-                    (super::stats_viewer(&self.nations, self.query_data.stats_viewer_shown))
+                    (super::stats_viewer(&self.nations, self.query_data.stats_viewer_shown, self.when))
                     @if let Some(when) = self.when {
                         div.panel.fade.dark-grey.flex style="align-items: center;" {
                              span style = "text-align: end"{
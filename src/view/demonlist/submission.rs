@@ -0,0 +1,119 @@
+use crate::{
+    model::demonlist::record::{RecordStatus, SubmissionStatus},
+    state::PointercrateState,
+    view::Page,
+    ViewResult,
+};
+use actix_web::{web::Path, HttpResponse};
+use actix_web_codegen::get;
+use maud::{html, Markup};
+
+#[derive(Debug)]
+struct SubmissionStatusPage {
+    submission: SubmissionStatus,
+}
+
+#[get("/demonlist/submission/{token}/")]
+pub async fn submission_status(state: PointercrateState, token: Path<String>) -> ViewResult<HttpResponse> {
+    let mut connection = state.connection().await?;
+    let submission = SubmissionStatus::by_token(&token.into_inner(), &mut connection).await?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(SubmissionStatusPage { submission }.render().0))
+}
+
+impl SubmissionStatusPage {
+    fn explanation(&self) -> &'static str {
+        match self.submission.status {
+            RecordStatus::Submitted => "Your submission has not yet been reviewed by the list team. Please be patient!",
+            RecordStatus::UnderConsideration =>
+                "Your submission is being looked at more closely by the list team. This can take a while, as sometimes further evidence \
+                 needs to be gathered.",
+            RecordStatus::Approved => "Your submission has been approved and is now displayed on the demonlist. Congratulations!",
+            RecordStatus::Rejected => "Your submission has been rejected by the list team.",
+        }
+    }
+}
+
+impl Page for SubmissionStatusPage {
+    fn title(&self) -> String {
+        format!("Submission {} - Geometry Dash Demonlist", self.submission.id)
+    }
+
+    fn description(&self) -> String {
+        "The status of a record submitted to the Geometry Dash Demonlist".to_owned()
+    }
+
+    fn scripts(&self) -> Vec<&str> {
+        vec![]
+    }
+
+    fn stylesheets(&self) -> Vec<&str> {
+        vec!["css/demonlist.v2.1.css", "css/sidebar.css"]
+    }
+
+    fn body(&self) -> Markup {
+        let submission = &self.submission;
+
+        html! {
+            div.flex.m-center.container {
+                main.left {
+                    section.panel.fade {
+                        h1.underlined.pad {
+                            "Submission " (submission.id)
+                        }
+                        p {
+                            (submission.player.name) " - " (submission.progress) "% on "
+                            a.link href = {"/demonlist/permalink/" (submission.demon.id) "/"} {
+                                (submission.demon.name)
+                            }
+                        }
+                        @if let Some(ref video) = submission.video {
+                            p {
+                                "Video: "
+                                a.link href = (video) target = "_blank" {
+                                    (video)
+                                }
+                            }
+                        }
+                        h2 {
+                            "Status: " (submission.status)
+                        }
+                        p {
+                            (self.explanation())
+                        }
+                        @if let Some(ref reason) = submission.rejection_reason {
+                            h3 {
+                                "Reason: " (reason.name)
+                            }
+                            @if let Some(ref description) = reason.description {
+                                p {
+                                    (description)
+                                }
+                            }
+                        }
+                        @if !submission.notes.is_empty() {
+                            h3 {
+                                "Notes:"
+                            }
+                            @for note in &submission.notes {
+                                p.info-yellow {
+                                    (note)
+                                }
+                            }
+                        }
+                    }
+                }
+                aside.right {
+                    (super::rules_panel())
+                    (super::discord_panel())
+                }
+            }
+        }
+    }
+
+    fn head(&self) -> Vec<Markup> {
+        vec![]
+    }
+}
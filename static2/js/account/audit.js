@@ -0,0 +1,127 @@
+import { Paginator, Viewer } from "../modules/form.mjs";
+
+export let auditLogViewer;
+
+function generateEntry(entry) {
+  var li = document.createElement("li");
+  var b = document.createElement("b");
+  var i = document.createElement("i");
+
+  li.className = "dark-grey";
+  li.dataset.id = entry.audit_id;
+
+  switch (entry.action) {
+    case "addition":
+      li.style.backgroundColor = "rgba(161, 255, 101, .2)";
+      break;
+    case "deletion":
+      li.style.backgroundColor = "rgba(255, 33, 64, .2)";
+      break;
+  }
+
+  b.innerText = entry.object.replace("_", " ") + " #" + entry.object_id;
+  i.innerText = entry.action.replace("_", " ") + " by " + describeUser(entry.user);
+
+  li.appendChild(b);
+  li.appendChild(document.createElement("br"));
+  li.appendChild(i);
+
+  return li;
+}
+
+function describeUser(user) {
+  if (user.id === 0) {
+    return "pointercrate";
+  }
+
+  if (user.name === null) {
+    return "deleted user #" + user.id;
+  }
+
+  return user.name + " (#" + user.id + ")";
+}
+
+class AuditLogViewer extends Paginator {
+  constructor() {
+    super("audit-pagination", {}, generateEntry);
+
+    this.output = new Viewer(
+      this.html.parentNode.getElementsByClassName("viewer-content")[0],
+      this
+    );
+
+    // The entries on the current page, by audit ID. There is no endpoint for retrieving single
+    // entries, so the details are displayed from the pagination response directly.
+    this._entries = {};
+
+    this._id = document.getElementById("audit-entry-id");
+    this._time = document.getElementById("audit-entry-time");
+    this._user = document.getElementById("audit-entry-user");
+    this._object = document.getElementById("audit-entry-object");
+    this._action = document.getElementById("audit-entry-action");
+    this._data = document.getElementById("audit-entry-data");
+  }
+
+  handleResponse(response) {
+    this._entries = {};
+
+    for (let entry of response.data) {
+      this._entries[entry.audit_id] = entry;
+    }
+
+    super.handleResponse(response);
+  }
+
+  onSelect(selected) {
+    this.currentlySelected = selected;
+    this.currentObject = this._entries[selected.dataset.id];
+
+    for (let listener of this.selectionListeners) {
+      listener(this.currentObject);
+    }
+
+    this._id.innerText = this.currentObject.audit_id;
+    this._time.innerText = this.currentObject.time;
+    this._user.innerText = describeUser(this.currentObject.user);
+    this._object.innerText =
+      this.currentObject.object + " #" + this.currentObject.object_id;
+    this._action.innerText = this.currentObject.action;
+    this._data.innerText =
+      this.currentObject.data === null
+        ? "-"
+        : JSON.stringify(this.currentObject.data, null, 2);
+  }
+}
+
+function setupAuditFilterForm() {
+  let form = document.getElementById("audit-filter-form");
+
+  form.addEventListener("submit", (event) => {
+    event.preventDefault();
+
+    let queryData = {};
+
+    for (let element of form.querySelectorAll("input[name], select[name]")) {
+      if (element.value === "") {
+        continue;
+      }
+
+      // datetime-local inputs omit the seconds, which the API requires
+      if (element.type === "datetime-local" && element.value.length === 16) {
+        queryData[element.name] = element.value + ":00";
+      } else {
+        queryData[element.name] = element.value;
+      }
+    }
+
+    auditLogViewer.output.hideContent();
+    auditLogViewer.setQueryData(queryData);
+  });
+}
+
+export function initialize() {
+  auditLogViewer = new AuditLogViewer();
+  auditLogViewer.initialize();
+
+  setupAuditFilterForm();
+}
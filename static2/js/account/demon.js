@@ -15,6 +15,7 @@ import {
   valueMissing,
   typeMismatch,
   del,
+  get,
   displayError,
   Form,
   post, setupEditorDialog, FormDialog,
@@ -49,6 +50,13 @@ export class DemonManager extends FilteredPaginator {
 
     this._creators = document.getElementById("demon-creators");
 
+    this._history = document.getElementById("demon-history");
+    this._historyEntries = document.getElementById("demon-history-entries");
+
+    document
+      .getElementById("demon-show-history")
+      .addEventListener("click", () => this.showHistory());
+
     let videoForm = setupFormDialogEditor(
       new PaginatorEditorBackend(this, csrfToken, false),
       "demon-video-dialog",
@@ -168,6 +176,24 @@ export class DemonManager extends FilteredPaginator {
     for (let creator of this.currentObject.creators) {
       this.addCreator(creator);
     }
+
+    this._history.style.display = "none";
+  }
+
+  showHistory() {
+    get("/api/v2/demons/" + this.currentObject.id + "/audit/")
+      .then((response) => {
+        while (this._historyEntries.lastChild) {
+          this._historyEntries.removeChild(this._historyEntries.lastChild);
+        }
+
+        for (let item of generateHistory(response.data, this.currentObject)) {
+          this._historyEntries.appendChild(item);
+        }
+
+        this._history.style.display = "block";
+      })
+      .catch(displayError(this.output));
   }
 
   addCreator(creator) {
@@ -192,6 +218,94 @@ export class DemonManager extends FilteredPaginator {
   }
 }
 
+const HISTORY_FIELDS = [
+  "name",
+  "position",
+  "requirement",
+  "video",
+  "verifier",
+  "publisher",
+];
+
+function describeValue(value) {
+  if (value === null || value === undefined) {
+    return "-";
+  }
+
+  if (typeof value === "object") {
+    return value.name + " (" + value.id + ")";
+  }
+
+  return value.toString();
+}
+
+/**
+ * Generates the list items showing the given audit log entries of the given demon
+ *
+ * The audit log only contains the old values of changed fields. The new values are reconstructed by
+ * walking the log backwards, starting at the demon's current state.
+ */
+function generateHistory(entries, demon) {
+  let state = {};
+
+  for (let field of HISTORY_FIELDS) {
+    state[field] = demon[field];
+  }
+
+  let items = [];
+
+  for (let entry of entries.slice().reverse()) {
+    let li = document.createElement("li");
+    let b = document.createElement("b");
+
+    b.innerText =
+      entry.time.replace("T", " ").substring(0, 19) +
+      " by " +
+      (entry.user.id === 0 ? "pointercrate" : describeValue(entry.user)) +
+      ": ";
+
+    li.appendChild(b);
+
+    if (entry.type === "Addition") {
+      li.appendChild(document.createTextNode("Demon added"));
+    } else if (entry.type.CreatorAddition) {
+      li.appendChild(
+        document.createTextNode(
+          "Added creator " + describeValue(entry.type.CreatorAddition)
+        )
+      );
+    } else if (entry.type.CreatorDeletion) {
+      li.appendChild(
+        document.createTextNode(
+          "Removed creator " + describeValue(entry.type.CreatorDeletion)
+        )
+      );
+    } else {
+      let data = entry.type.Modification;
+      let changes = [];
+
+      for (let field of HISTORY_FIELDS) {
+        if (data[field] !== null) {
+          changes.push(
+            field +
+              ": " +
+              describeValue(data[field]) +
+              " \u2192 " +
+              describeValue(state[field])
+          );
+          state[field] = data[field];
+        }
+      }
+
+      li.appendChild(document.createTextNode(changes.join(", ")));
+    }
+
+    items.unshift(li);
+  }
+
+  return items;
+}
+
 function insertCreatorInto(creator, container) {
   let html = createCreatorHtml(creator);
   if (container.children.length == 0) {
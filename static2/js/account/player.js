@@ -1,7 +1,10 @@
 import { generatePlayer } from "../modules/demonlist.mjs";
 import {
+  del,
   displayError,
   Form,
+  get,
+  post,
   valueMissing,
   FilteredPaginator,
   setupFormDialogEditor,
@@ -41,6 +44,9 @@ class PlayerManager extends FilteredPaginator {
       { None: null }
     );
 
+    this._claims = document.getElementById("player-claims-list");
+    this._csrf = csrfToken;
+
     this.initNameDialog(csrfToken);
   }
 
@@ -63,6 +69,88 @@ class PlayerManager extends FilteredPaginator {
     } else {
       this._nationality.selectSilently("None");
     }
+
+    // only rendered for leaderboard moderators
+    if (this._claims) {
+      this.loadClaims();
+    }
+  }
+
+  loadClaims() {
+    let playerId = this.currentObject.id;
+
+    get("/api/v1/players/" + playerId + "/claims/")
+      .then((response) => {
+        // a different player might have been selected in the meantime
+        if (this.currentObject.id !== playerId) return;
+
+        while (this._claims.lastChild)
+          this._claims.removeChild(this._claims.lastChild);
+
+        if (response.data.length === 0) {
+          let i = document.createElement("i");
+          i.innerText = "Nobody has claimed this player";
+          this._claims.appendChild(i);
+        }
+
+        for (let claim of response.data) {
+          this._claims.appendChild(this.createClaimHtml(claim));
+        }
+      })
+      .catch(displayError(this.output));
+  }
+
+  createClaimHtml(claim) {
+    let claimDiv = document.createElement("div");
+    let url =
+      "/api/v1/players/" + claim.player.id + "/claims/" + claim.user.id + "/";
+
+    claimDiv.classList.add("hover");
+
+    let closeX = document.createElement("span");
+    closeX.classList.add("hover", "plus", "cross");
+    closeX.style.transform = "scale(0.75)";
+    closeX.addEventListener("click", () => {
+      if (
+        confirm(
+          "This will delete the claim of " + claim.user.name + ". Proceed?"
+        )
+      ) {
+        del(url, { "X-CSRF-TOKEN": this._csrf })
+          .then(() => this.loadClaims())
+          .catch(displayError(this.output));
+      }
+    });
+
+    let b = document.createElement("b");
+    b.innerText = claim.user.name + " (ID: " + claim.user.id + ")";
+
+    let info = document.createElement("i");
+    info.style.fontSize = "80%";
+    info.innerText =
+      "Requested " + claim.requested_at.replace("T", " ").split(".")[0];
+
+    claimDiv.appendChild(closeX);
+    claimDiv.appendChild(b);
+    claimDiv.appendChild(document.createElement("br"));
+    claimDiv.appendChild(info);
+
+    if (claim.verified) {
+      info.innerText += " - verified";
+    } else {
+      let verify = document.createElement("span");
+      verify.classList.add("button", "dark-grey", "hover");
+      verify.style.margin = "5px auto";
+      verify.innerText = "Verify";
+      verify.addEventListener("click", () => {
+        post(url + "verify/", { "X-CSRF-TOKEN": this._csrf })
+          .then(() => this.loadClaims())
+          .catch(displayError(this.output));
+      });
+      claimDiv.appendChild(verify);
+    }
+
+    return claimDiv;
   }
 
   initNameDialog(csrfToken) {
@@ -44,6 +44,9 @@ class RecordManager extends Paginator {
     this._holder = document.getElementById("record-holder");
     this._progress = document.getElementById("record-progress");
     this._submitter = document.getElementById("record-submitter");
+    this._assignee = document.getElementById("record-assignee");
+    this._claim = document.getElementById("record-claim");
+    this._userId = parseInt(this._claim.dataset.userId);
     this._notes = document.getElementById("record-notes");
     this._tok = tok; // FIXME: bad
 
@@ -64,6 +67,15 @@ class RecordManager extends Paginator {
       else this.updateQueryData("status", selected);
     });
 
+    new Dropdown(
+      document
+        .getElementById("status-filter-panel")
+        .getElementsByClassName("dropdown-menu")[1]
+    ).addEventListener((selected) => {
+      if (selected === "Anyone") this.updateQueryData("reviewer", undefined);
+      else this.updateQueryData("reviewer", this._userId);
+    });
+
     this._status = setupDropdownEditor(
       new PaginatorEditorBackend(this, this._tok, true),
       "edit-record-status",
@@ -76,6 +88,7 @@ class RecordManager extends Paginator {
 
     setupEditorDialog(new PlayerSelectionDialog("record-holder-dialog"), "record-holder-pen", new PaginatorEditorBackend(this, this._tok, true), this.output);
     this.initDemonDialog();
+    this.initClaimButton();
 
     document.getElementById("record-copy-info").addEventListener('click', () => {
       navigator.clipboard.writeText(this.currentObject.id + ", " + this._holder.innerText + ", " + this.currentObject.video)
@@ -134,6 +147,23 @@ class RecordManager extends Paginator {
     );
   }
 
+  initClaimButton() {
+    let backend = new PaginatorEditorBackend(this, this._tok, false);
+
+    this._claim.addEventListener("click", () => {
+      let assignee = this.currentObject.assignee;
+      let claimedByMe = assignee !== null && assignee.id === this._userId;
+
+      backend
+        .edit({ assignee: claimedByMe ? null : this._userId })
+        .then((unmodified) => {
+          if (!unmodified)
+            this.output.setSuccess(claimedByMe ? "Claim released!" : "Record claimed!");
+        })
+        .catch((response) => displayError(this.output)(response));
+    });
+  }
+
   onReceive(response) {
     super.onReceive(response);
 
@@ -170,6 +200,17 @@ class RecordManager extends Paginator {
     this._progress.innerHTML = this.currentObject.progress + "%";
     this._submitter.innerHTML = this.currentObject.submitter.id;
 
+    let assignee = this.currentObject.assignee;
+
+    if (assignee === null) {
+      this._assignee.innerText = "Nobody";
+      this._claim.innerText = "Claim";
+    } else {
+      this._assignee.innerText = assignee.name + " (" + assignee.id + ")";
+      this._claim.innerText =
+        assignee.id === this._userId ? "Release" : "Take over";
+    }
+
     // clear notes
     while (this._notes.firstChild) {
       this._notes.removeChild(this._notes.firstChild);
@@ -238,6 +279,10 @@ function createNoteHtml(note, csrfToken) {
       ". ";
   }
 
+  if (note.is_public) {
+    furtherInfo.innerHTML += "This note is visible to the submitter. ";
+  }
+
   if (note.transferred) {
     furtherInfo.innerHTML += "This not was not originally left on this record.";
   }
@@ -254,13 +299,14 @@ function setupAddNote(csrfToken) {
   let adder = document.getElementById("add-record-note");
   let output = new Output(adder);
   let textArea = adder.getElementsByTagName("textarea")[0];
+  let isPublic = adder.getElementsByTagName("input")[0];
   let add = adder.getElementsByClassName("button")[0];
 
   add.addEventListener("click", () => {
     post(
       "/api/v1/records/" + recordManager.currentObject.id + "/notes/",
       { "X-CSRF-TOKEN": csrfToken },
-      { content: textArea.value }
+      { content: textArea.value, is_public: isPublic.checked }
     )
       .then((noteResponse) => {
         let newNote = createNoteHtml(noteResponse.data.data, csrfToken);
@@ -268,6 +314,7 @@ function setupAddNote(csrfToken) {
 
         $(adder).hide(100);
         textArea.value = "";
+        isPublic.checked = false;
       })
       .catch(displayError(output));
   });
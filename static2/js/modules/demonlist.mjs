@@ -122,8 +122,12 @@ export function initializeRecordSubmitter(csrf = null, submitApproved = false) {
       headers["X-CSRF-TOKEN"] = csrf;
     }
     post("/api/v1/records/", headers, data)
-      .then(() => {
-        submissionForm.setSuccess("Record successfully submitted");
+      .then((response) => {
+        submissionForm.setSuccess(
+          "Record successfully submitted! You can check on its status <a class='link' href='/demonlist/submission/" +
+            response.data.lookup_token +
+            "/'>here</a>. Make sure to save this link, as it will not be shown again."
+        );
         submissionForm.clear();
       })
       .catch((response) =>  {
@@ -138,6 +142,7 @@ export function initializeRecordSubmitter(csrf = null, submitApproved = false) {
           case 42220:
             progress.errorText = response.data.message;
             break;
+          case 40906:
           case 42222:
           case 42223:
           case 42224:
@@ -161,7 +166,9 @@ export class StatsViewer extends FilteredPaginator {
     super(
       "stats-viewer-pagination",
       generateStatsViewerPlayer,
-      "name_contains"
+      "name_contains",
+      // If the time machine is active, show the ranking as it was at that point in time
+      html.dataset.at ? { at: html.dataset.at } : {}
     );
 
     // different from pagination endpoint here!
@@ -212,6 +219,16 @@ export class StatsViewer extends FilteredPaginator {
     });
   }
 
+  selectArbitrary(id) {
+    if (this.queryData.at === undefined) {
+      return super.selectArbitrary(id);
+    }
+
+    return get(
+      this.retrievalEndpoint + id + "/?at=" + encodeURIComponent(this.queryData.at)
+    ).then(this.onReceive.bind(this));
+  }
+
   onReceive(response) {
     super.onReceive(response);
 
@@ -239,6 +256,17 @@ export class StatsViewer extends FilteredPaginator {
 
       this._name.textContent = playerData.name + " ";
       this._name.appendChild(span);
+
+      if (playerData.nationality.subdivision) {
+        let small = document.createElement("small");
+        small.textContent = " " + playerData.nationality.subdivision.name;
+        small.title =
+          playerData.nationality.country_code +
+          "-" +
+          playerData.nationality.subdivision.code;
+
+        this._name.appendChild(small);
+      }
     }
 
     this.formatDemonsInto(this._created, playerData.created);
@@ -1,4 +1,5 @@
 import {StatsViewer} from "./modules/demonlist.mjs";
+import {get} from "./modules/form.mjs";
 
 $(window).on("load", function () {
     let worldMapWrapper = document.getElementById("world-map-wrapper");
@@ -8,6 +9,8 @@ $(window).on("load", function () {
     window.statsViewer = new StatsViewer(document.getElementById("statsviewer"));
     window.statsViewer.initialize();
 
+    colorHeatmap(svg);
+
     document.addEventListener('scroll', () => {
         let scrollRatio = window.scrollY / worldMapWrapper.clientHeight;
 
@@ -108,4 +111,22 @@ $(window).on("load", function () {
             }
         })
     }
-});
\ No newline at end of file
+});
+
+function colorHeatmap(svg) {
+    get("/api/v1/nationalities/heatmap/?low_level=10&high_level=60&metric=players").then(response => {
+        for (let nation of response.data) {
+            let land = svg.getElementById(nation.country_code.toLowerCase());
+
+            if (land === null)
+                continue;
+
+            // heat map by gradient from dadce0 to 0881c6
+            let fill = "rgb(" + Math.round(0xda + (0x08 - 0xda) * nation.level / 100) + ", " + Math.round(0xdc + (0x81 - 0xdc) * nation.level / 100) + ", " + Math.round(0xe0 + (0xc6 - 0xe0) * nation.level / 100) + ")";
+
+            for (let path of land.getElementsByTagName("path")) {
+                path.style.fill = fill;
+            }
+        }
+    });
+}